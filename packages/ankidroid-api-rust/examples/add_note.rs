@@ -1,11 +1,11 @@
 //! Complete example showing how to add notes to AnkiDroid
-//! 
+//!
 //! This example demonstrates:
 //! - Checking AnkiDroid availability
 //! - Creating a custom model if needed
 //! - Adding a single note with fields and tags
 //! - Error handling best practices
-//! 
+//!
 //! Note: This example only works on Android targets where AnkiDroid is installed
 
 #[cfg(target_os = "android")]
@@ -15,26 +15,25 @@ use ankidroid_api_rust::{AnkiDroidApi, AnkiDroidError, Result, DEFAULT_DECK_ID};
 use android_logger;
 
 #[cfg(target_os = "android")]
-use log::{info, warn, error, debug};
+use log::{debug, error, info, warn};
 
 /// Complete workflow for adding a note to AnkiDroid
 #[cfg(target_os = "android")]
 fn main() -> Result<()> {
     // Initialize Android logging
     android_logger::init_once(
-        android_logger::Config::default()
-            .with_max_level(log::LevelFilter::Debug)
+        android_logger::Config::default().with_max_level(log::LevelFilter::Debug),
     );
-    
+
     info!("Starting AnkiDroid note addition example");
-    
+
     // Get the JNI JavaVM - in a real Android app, this would be provided
     // by the Android runtime or through JNI native method parameters
     let vm = get_java_vm()?;
-    
+
     // Initialize the AnkiDroid API
     let api = AnkiDroidApi::new(vm);
-    
+
     // Step 1: Check if AnkiDroid is available
     info!("Checking AnkiDroid availability...");
     if !api.is_available()? {
@@ -44,20 +43,20 @@ fn main() -> Result<()> {
         ));
     }
     info!("AnkiDroid is available!");
-    
+
     // Step 2: Get or create a suitable model
     let model_id = ensure_model_exists(&api)?;
     info!("Using model ID: {}", model_id);
-    
+
     // Step 3: Create and add a single note
     add_sample_note(&api, model_id)?;
-    
+
     // Step 4: Demonstrate bulk note addition
     bulk_add_sample_notes(&api, model_id)?;
-    
+
     // Step 5: Demonstrate note querying and updating
     demonstrate_note_operations(&api)?;
-    
+
     info!("Example completed successfully!");
     Ok(())
 }
@@ -69,26 +68,30 @@ fn ensure_model_exists(api: &AnkiDroidApi) -> Result<i64> {
     match api.current_model_id() {
         Ok(model_id) => {
             info!("Using existing current model: {}", model_id);
-            
+
             // Verify the model has at least 2 fields (front/back)
             let fields = api.get_field_list(model_id)?;
             if fields.len() >= 2 {
                 info!("Model has {} fields: {:?}", fields.len(), fields);
                 return Ok(model_id);
             } else {
-                warn!("Current model only has {} fields, creating a new one", fields.len());
+                warn!(
+                    "Current model only has {} fields, creating a new one",
+                    fields.len()
+                );
             }
         }
         Err(e) => {
             warn!("Could not get current model: {}", e);
         }
     }
-    
+
     // If we don't have a suitable model, create one
     info!("Creating a new basic model...");
-    let model_id = api.add_new_basic_model("Rust API Example Cards")?
+    let model_id = api
+        .add_new_basic_model("Rust API Example Cards")?
         .ok_or_else(|| AnkiDroidError::operation_failed("Failed to create basic model"))?;
-    
+
     info!("Created new model with ID: {}", model_id);
     Ok(model_id)
 }
@@ -97,7 +100,7 @@ fn ensure_model_exists(api: &AnkiDroidApi) -> Result<i64> {
 #[cfg(target_os = "android")]
 fn add_sample_note(api: &AnkiDroidApi, model_id: i64) -> Result<()> {
     info!("Creating a sample note...");
-    
+
     // Create a note using the builder pattern
     let note = Note::builder(model_id)
         .field("What is the capital of France?".to_string())
@@ -106,14 +109,18 @@ fn add_sample_note(api: &AnkiDroidApi, model_id: i64) -> Result<()> {
         .tag("capitals".to_string())
         .tag("europe".to_string())
         .build();
-    
-    info!("Adding note with {} fields and {} tags", note.fields.len(), note.tags.len());
+
+    info!(
+        "Adding note with {} fields and {} tags",
+        note.fields.len(),
+        note.tags.len()
+    );
     debug!("Note fields: {:?}", note.fields);
     debug!("Note tags: {:?}", note.tags);
-    
+
     // Add the note to the default deck
     let note_id = api.add_note(&note)?;
-    
+
     info!("Successfully added note with ID: {}", note_id);
     Ok(())
 }
@@ -122,7 +129,7 @@ fn add_sample_note(api: &AnkiDroidApi, model_id: i64) -> Result<()> {
 #[cfg(target_os = "android")]
 fn bulk_add_sample_notes(api: &AnkiDroidApi, model_id: i64) -> Result<()> {
     info!("Creating multiple notes for bulk addition...");
-    
+
     let notes = vec![
         Note::builder(model_id)
             .field("What is the capital of Germany?".to_string())
@@ -131,7 +138,6 @@ fn bulk_add_sample_notes(api: &AnkiDroidApi, model_id: i64) -> Result<()> {
             .tag("capitals".to_string())
             .tag("europe".to_string())
             .build(),
-            
         Note::builder(model_id)
             .field("What is the capital of Japan?".to_string())
             .field("Tokyo".to_string())
@@ -139,7 +145,6 @@ fn bulk_add_sample_notes(api: &AnkiDroidApi, model_id: i64) -> Result<()> {
             .tag("capitals".to_string())
             .tag("asia".to_string())
             .build(),
-            
         Note::builder(model_id)
             .field("What is the capital of Australia?".to_string())
             .field("Canberra".to_string())
@@ -148,16 +153,20 @@ fn bulk_add_sample_notes(api: &AnkiDroidApi, model_id: i64) -> Result<()> {
             .tag("oceania".to_string())
             .build(),
     ];
-    
+
     info!("Adding {} notes in bulk...", notes.len());
     let added_count = api.add_notes(DEFAULT_DECK_ID, &notes)?;
-    
-    info!("Successfully added {} out of {} notes", added_count, notes.len());
-    
+
+    info!(
+        "Successfully added {} out of {} notes",
+        added_count,
+        notes.len()
+    );
+
     if added_count != notes.len() {
         warn!("Not all notes were added successfully. Check for duplicates or validation errors.");
     }
-    
+
     Ok(())
 }
 
@@ -165,19 +174,19 @@ fn bulk_add_sample_notes(api: &AnkiDroidApi, model_id: i64) -> Result<()> {
 #[cfg(target_os = "android")]
 fn demonstrate_note_operations(api: &AnkiDroidApi) -> Result<()> {
     info!("Demonstrating note query and update operations...");
-    
+
     // For this example, we'll assume we have note IDs from previous operations
     // In a real application, you would get these from the add_note results
     // or by querying the database
-    
+
     // This is a simplified example - in practice you'd query for existing notes
     info!("Note operations demonstration completed (simplified)");
-    
+
     Ok(())
 }
 
 /// Get the JNI JavaVM instance
-/// 
+///
 /// In a real Android application, this would typically be:
 /// 1. Passed as a parameter to a JNI native method
 /// 2. Obtained through the android runtime
@@ -188,7 +197,7 @@ fn get_java_vm() -> Result<jni::JavaVM> {
     // - JNI native method parameters
     // - Global storage set during JNI_OnLoad
     // - Android NDK context
-    
+
     // For the example, we'll return an error with instructions
     Err(AnkiDroidError::system_error(
         "JavaVM not available in this context. In a real Android app, obtain the JavaVM from JNI method parameters or global storage."
@@ -199,7 +208,7 @@ fn get_java_vm() -> Result<jni::JavaVM> {
 #[cfg(target_os = "android")]
 fn demonstrate_error_handling() {
     use ankidroid_api_rust::{AnkiDroidError, ErrorCategory};
-    
+
     // Example of handling different error types
     let errors = vec![
         AnkiDroidError::not_available("AnkiDroid not installed"),
@@ -210,7 +219,7 @@ fn demonstrate_error_handling() {
         AnkiDroidError::operation_failed("Network timeout"),
         AnkiDroidError::system_error("Out of memory"),
     ];
-    
+
     for error in errors {
         println!("Error: {}", error);
         println!("Category: {:?}", error.category());
@@ -228,4 +237,4 @@ fn main() {
     println!("2. Build for an Android target:");
     println!("   cargo build --target aarch64-linux-android");
     println!("3. Deploy and run on an Android device with AnkiDroid installed");
-}
\ No newline at end of file
+}
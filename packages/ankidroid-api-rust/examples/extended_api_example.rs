@@ -58,13 +58,14 @@ pub fn extended_api_example(env: JNIEnv, context: &JObject) -> Result<()> {
     // === EXTENDED API: Update Notes ===
     if let Some(first_note) = notes.first() {
         println!("\n✏️  Updating note {}...", first_note.id);
-        
+
         // Prepare updated fields (same count as original)
         let mut updated_fields = first_note.fields.clone();
         if !updated_fields.is_empty() {
             // Update the first field to include a timestamp
-            updated_fields[0] = format!("{} (Updated at {})", 
-                updated_fields[0], 
+            updated_fields[0] = format!(
+                "{} (Updated at {})",
+                updated_fields[0],
                 std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
@@ -86,7 +87,7 @@ pub fn extended_api_example(env: JNIEnv, context: &JObject) -> Result<()> {
     // === EXTENDED API: Delete Notes (Demo - commented out for safety) ===
     println!("\n🗑️  Note deletion example (commented out for safety)");
     println!("   To delete a note, use: api.delete_note(note_id)?");
-    
+
     // Uncomment the following lines to actually delete notes:
     /*
     if notes.len() > 10 { // Only delete if there are many notes
@@ -106,13 +107,13 @@ pub fn extended_api_example(env: JNIEnv, context: &JObject) -> Result<()> {
     let final_notes = api.list_notes()?;
     println!("\n📊 Final Statistics:");
     println!("   Total notes: {}", final_notes.len());
-    
+
     // Group notes by model
     let mut model_counts = std::collections::HashMap::new();
     for note in &final_notes {
         *model_counts.entry(note.mid).or_insert(0) += 1;
     }
-    
+
     println!("   Notes by model:");
     for (model_id, count) in model_counts {
         println!("     Model {}: {} notes", model_id, count);
@@ -183,7 +184,7 @@ fn main() {
         println!("🤖 This example should be integrated into an Android application.");
         println!("   Use the extended_api_example() function from your Android code.");
     }
-    
+
     #[cfg(not(target_os = "android"))]
     {
         extended_api_example();
@@ -211,4 +212,4 @@ mod tests {
         // The trait should be available for import
         assert!(true);
     }
-}
\ No newline at end of file
+}
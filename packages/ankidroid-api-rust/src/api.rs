@@ -49,13 +49,13 @@
 
 #[cfg(target_os = "android")]
 use crate::{
+    contract::{self, card, deck, model, note, DEFAULT_DECK_ID},
     error::{AnkiDroidError, Result},
     jni::{
         content_resolver::ContentResolver,
-        helpers::{ContentValuesBuilder, SafeJNIEnv, JniResultExt},
+        helpers::{ContentValuesBuilder, JniResultExt, SafeJNIEnv},
     },
-    models::{BasicModel, Basic2Model},
-    contract::{self, note, deck, model, card, DEFAULT_DECK_ID},
+    models::{Basic2Model, BasicModel},
     utils::{join_fields, join_tags},
 };
 
@@ -108,7 +108,7 @@ pub struct AnkiDroidApi<'local> {
 impl<'local> AnkiDroidApi<'local> {
     /// Create a new AnkiDroid API instance
     ///
-    /// This method creates a new API client from JNI environment and Android Context, 
+    /// This method creates a new API client from JNI environment and Android Context,
     /// automatically detecting the API version and initializing the ContentResolver.
     ///
     /// # Arguments
@@ -131,32 +131,32 @@ impl<'local> AnkiDroidApi<'local> {
     /// ```
     pub fn try_new(env: JNIEnv<'local>, context: &JObject<'local>) -> Result<Self> {
         log::info!("Creating AnkiDroidApi instance...");
-        
+
         // Validate context
         if context.is_null() {
             return Err(AnkiDroidError::null_pointer("Context cannot be null"));
         }
 
         let mut safe_env = SafeJNIEnv::new(env);
-        
+
         // Create ContentResolver
         let resolver = ContentResolver::from_context(safe_env.clone(), context)?;
-        
+
         // Detect API version
         let version = Self::detect_api_version(&mut safe_env, context)?;
         log::info!("Detected AnkiDroid API version: {}", version);
-        
+
         // Verify AnkiDroid is available
         if !Self::is_available_internal(&mut safe_env, context)? {
             return Err(AnkiDroidError::not_available(
-                "AnkiDroid API is not available"
+                "AnkiDroid API is not available",
             ));
         }
-        
+
         // We need to create a global reference to the context so it persists
         let context_global = safe_env.env_mut().new_global_ref(context)?;
         let context_obj = safe_env.env_mut().new_local_ref(&context_global)?;
-        
+
         Ok(Self {
             env: safe_env,
             context: context_obj,
@@ -198,7 +198,7 @@ impl<'local> AnkiDroidApi<'local> {
     fn is_available_internal(env: &mut SafeJNIEnv, context: &JObject) -> Result<bool> {
         // For external apps, we check if AnkiDroid is installed and accessible via ContentResolver
         // We don't check for AddContentApi class since it's in AnkiDroid app, not ours
-        
+
         // Try to get PackageManager to check if AnkiDroid is installed
         let pm_result = env.env_mut().call_method(
             context,
@@ -206,7 +206,7 @@ impl<'local> AnkiDroidApi<'local> {
             "()Landroid/content/pm/PackageManager;",
             &[],
         );
-        
+
         let package_manager = match pm_result {
             Ok(result) => match result.l() {
                 Ok(pm) if !pm.is_null() => pm,
@@ -214,10 +214,10 @@ impl<'local> AnkiDroidApi<'local> {
             },
             Err(_) => return Ok(false),
         };
-        
+
         // Check if AnkiDroid package is installed
         let package_name = env.new_string_checked("com.ichi2.anki")?;
-        
+
         // Try to get package info (will fail if not installed)
         let package_info_result = env.env_mut().call_method(
             &package_manager,
@@ -225,35 +225,38 @@ impl<'local> AnkiDroidApi<'local> {
             "(Ljava/lang/String;I)Landroid/content/pm/PackageInfo;",
             &[JValue::Object(&package_name), JValue::Int(0)],
         );
-        
+
         match package_info_result {
             Ok(result) => match result.l() {
                 Ok(info) => {
                     log::debug!("AnkiDroid package found: com.ichi2.anki");
                     Ok(!info.is_null())
-                },
+                }
                 Err(_) => {
                     log::debug!("AnkiDroid package not found");
                     Ok(false)
-                },
+                }
             },
             Err(_) => {
                 log::debug!("Failed to check for AnkiDroid package");
                 Ok(false)
-            },
+            }
         }
     }
 
     // Detect API specification version
     fn detect_api_version(env: &mut SafeJNIEnv, context: &JObject) -> Result<i32> {
         // Use PackageManager to get provider metadata
-        let pm_result = env.env_mut().call_method(
-            context,
-            "getPackageManager",
-            "()Landroid/content/pm/PackageManager;",
-            &[],
-        ).check_exception(env.env_mut())?;
-        
+        let pm_result = env
+            .env_mut()
+            .call_method(
+                context,
+                "getPackageManager",
+                "()Landroid/content/pm/PackageManager;",
+                &[],
+            )
+            .check_exception(env.env_mut())?;
+
         let package_manager = pm_result.l().map_err(AnkiDroidError::from)?;
         if package_manager.is_null() {
             return Ok(1); // Default to v1
@@ -261,16 +264,19 @@ impl<'local> AnkiDroidApi<'local> {
 
         // Try to resolve FlashCards content provider
         let authority_string = env.new_string_checked(contract::AUTHORITY)?;
-        
-        let provider_info_result = env.env_mut().call_method(
-            &package_manager,
-            "resolveContentProvider",
-            "(Ljava/lang/String;I)Landroid/content/pm/ProviderInfo;",
-            &[
-                JValue::Object(&authority_string.into()),
-                JValue::Int(0), // flags
-            ],
-        ).check_exception(env.env_mut());
+
+        let provider_info_result = env
+            .env_mut()
+            .call_method(
+                &package_manager,
+                "resolveContentProvider",
+                "(Ljava/lang/String;I)Landroid/content/pm/ProviderInfo;",
+                &[
+                    JValue::Object(&authority_string.into()),
+                    JValue::Int(0), // flags
+                ],
+            )
+            .check_exception(env.env_mut());
 
         let provider_info = match provider_info_result {
             Ok(result) => result.l().map_err(AnkiDroidError::from)?,
@@ -282,11 +288,10 @@ impl<'local> AnkiDroidApi<'local> {
         }
 
         // Try to get metadata
-        let metadata_result = env.env_mut().get_field(
-            &provider_info,
-            "metaData",
-            "Landroid/os/Bundle;",
-        ).check_exception(env.env_mut());
+        let metadata_result = env
+            .env_mut()
+            .get_field(&provider_info, "metaData", "Landroid/os/Bundle;")
+            .check_exception(env.env_mut());
 
         let metadata = match metadata_result {
             Ok(metadata) => metadata.l().map_err(AnkiDroidError::from)?,
@@ -299,15 +304,18 @@ impl<'local> AnkiDroidApi<'local> {
 
         // Try to get the spec version from metadata
         let spec_key = env.new_string_checked("com.ichi2.anki.provider.spec")?;
-        let version_result = env.env_mut().call_method(
-            &metadata,
-            "getInt",
-            "(Ljava/lang/String;I)I",
-            &[
-                JValue::Object(&spec_key.into()),
-                JValue::Int(1), // default value
-            ],
-        ).check_exception(env.env_mut())?;
+        let version_result = env
+            .env_mut()
+            .call_method(
+                &metadata,
+                "getInt",
+                "(Ljava/lang/String;I)I",
+                &[
+                    JValue::Object(&spec_key.into()),
+                    JValue::Int(1), // default value
+                ],
+            )
+            .check_exception(env.env_mut())?;
 
         Ok(version_result.i().unwrap_or(1))
     }
@@ -364,7 +372,7 @@ impl<'local> AnkiDroidApi<'local> {
     ///     &["What is the capital of France?", "Paris"],
     ///     Some(&["geography", "capitals"])
     /// )?;
-    /// 
+    ///
     /// if let Some(id) = note_id {
     ///     println!("Created note with ID: {}", id);
     /// }
@@ -378,7 +386,9 @@ impl<'local> AnkiDroidApi<'local> {
     ) -> Result<Option<i64>> {
         log::info!(
             "Adding note with model_id={}, deck_id={}, {} fields",
-            model_id, deck_id, fields.len()
+            model_id,
+            deck_id,
+            fields.len()
         );
 
         // Validate inputs
@@ -389,20 +399,23 @@ impl<'local> AnkiDroidApi<'local> {
         // Build ContentValues for the note
         let mut values = ContentValuesBuilder::new(&mut self.env)?
             .put_long(note::MID, model_id)?
-            .put_string(note::FLDS, &join_fields(&fields.iter().map(|s| *s).collect::<Vec<_>>()))?;
+            .put_string(
+                note::FLDS,
+                &join_fields(&fields.iter().map(|s| *s).collect::<Vec<_>>()),
+            )?;
 
         // Add tags if provided
         if let Some(tags_array) = tags {
             if !tags_array.is_empty() {
-                values = values.put_string(note::TAGS, &join_tags(&tags_array.iter().map(|s| *s).collect::<Vec<_>>()))?;
+                values = values.put_string(
+                    note::TAGS,
+                    &join_tags(&tags_array.iter().map(|s| *s).collect::<Vec<_>>()),
+                )?;
             }
         }
 
         // Insert the note
-        let note_uri = self.resolver.insert(
-            &contract::build_note_uri(),
-            values,
-        )?;
+        let note_uri = self.resolver.insert(&contract::build_note_uri(), values)?;
 
         // Extract note ID from URI
         let note_id = Self::extract_id_from_uri(&note_uri)?;
@@ -439,7 +452,7 @@ impl<'local> AnkiDroidApi<'local> {
     // Helper method to move cards to a deck
     fn move_note_cards_to_deck(&mut self, note_id: i64, deck_id: i64) -> Result<()> {
         let cards_uri = contract::build_cards_for_note_uri(note_id);
-        
+
         let mut cursor = self.resolver.query(
             &cards_uri,
             Some(vec![card::CARD_ORD.to_string()]),
@@ -451,10 +464,10 @@ impl<'local> AnkiDroidApi<'local> {
         while cursor.move_to_next()? {
             let ord_str = cursor.get_string_by_name(card::CARD_ORD)?;
             let card_uri = contract::build_specific_card_uri(note_id, &ord_str);
-            
-            let values = ContentValuesBuilder::new(&mut self.env)?
-                .put_long(card::DECK_ID, deck_id)?;
-            
+
+            let values =
+                ContentValuesBuilder::new(&mut self.env)?.put_long(card::DECK_ID, deck_id)?;
+
             self.resolver.update(&card_uri, values, None, None)?;
         }
 
@@ -574,7 +587,7 @@ impl<'local> AnkiDroidApi<'local> {
         // Validate input arrays
         if qfmt.len() != cards.len() || afmt.len() != cards.len() {
             return Err(AnkiDroidError::validation_error(
-                "cards, qfmt, and afmt arrays must all be the same length"
+                "cards, qfmt, and afmt arrays must all be the same length",
             ));
         }
 
@@ -582,13 +595,20 @@ impl<'local> AnkiDroidApi<'local> {
             return Err(AnkiDroidError::validation_error("Fields cannot be empty"));
         }
 
-        log::info!("Creating custom model '{}' with {} fields, {} cards", 
-                   name, fields.len(), cards.len());
+        log::info!(
+            "Creating custom model '{}' with {} fields, {} cards",
+            name,
+            fields.len(),
+            cards.len()
+        );
 
         // Create the model
         let mut values = ContentValuesBuilder::new(&mut self.env)?
             .put_string(model::NAME, name)?
-            .put_string(model::FIELD_NAMES, &join_fields(&fields.iter().map(|s| *s).collect::<Vec<_>>()))?
+            .put_string(
+                model::FIELD_NAMES,
+                &join_fields(&fields.iter().map(|s| *s).collect::<Vec<_>>()),
+            )?
             .put_int(model::NUM_CARDS, cards.len() as i32)?;
 
         if let Some(css_content) = css {
@@ -603,19 +623,23 @@ impl<'local> AnkiDroidApi<'local> {
             values = values.put_int(model::SORT_FIELD_INDEX, sort_field)?;
         }
 
-        let model_uri = self.resolver.insert(&contract::build_models_uri(), values)?;
+        let model_uri = self
+            .resolver
+            .insert(&contract::build_models_uri(), values)?;
         let model_id = Self::extract_id_from_uri(&model_uri)?;
 
         // Set up card templates
         for i in 0..cards.len() {
             let template_uri = contract::build_template_uri(model_id, i as i32);
-            
+
             let template_values = ContentValuesBuilder::new(&mut self.env)?
                 .put_string(contract::card_template::NAME, cards[i])?
                 .put_string(contract::card_template::QUESTION_FORMAT, qfmt[i])?
                 .put_string(contract::card_template::ANSWER_FORMAT, afmt[i])?;
 
-            let updated = self.resolver.update(&template_uri, template_values, None, None)?;
+            let updated = self
+                .resolver
+                .update(&template_uri, template_values, None, None)?;
             if updated == 0 {
                 log::warn!("Failed to update template {} for model {}", i, model_id);
             }
@@ -644,13 +668,13 @@ impl<'local> AnkiDroidApi<'local> {
     /// ```
     pub fn add_new_deck(&mut self, deck_name: &str) -> Result<Option<i64>> {
         log::info!("Creating new deck: {}", deck_name);
-        
-        let values = ContentValuesBuilder::new(&mut self.env)?
-            .put_string(deck::DECK_NAME, deck_name)?;
+
+        let values =
+            ContentValuesBuilder::new(&mut self.env)?.put_string(deck::DECK_NAME, deck_name)?;
 
         let deck_uri = self.resolver.insert(&contract::build_decks_uri(), values)?;
         let deck_id = Self::extract_id_from_uri(&deck_uri)?;
-        
+
         log::info!("✅ Deck created with ID: {}", deck_id);
         Ok(Some(deck_id))
     }
@@ -672,21 +696,18 @@ impl<'local> AnkiDroidApi<'local> {
     pub fn get_deck_list(&mut self) -> Result<HashMap<i64, String>> {
         let mut cursor = self.resolver.query(
             &contract::build_decks_uri(),
-            Some(vec![
-                deck::DECK_ID.to_string(),
-                deck::DECK_NAME.to_string(),
-            ]),
+            Some(vec![deck::DECK_ID.to_string(), deck::DECK_NAME.to_string()]),
             None,
             None,
             None,
         )?;
 
         let mut decks = HashMap::new();
-        
+
         while cursor.move_to_next()? {
             let id_str = cursor.get_string_by_name(deck::DECK_ID)?;
             let name = cursor.get_string_by_name(deck::DECK_NAME)?;
-            
+
             if let Ok(id) = id_str.parse::<i64>() {
                 decks.insert(id, name);
             }
@@ -718,6 +739,18 @@ impl<'local> AnkiDroidApi<'local> {
     pub fn env_mut(&mut self) -> &mut SafeJNIEnv<'local> {
         &mut self.env
     }
+
+    /// Get the Android Context this API instance was created with
+    ///
+    /// This allows extended operations such as starting activities to reuse the
+    /// same Context as the database operations.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the Context object
+    pub fn context(&self) -> &JObject<'local> {
+        &self.context
+    }
 }
 
 // Make sure we only export for Android targets
@@ -750,4 +783,4 @@ mod tests {
 
     // Additional tests would require a mock JNI environment
     // which is complex to set up in unit tests
-}
\ No newline at end of file
+}
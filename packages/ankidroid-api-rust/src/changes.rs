@@ -0,0 +1,133 @@
+//! Incremental change tracking for notes
+//!
+//! AnkiDroid stamps every note with its modification time (`note::MOD`, in
+//! seconds) but keeps no record of deleted notes. An app mirroring part of the
+//! collection remembers when it last synced and which note IDs it knows about;
+//! [`NoteChanges`] sorts the current notes into created, updated and deleted
+//! relative to that state. It is pure so it can run on any target; the
+//! Android-only `note_changes` method of
+//! [`AnkiDroidApiExtended`](crate::AnkiDroidApiExtended) feeds it the notes read
+//! from the content provider.
+
+use crate::models::Note;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Check whether a note was modified at or after `since`
+///
+/// The comparison is inclusive because modification times only have a
+/// resolution of one second; a note changed in the same second as the previous
+/// sync is reported again rather than missed.
+pub fn modified_since(note: &Note, since: i64) -> bool {
+    note.mod_ >= since
+}
+
+/// Get the known IDs that are no longer present, sorted
+pub fn deleted_ids(known_ids: &[i64], current_ids: &HashSet<i64>) -> Vec<i64> {
+    let mut deleted: Vec<i64> = known_ids
+        .iter()
+        .copied()
+        .filter(|id| !current_ids.contains(id))
+        .collect();
+    deleted.sort_unstable();
+    deleted.dedup();
+    deleted
+}
+
+/// Notes created, updated and deleted since a previous sync
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NoteChanges {
+    /// Notes modified since the sync whose IDs were not known
+    pub created: Vec<Note>,
+    /// Known notes modified since the sync
+    pub updated: Vec<Note>,
+    /// Known note IDs that no longer exist
+    pub deleted: Vec<i64>,
+}
+
+impl NoteChanges {
+    /// Sort the current notes into changes relative to `since` and `known_ids`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ankidroid_api_rust::{Note, NoteChanges};
+    ///
+    /// let mut edited = Note::new(1, vec!["Q".to_string(), "A".to_string()]);
+    /// edited.id = 10;
+    /// edited.mod_ = 2_000;
+    ///
+    /// let changes = NoteChanges::from_notes(vec![edited], 1_000, &[10, 11]);
+    /// assert_eq!(changes.updated.len(), 1);
+    /// assert_eq!(changes.deleted, vec![11]);
+    /// ```
+    pub fn from_notes(notes: Vec<Note>, since: i64, known_ids: &[i64]) -> Self {
+        let known: HashSet<i64> = known_ids.iter().copied().collect();
+        let current: HashSet<i64> = notes.iter().map(|note| note.id).collect();
+
+        let (updated, created) = notes
+            .into_iter()
+            .filter(|note| modified_since(note, since))
+            .partition(|note| known.contains(&note.id));
+
+        Self {
+            created,
+            updated,
+            deleted: deleted_ids(known_ids, &current),
+        }
+    }
+
+    /// Check whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: i64, mod_: i64) -> Note {
+        let mut note = Note::new(1, vec!["Front".to_string(), "Back".to_string()]);
+        note.id = id;
+        note.mod_ = mod_;
+        note
+    }
+
+    fn ids(notes: &[Note]) -> Vec<i64> {
+        notes.iter().map(|note| note.id).collect()
+    }
+
+    #[test]
+    fn test_from_notes() {
+        let notes = vec![note(1, 100), note(2, 500), note(3, 600), note(4, 50)];
+        let changes = NoteChanges::from_notes(notes, 500, &[1, 2, 5, 6]);
+
+        assert_eq!(ids(&changes.created), vec![3]);
+        // Modified in the same second as the previous sync
+        assert_eq!(ids(&changes.updated), vec![2]);
+        assert_eq!(changes.deleted, vec![5, 6]);
+        assert!(!changes.is_empty());
+    }
+
+    #[test]
+    fn test_first_sync_creates_everything() {
+        let changes = NoteChanges::from_notes(vec![note(1, 100), note(2, 200)], 0, &[]);
+        assert_eq!(ids(&changes.created), vec![1, 2]);
+        assert!(changes.updated.is_empty());
+        assert!(changes.deleted.is_empty());
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let changes = NoteChanges::from_notes(vec![note(1, 100)], 200, &[1]);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_deleted_ids() {
+        let current: HashSet<i64> = [1, 2].into_iter().collect();
+        assert_eq!(deleted_ids(&[3, 1, 3, 4], &current), vec![3, 4]);
+        assert!(deleted_ids(&[], &current).is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 //! AnkiDroid FlashCards Contract Constants
-//! 
+//!
 //! This module contains all the constants and URI builders needed to interact
 //! with the AnkiDroid content provider API. It's a Rust translation of the
 //! original Kotlin FlashCardsContract.
@@ -58,13 +58,20 @@ pub fn build_templates_uri(model_id: i64) -> String {
 }
 
 pub fn build_template_uri(model_id: i64, template_ord: i32) -> String {
-    format!("content://{}/models/{}/templates/{}", AUTHORITY, model_id, template_ord)
+    format!(
+        "content://{}/models/{}/templates/{}",
+        AUTHORITY, model_id, template_ord
+    )
 }
 
 pub fn build_decks_uri() -> String {
     format!("content://{}/decks", AUTHORITY)
 }
 
+pub fn build_deck_by_id_uri(deck_id: i64) -> String {
+    format!("content://{}/decks/{}", AUTHORITY, deck_id)
+}
+
 pub fn build_selected_deck_uri() -> String {
     format!("content://{}/selected_deck", AUTHORITY)
 }
@@ -116,9 +123,8 @@ pub mod card {
     pub const ANSWER_PURE: &str = "answer_pure";
 
     /// Default projection for card queries
-    pub static DEFAULT_PROJECTION: &[&str] = &[
-        NOTE_ID, CARD_ORD, CARD_NAME, DECK_ID, QUESTION, ANSWER,
-    ];
+    pub static DEFAULT_PROJECTION: &[&str] =
+        &[NOTE_ID, CARD_ORD, CARD_NAME, DECK_ID, QUESTION, ANSWER];
 
     /// MIME types
     pub const CONTENT_ITEM_TYPE: &str = "vnd.android.cursor.item/vnd.com.ichi2.anki.card";
@@ -136,7 +142,12 @@ pub mod deck {
 
     /// Default projection for deck queries
     pub static DEFAULT_PROJECTION: &[&str] = &[
-        DECK_NAME, DECK_ID, DECK_COUNTS, OPTIONS, DECK_DYN, DECK_DESC,
+        DECK_NAME,
+        DECK_ID,
+        DECK_COUNTS,
+        OPTIONS,
+        DECK_DYN,
+        DECK_DESC,
     ];
 
     /// MIME types
@@ -163,7 +174,16 @@ pub mod model {
 
     /// Default projection for model queries
     pub static DEFAULT_PROJECTION: &[&str] = &[
-        _ID, NAME, FIELD_NAMES, NUM_CARDS, CSS, DECK_ID, SORT_FIELD_INDEX, TYPE, LATEX_POST, LATEX_PRE,
+        _ID,
+        NAME,
+        FIELD_NAMES,
+        NUM_CARDS,
+        CSS,
+        DECK_ID,
+        SORT_FIELD_INDEX,
+        TYPE,
+        LATEX_POST,
+        LATEX_PRE,
     ];
 
     /// MIME types
@@ -184,9 +204,8 @@ pub mod card_template {
     pub const CARD_COUNT: &str = "card_count";
 
     /// Default projection for card template queries
-    pub static DEFAULT_PROJECTION: &[&str] = &[
-        _ID, MODEL_ID, ORD, NAME, QUESTION_FORMAT, ANSWER_FORMAT,
-    ];
+    pub static DEFAULT_PROJECTION: &[&str] =
+        &[_ID, MODEL_ID, ORD, NAME, QUESTION_FORMAT, ANSWER_FORMAT];
 
     /// MIME types
     pub const CONTENT_TYPE: &str = "vnd.android.cursor.dir/vnd.com.ichi2.anki.model.template";
@@ -207,7 +226,11 @@ pub mod review_info {
 
     /// Default projection for review info queries
     pub static DEFAULT_PROJECTION: &[&str] = &[
-        NOTE_ID, CARD_ORD, BUTTON_COUNT, NEXT_REVIEW_TIMES, MEDIA_FILES,
+        NOTE_ID,
+        CARD_ORD,
+        BUTTON_COUNT,
+        NEXT_REVIEW_TIMES,
+        MEDIA_FILES,
     ];
 
     /// MIME types
@@ -227,25 +250,71 @@ mod tests {
     #[test]
     fn test_uri_builders() {
         assert_eq!(build_authority_uri(), "content://com.ichi2.anki.flashcards");
-        assert_eq!(build_note_uri(), "content://com.ichi2.anki.flashcards/notes");
-        assert_eq!(build_note_by_id_uri(123), "content://com.ichi2.anki.flashcards/notes/123");
-        assert_eq!(build_cards_for_note_uri(456), "content://com.ichi2.anki.flashcards/notes/456/cards");
-        assert_eq!(build_specific_card_uri(789, "2"), "content://com.ichi2.anki.flashcards/notes/789/cards/2");
-        assert_eq!(build_notes_v2_uri(), "content://com.ichi2.anki.flashcards/notes_v2");
-        assert_eq!(build_models_uri(), "content://com.ichi2.anki.flashcards/models");
-        assert_eq!(build_model_by_id_uri(101112), "content://com.ichi2.anki.flashcards/models/101112");
-        assert_eq!(build_current_model_uri(), "content://com.ichi2.anki.flashcards/models/current");
-        assert_eq!(build_templates_uri(131415), "content://com.ichi2.anki.flashcards/models/131415/templates");
-        assert_eq!(build_decks_uri(), "content://com.ichi2.anki.flashcards/decks");
-        assert_eq!(build_selected_deck_uri(), "content://com.ichi2.anki.flashcards/selected_deck");
-        assert_eq!(build_schedule_uri(), "content://com.ichi2.anki.flashcards/schedule");
-        assert_eq!(build_media_uri(), "content://com.ichi2.anki.flashcards/media");
+        assert_eq!(
+            build_note_uri(),
+            "content://com.ichi2.anki.flashcards/notes"
+        );
+        assert_eq!(
+            build_note_by_id_uri(123),
+            "content://com.ichi2.anki.flashcards/notes/123"
+        );
+        assert_eq!(
+            build_cards_for_note_uri(456),
+            "content://com.ichi2.anki.flashcards/notes/456/cards"
+        );
+        assert_eq!(
+            build_specific_card_uri(789, "2"),
+            "content://com.ichi2.anki.flashcards/notes/789/cards/2"
+        );
+        assert_eq!(
+            build_notes_v2_uri(),
+            "content://com.ichi2.anki.flashcards/notes_v2"
+        );
+        assert_eq!(
+            build_models_uri(),
+            "content://com.ichi2.anki.flashcards/models"
+        );
+        assert_eq!(
+            build_model_by_id_uri(101112),
+            "content://com.ichi2.anki.flashcards/models/101112"
+        );
+        assert_eq!(
+            build_current_model_uri(),
+            "content://com.ichi2.anki.flashcards/models/current"
+        );
+        assert_eq!(
+            build_templates_uri(131415),
+            "content://com.ichi2.anki.flashcards/models/131415/templates"
+        );
+        assert_eq!(
+            build_decks_uri(),
+            "content://com.ichi2.anki.flashcards/decks"
+        );
+        assert_eq!(
+            build_deck_by_id_uri(42),
+            "content://com.ichi2.anki.flashcards/decks/42"
+        );
+        assert_eq!(
+            build_selected_deck_uri(),
+            "content://com.ichi2.anki.flashcards/selected_deck"
+        );
+        assert_eq!(
+            build_schedule_uri(),
+            "content://com.ichi2.anki.flashcards/schedule"
+        );
+        assert_eq!(
+            build_media_uri(),
+            "content://com.ichi2.anki.flashcards/media"
+        );
     }
 
     #[test]
     fn test_constants() {
         assert_eq!(AUTHORITY, "com.ichi2.anki.flashcards");
-        assert_eq!(READ_WRITE_PERMISSION, "com.ichi2.anki.permission.READ_WRITE_DATABASE");
+        assert_eq!(
+            READ_WRITE_PERMISSION,
+            "com.ichi2.anki.permission.READ_WRITE_DATABASE"
+        );
         assert_eq!(DEFAULT_DECK_ID, 1);
         assert_eq!(FIELD_SEPARATOR, '\u{001f}');
     }
@@ -275,9 +344,21 @@ mod tests {
     #[test]
     fn test_mime_types() {
         // Test a few key MIME types
-        assert_eq!(note::CONTENT_TYPE, "vnd.android.cursor.dir/vnd.com.ichi2.anki.note");
-        assert_eq!(note::CONTENT_ITEM_TYPE, "vnd.android.cursor.item/vnd.com.ichi2.anki.note");
-        assert_eq!(card::CONTENT_TYPE, "vnd.android.cursor.dir/vnd.com.ichi2.anki.card");
-        assert_eq!(model::CONTENT_TYPE, "vnd.android.cursor.dir/vnd.com.ichi2.anki.model");
+        assert_eq!(
+            note::CONTENT_TYPE,
+            "vnd.android.cursor.dir/vnd.com.ichi2.anki.note"
+        );
+        assert_eq!(
+            note::CONTENT_ITEM_TYPE,
+            "vnd.android.cursor.item/vnd.com.ichi2.anki.note"
+        );
+        assert_eq!(
+            card::CONTENT_TYPE,
+            "vnd.android.cursor.dir/vnd.com.ichi2.anki.card"
+        );
+        assert_eq!(
+            model::CONTENT_TYPE,
+            "vnd.android.cursor.dir/vnd.com.ichi2.anki.model"
+        );
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,227 @@
+//! Typed deck options and details
+//!
+//! AnkiDroid reports a deck's options group as a JSON object in the
+//! `deck::OPTIONS` column and its description and filtered flag in
+//! `deck::DECK_DESC` and `deck::DECK_DYN`. This module gives those columns a
+//! typed shape. They are read only: AnkiDroid's provider rejects updates to a
+//! deck other than selecting it, so there is no way to write them back.
+
+use crate::error::{AnkiDroidError, Result};
+use crate::models::Deck;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Scheduling options of a deck's options group
+///
+/// Learning and relearning steps are in minutes, intervals in days and the
+/// starting ease in permille (2500 is 250%), as stored by Anki.
+///
+/// Options are read only, since AnkiDroid's provider rejects deck updates;
+/// change them in AnkiDroid's deck options screen instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeckOptions {
+    /// ID of the options group (shared by every deck using it)
+    pub id: i64,
+    /// Name of the options group
+    pub name: String,
+    /// Maximum new cards introduced per day
+    pub new_per_day: i32,
+    /// Maximum reviews per day
+    pub reviews_per_day: i32,
+    /// Learning steps for new cards
+    pub learning_steps: Vec<f64>,
+    /// Interval after a new card graduates
+    pub graduating_interval: i32,
+    /// Interval after a new card is answered Easy
+    pub easy_interval: i32,
+    /// Starting ease of new cards
+    pub starting_ease: i32,
+    /// Longest interval a review can reach
+    pub maximum_interval: i32,
+    /// Relearning steps for lapsed cards
+    pub relearning_steps: Vec<f64>,
+    /// Number of lapses before a card is tagged as a leech
+    pub leech_threshold: i32,
+    /// Seconds after which an answer is capped for the statistics
+    pub max_answer_seconds: i32,
+}
+
+impl Default for DeckOptions {
+    /// Anki's defaults for a new options group
+    fn default() -> Self {
+        Self {
+            id: 1,
+            name: "Default".to_string(),
+            new_per_day: 20,
+            reviews_per_day: 200,
+            learning_steps: vec![1.0, 10.0],
+            graduating_interval: 1,
+            easy_interval: 4,
+            starting_ease: 2500,
+            maximum_interval: 36500,
+            relearning_steps: vec![10.0],
+            leech_threshold: 8,
+            max_answer_seconds: 60,
+        }
+    }
+}
+
+impl DeckOptions {
+    /// Parse the options JSON; settings it lacks keep Anki's defaults
+    pub fn from_json(value: &Value) -> Result<Self> {
+        if !value.is_object() {
+            return Err(AnkiDroidError::json_error(
+                "Deck options must be a JSON object",
+            ));
+        }
+
+        let defaults = Self::default();
+        let int = |path: &[&str], default: i32| {
+            lookup(value, path)
+                .and_then(Value::as_i64)
+                .map(|v| v as i32)
+                .unwrap_or(default)
+        };
+        let steps = |path: &[&str], default: &[f64]| {
+            lookup(value, path)
+                .and_then(Value::as_array)
+                .map(|steps| steps.iter().filter_map(Value::as_f64).collect())
+                .unwrap_or_else(|| default.to_vec())
+        };
+
+        Ok(Self {
+            id: lookup(value, &["id"])
+                .and_then(Value::as_i64)
+                .unwrap_or(defaults.id),
+            name: lookup(value, &["name"])
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or(defaults.name),
+            new_per_day: int(&["new", "perDay"], defaults.new_per_day),
+            reviews_per_day: int(&["rev", "perDay"], defaults.reviews_per_day),
+            learning_steps: steps(&["new", "delays"], &defaults.learning_steps),
+            graduating_interval: int(&["new", "ints", "0"], defaults.graduating_interval),
+            easy_interval: int(&["new", "ints", "1"], defaults.easy_interval),
+            starting_ease: int(&["new", "initialFactor"], defaults.starting_ease),
+            maximum_interval: int(&["rev", "maxIvl"], defaults.maximum_interval),
+            relearning_steps: steps(&["lapse", "delays"], &defaults.relearning_steps),
+            leech_threshold: int(&["lapse", "leechFails"], defaults.leech_threshold),
+            max_answer_seconds: int(&["maxTaken"], defaults.max_answer_seconds),
+        })
+    }
+
+    /// Parse the options JSON from the `deck::OPTIONS` column
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        Self::from_json(&serde_json::from_str(json)?)
+    }
+}
+
+/// Look up a value by object keys, or array indices for numeric segments
+fn lookup<'a>(value: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |current, key| match current {
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => current.get(key),
+    })
+}
+
+/// Description, filtered flag and options of a deck
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeckDetails {
+    /// Deck ID
+    pub id: i64,
+    /// Full deck name
+    pub name: String,
+    /// Deck description shown on the overview screen
+    pub description: String,
+    /// Whether this is a filtered (dynamic) deck
+    pub filtered: bool,
+    /// Options group of a regular deck (None for filtered decks, which have no options group)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<DeckOptions>,
+}
+
+impl DeckDetails {
+    /// Build the details from a deck read from the provider
+    pub fn from_deck(deck: &Deck) -> Result<Self> {
+        let options = if deck.dyn_ {
+            None
+        } else {
+            Some(DeckOptions::from_json(&deck.options)?)
+        };
+
+        Ok(Self {
+            id: deck.id,
+            name: deck.name.clone(),
+            description: deck.desc.clone(),
+            filtered: deck.dyn_,
+            options,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options_json() -> Value {
+        json!({
+            "id": 1617,
+            "name": "Languages",
+            "new": {"perDay": 15, "delays": [1, 10, 60], "ints": [2, 5, 0], "initialFactor": 2300, "bury": true},
+            "rev": {"perDay": 150, "maxIvl": 3650, "ease4": 1.3},
+            "lapse": {"delays": [10], "leechFails": 6},
+            "maxTaken": 90,
+            "autoplay": true
+        })
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = DeckOptions::from_json(&options_json()).unwrap();
+        assert_eq!(options.id, 1617);
+        assert_eq!(options.name, "Languages");
+        assert_eq!(options.new_per_day, 15);
+        assert_eq!(options.reviews_per_day, 150);
+        assert_eq!(options.learning_steps, vec![1.0, 10.0, 60.0]);
+        assert_eq!(options.graduating_interval, 2);
+        assert_eq!(options.easy_interval, 5);
+        assert_eq!(options.starting_ease, 2300);
+        assert_eq!(options.maximum_interval, 3650);
+        assert_eq!(options.relearning_steps, vec![10.0]);
+        assert_eq!(options.leech_threshold, 6);
+        assert_eq!(options.max_answer_seconds, 90);
+    }
+
+    #[test]
+    fn test_missing_settings_use_defaults() {
+        let options = DeckOptions::from_json_str(r#"{"new": {"perDay": 5}}"#).unwrap();
+        assert_eq!(options.new_per_day, 5);
+        assert_eq!(options.reviews_per_day, 200);
+        assert_eq!(options.learning_steps, vec![1.0, 10.0]);
+
+        assert!(DeckOptions::from_json_str("[1, 2]").is_err());
+        assert!(DeckOptions::from_json_str("not json").is_err());
+    }
+
+    #[test]
+    fn test_details_from_deck() {
+        let mut deck = Deck::new(5, "Lang::Japanese".to_string());
+        deck.desc = "JLPT vocabulary".to_string();
+        deck.options = options_json();
+
+        let details = DeckDetails::from_deck(&deck).unwrap();
+        assert_eq!(details.description, "JLPT vocabulary");
+        assert!(!details.filtered);
+        assert_eq!(details.options.unwrap().new_per_day, 15);
+
+        deck.dyn_ = true;
+        let details = DeckDetails::from_deck(&deck).unwrap();
+        assert!(details.filtered);
+        assert!(details.options.is_none());
+        assert!(serde_json::to_value(&details)
+            .unwrap()
+            .get("options")
+            .is_none());
+    }
+}
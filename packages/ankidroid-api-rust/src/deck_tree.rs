@@ -0,0 +1,273 @@
+//! Hierarchical view of AnkiDroid decks
+//!
+//! AnkiDroid stores subdecks as flat decks whose names contain the `::`
+//! separator (e.g. "Languages::Japanese::N5"). This module rebuilds the
+//! hierarchy from the flat list and rolls study counts up to parent decks. It
+//! is pure so it can run on any target; the Android-only `get_deck_tree` method
+//! of [`AnkiDroidApiExtended`](crate::AnkiDroidApiExtended) feeds it the decks
+//! read from the content provider.
+
+use crate::error::Result;
+use crate::models::Deck;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign};
+
+/// Separator between the segments of a nested deck name
+pub const DECK_SEPARATOR: &str = "::";
+
+/// Study counts of a deck
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckCounts {
+    /// Cards in learning
+    pub learning: i32,
+    /// Cards due for review
+    pub review: i32,
+    /// New cards
+    pub new: i32,
+}
+
+impl DeckCounts {
+    /// Create counts from individual values
+    pub fn new(learning: i32, review: i32, new: i32) -> Self {
+        Self {
+            learning,
+            review,
+            new,
+        }
+    }
+
+    /// Parse the `deck::DECK_COUNTS` column, a JSON array `[learn, review, new]`
+    pub fn from_json(json: &str) -> Result<Self> {
+        let counts: Vec<i32> = serde_json::from_str(json)?;
+        Ok(Self::from_slice(&counts))
+    }
+
+    /// Build counts from a `[learn, review, new]` slice; missing values are zero
+    pub fn from_slice(counts: &[i32]) -> Self {
+        let at = |i: usize| counts.get(i).copied().unwrap_or(0);
+        Self::new(at(0), at(1), at(2))
+    }
+
+    /// Get the sum of all counts
+    pub fn total(&self) -> i32 {
+        self.learning + self.review + self.new
+    }
+}
+
+impl Add for DeckCounts {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.learning + other.learning,
+            self.review + other.review,
+            self.new + other.new,
+        )
+    }
+}
+
+impl AddAssign for DeckCounts {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// A deck and its subdecks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckNode {
+    /// Deck ID (None for a parent that was missing from the deck list)
+    pub id: Option<i64>,
+    /// Last segment of the deck name
+    pub name: String,
+    /// Full `::`-separated deck name
+    pub full_name: String,
+    /// Counts of this deck alone
+    pub counts: DeckCounts,
+    /// Counts of this deck and all of its subdecks
+    pub totals: DeckCounts,
+    /// Subdecks sorted by name
+    pub children: Vec<DeckNode>,
+}
+
+impl DeckNode {
+    fn new(name: &str, full_name: String) -> Self {
+        Self {
+            id: None,
+            name: name.to_string(),
+            full_name,
+            counts: DeckCounts::default(),
+            totals: DeckCounts::default(),
+            children: Vec::new(),
+        }
+    }
+
+    fn child_mut(&mut self, name: &str) -> &mut DeckNode {
+        let full_name = format!("{}{}{}", self.full_name, DECK_SEPARATOR, name);
+        child_mut(&mut self.children, name, full_name)
+    }
+
+    fn finish(&mut self) -> DeckCounts {
+        sort_by_name(&mut self.children);
+        self.totals = self.counts;
+        for child in &mut self.children {
+            self.totals += child.finish();
+        }
+        self.totals
+    }
+
+    fn find(&self, segments: &[&str]) -> Option<&DeckNode> {
+        match segments.split_first() {
+            None => Some(self),
+            Some((first, rest)) => self
+                .children
+                .iter()
+                .find(|child| child.name == *first)
+                .and_then(|child| child.find(rest)),
+        }
+    }
+}
+
+fn child_mut<'a>(nodes: &'a mut Vec<DeckNode>, name: &str, full_name: String) -> &'a mut DeckNode {
+    let index = match nodes.iter().position(|node| node.name == name) {
+        Some(index) => index,
+        None => {
+            nodes.push(DeckNode::new(name, full_name));
+            nodes.len() - 1
+        }
+    };
+    &mut nodes[index]
+}
+
+fn sort_by_name(nodes: &mut [DeckNode]) {
+    nodes.sort_by_key(|node| node.name.to_lowercase());
+}
+
+/// Decks arranged by their `::`-separated names
+///
+/// # Examples
+///
+/// ```rust
+/// use ankidroid_api_rust::{Deck, DeckTree};
+///
+/// let mut n5 = Deck::new(2, "Japanese::N5".to_string());
+/// n5.counts = vec![1, 2, 3];
+/// let tree = DeckTree::build(&[Deck::new(1, "Japanese".to_string()), n5]);
+///
+/// let japanese = tree.find("Japanese").unwrap();
+/// assert_eq!(japanese.children.len(), 1);
+/// assert_eq!(japanese.totals.new, 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckTree {
+    /// Top-level decks sorted by name
+    pub roots: Vec<DeckNode>,
+}
+
+impl DeckTree {
+    /// Build the tree from a flat deck list, rolling counts up to parents
+    pub fn build(decks: &[Deck]) -> Self {
+        let mut roots = Vec::new();
+
+        for deck in decks {
+            let mut segments = deck.name.split(DECK_SEPARATOR);
+            let first = segments.next().unwrap_or_default();
+            let mut node = child_mut(&mut roots, first, first.to_string());
+            for segment in segments {
+                node = node.child_mut(segment);
+            }
+            node.id = Some(deck.id);
+            node.counts = DeckCounts::from_slice(&deck.counts);
+        }
+
+        let mut tree = Self { roots };
+        sort_by_name(&mut tree.roots);
+        for root in &mut tree.roots {
+            root.finish();
+        }
+        tree
+    }
+
+    /// Find a deck by its full name
+    pub fn find(&self, full_name: &str) -> Option<&DeckNode> {
+        let segments: Vec<&str> = full_name.split(DECK_SEPARATOR).collect();
+        let (first, rest) = segments.split_first()?;
+        self.roots
+            .iter()
+            .find(|root| root.name == *first)
+            .and_then(|root| root.find(rest))
+    }
+
+    /// Get the counts summed over every deck
+    pub fn totals(&self) -> DeckCounts {
+        self.roots
+            .iter()
+            .fold(DeckCounts::default(), |acc, root| acc + root.totals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deck(id: i64, name: &str, counts: [i32; 3]) -> Deck {
+        let mut deck = Deck::new(id, name.to_string());
+        deck.counts = counts.to_vec();
+        deck
+    }
+
+    #[test]
+    fn test_counts_from_json() {
+        assert_eq!(
+            DeckCounts::from_json("[1, 2, 3]").unwrap(),
+            DeckCounts::new(1, 2, 3)
+        );
+        assert_eq!(
+            DeckCounts::from_json("[4]").unwrap(),
+            DeckCounts::new(4, 0, 0)
+        );
+        assert!(DeckCounts::from_json("not json").is_err());
+        assert_eq!(DeckCounts::new(1, 2, 3).total(), 6);
+    }
+
+    #[test]
+    fn test_build_nests_and_rolls_up() {
+        let tree = DeckTree::build(&[
+            deck(3, "Lang::Japanese::N5", [1, 2, 3]),
+            deck(1, "Default", [0, 0, 1]),
+            deck(2, "Lang", [0, 1, 0]),
+            deck(4, "Lang::Japanese", [0, 0, 0]),
+            deck(5, "Lang::French", [2, 0, 0]),
+        ]);
+
+        let names: Vec<&str> = tree.roots.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["Default", "Lang"]);
+
+        let lang = tree.find("Lang").unwrap();
+        assert_eq!(lang.id, Some(2));
+        assert_eq!(lang.counts, DeckCounts::new(0, 1, 0));
+        assert_eq!(lang.totals, DeckCounts::new(3, 3, 3));
+        let children: Vec<&str> = lang.children.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(children, vec!["French", "Japanese"]);
+
+        let n5 = tree.find("Lang::Japanese::N5").unwrap();
+        assert_eq!(n5.id, Some(3));
+        assert_eq!(n5.full_name, "Lang::Japanese::N5");
+        assert!(n5.children.is_empty());
+
+        assert_eq!(
+            tree.find("Lang::Japanese").unwrap().totals,
+            DeckCounts::new(1, 2, 3)
+        );
+        assert_eq!(tree.totals(), DeckCounts::new(3, 3, 4));
+        assert!(tree.find("Lang::Spanish").is_none());
+    }
+
+    #[test]
+    fn test_missing_parent_is_placeholder() {
+        let tree = DeckTree::build(&[deck(7, "Orphan::Child", [0, 0, 2])]);
+        let parent = tree.find("Orphan").unwrap();
+        assert_eq!(parent.id, None);
+        assert_eq!(parent.totals.new, 2);
+        assert_eq!(parent.children[0].full_name, "Orphan::Child");
+    }
+}
@@ -3,8 +3,29 @@
 //! This module provides comprehensive error handling for all AnkiDroid API operations,
 //! including JNI errors, Android-specific errors, and application-level errors.
 
+use std::fmt;
 use thiserror::Error;
 
+/// A Java exception thrown by AnkiDroid or the Android framework
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaException {
+    /// Fully qualified class name, e.g. `java.lang.SecurityException`
+    pub class_name: String,
+    /// The exception's message, if it had one
+    pub message: Option<String>,
+    /// The innermost frames of the stack trace, most recent first
+    pub stack_trace: Vec<String>,
+}
+
+impl fmt::Display for JavaException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "{}: {}", self.class_name, message),
+            None => f.write_str(&self.class_name),
+        }
+    }
+}
+
 /// Main error type for AnkiDroid API operations
 #[derive(Error, Debug)]
 pub enum AnkiDroidError {
@@ -13,8 +34,12 @@ pub enum AnkiDroidError {
     AnkiDroidNotAvailable(String),
 
     /// Permission denied for AnkiDroid API access
-    #[error("Permission denied: {0}")]
-    PermissionDenied(String),
+    #[error("Permission denied: {message}")]
+    PermissionDenied {
+        message: String,
+        /// The Java exception that reported the failure, if any
+        exception: Option<JavaException>,
+    },
 
     /// Invalid model ID provided
     #[error("Invalid model ID: {0}")]
@@ -45,20 +70,40 @@ pub enum AnkiDroidError {
     StringConversionError(String),
 
     /// Database operation failed
-    #[error("Database error: {0}")]
-    DatabaseError(String),
+    #[error("Database error: {message}")]
+    DatabaseError {
+        message: String,
+        /// The Java exception that reported the failure, if any
+        exception: Option<JavaException>,
+    },
 
     /// Validation failed
-    #[error("Validation error: {0}")]
-    ValidationError(String),
+    #[error("Validation error: {message}")]
+    ValidationError {
+        message: String,
+        /// The Java exception that reported the failure, if any
+        exception: Option<JavaException>,
+    },
 
     /// I/O operation failed
-    #[error("I/O error: {0}")]
-    IoError(String),
+    #[error("I/O error: {message}")]
+    IoError {
+        message: String,
+        /// The Java exception that reported the failure, if any
+        exception: Option<JavaException>,
+    },
 
     /// JSON serialization/deserialization failed
     #[error("JSON error: {0}")]
     JsonError(String),
+
+    /// AnkiDroid's database is locked or its provider is not ready yet
+    #[error("AnkiDroid is busy: {message}")]
+    Busy {
+        message: String,
+        /// The Java exception that reported the failure, if any
+        exception: Option<JavaException>,
+    },
 }
 
 impl AnkiDroidError {
@@ -69,7 +114,10 @@ impl AnkiDroidError {
 
     /// Create a new permission denied error
     pub fn permission_denied(msg: impl Into<String>) -> Self {
-        Self::PermissionDenied(msg.into())
+        Self::PermissionDenied {
+            message: msg.into(),
+            exception: None,
+        }
     }
 
     /// Create a new invalid model ID error
@@ -109,17 +157,26 @@ impl AnkiDroidError {
 
     /// Create a new database error
     pub fn database_error(msg: impl Into<String>) -> Self {
-        Self::DatabaseError(msg.into())
+        Self::DatabaseError {
+            message: msg.into(),
+            exception: None,
+        }
     }
 
     /// Create a new validation error
     pub fn validation_error(msg: impl Into<String>) -> Self {
-        Self::ValidationError(msg.into())
+        Self::ValidationError {
+            message: msg.into(),
+            exception: None,
+        }
     }
 
     /// Create a new I/O error
     pub fn io_error(msg: impl Into<String>) -> Self {
-        Self::IoError(msg.into())
+        Self::IoError {
+            message: msg.into(),
+            exception: None,
+        }
     }
 
     /// Create a new JSON error
@@ -127,6 +184,57 @@ impl AnkiDroidError {
         Self::JsonError(msg.into())
     }
 
+    /// Create a new busy error
+    pub fn busy(msg: impl Into<String>) -> Self {
+        Self::Busy {
+            message: msg.into(),
+            exception: None,
+        }
+    }
+
+    /// Map a Java exception to the variant for its cause
+    ///
+    /// `superclasses` lists the exception's superclasses, nearest first, so a
+    /// subclass such as `SQLiteConstraintException` maps like `SQLiteException`.
+    /// A locked database or a provider process that died maps to
+    /// [`AnkiDroidError::Busy`]. Exceptions of other classes become
+    /// [`AnkiDroidError::JniError`].
+    pub fn from_java_exception(exception: JavaException, superclasses: &[String]) -> Self {
+        let message = exception.to_string();
+        let class_name = exception.class_name.clone();
+        let exception = Some(exception);
+
+        for class in std::iter::once(&class_name).chain(superclasses) {
+            return match class.as_str() {
+                "android.database.sqlite.SQLiteDatabaseLockedException"
+                | "android.os.DeadObjectException" => Self::Busy { message, exception },
+                "java.lang.SecurityException" => Self::PermissionDenied { message, exception },
+                "java.lang.IllegalArgumentException" => {
+                    Self::ValidationError { message, exception }
+                }
+                "android.database.sqlite.SQLiteException" => {
+                    Self::DatabaseError { message, exception }
+                }
+                "java.io.FileNotFoundException" => Self::IoError { message, exception },
+                _ => continue,
+            };
+        }
+
+        Self::JniError(message)
+    }
+
+    /// Get the Java exception this error was mapped from, if any
+    pub fn java_exception(&self) -> Option<&JavaException> {
+        match self {
+            Self::PermissionDenied { exception, .. }
+            | Self::DatabaseError { exception, .. }
+            | Self::ValidationError { exception, .. }
+            | Self::IoError { exception, .. }
+            | Self::Busy { exception, .. } => exception.as_ref(),
+            _ => None,
+        }
+    }
+
     /// Check if this error is due to AnkiDroid not being available
     pub fn is_ankidroid_unavailable(&self) -> bool {
         matches!(self, Self::AnkiDroidNotAvailable(_))
@@ -134,7 +242,7 @@ impl AnkiDroidError {
 
     /// Check if this error is due to permission denial
     pub fn is_permission_denied(&self) -> bool {
-        matches!(self, Self::PermissionDenied(_))
+        matches!(self, Self::PermissionDenied { .. })
     }
 
     /// Check if this error is due to an invalid ID
@@ -148,27 +256,28 @@ impl AnkiDroidError {
     }
 
     /// Check if this error is recoverable (can be retried)
+    ///
+    /// Only [`AnkiDroidError::Busy`] is: constraint violations, unexpected
+    /// exceptions and other failures will fail the same way again.
     pub fn is_recoverable(&self) -> bool {
-        matches!(
-            self,
-            Self::DatabaseError(_) | Self::IoError(_) | Self::JniError(_)
-        )
+        matches!(self, Self::Busy { .. })
     }
 
     /// Get the error category for logging/monitoring purposes
     pub fn category(&self) -> &'static str {
         match self {
             Self::AnkiDroidNotAvailable(_) => "availability",
-            Self::PermissionDenied(_) => "permission",
+            Self::PermissionDenied { .. } => "permission",
             Self::InvalidModelId(_) | Self::InvalidDeckId(_) => "validation",
             Self::DuplicateNote(_) => "duplicate",
             Self::FieldCountMismatch { .. } => "validation",
             Self::JniError(_) | Self::NullPointer(_) => "jni",
             Self::StringConversionError(_) => "conversion",
-            Self::DatabaseError(_) => "database",
-            Self::ValidationError(_) => "validation",
-            Self::IoError(_) => "io",
+            Self::DatabaseError { .. } => "database",
+            Self::ValidationError { .. } => "validation",
+            Self::IoError { .. } => "io",
             Self::JsonError(_) => "serialization",
+            Self::Busy { .. } => "busy",
         }
     }
 }
@@ -184,7 +293,7 @@ impl From<jni::errors::Error> for AnkiDroidError {
 
 impl From<std::io::Error> for AnkiDroidError {
     fn from(err: std::io::Error) -> Self {
-        Self::IoError(err.to_string())
+        Self::io_error(err.to_string())
     }
 }
 
@@ -223,7 +332,7 @@ mod tests {
         assert!(err.is_ankidroid_unavailable());
 
         let err = AnkiDroidError::permission_denied("test");
-        assert!(matches!(err, AnkiDroidError::PermissionDenied(_)));
+        assert!(matches!(err, AnkiDroidError::PermissionDenied { .. }));
         assert!(err.is_permission_denied());
 
         let err = AnkiDroidError::invalid_model_id(123);
@@ -244,6 +353,10 @@ mod tests {
     fn test_error_categorization() {
         let err = AnkiDroidError::database_error("test");
         assert_eq!(err.category(), "database");
+        assert!(!err.is_recoverable());
+
+        let err = AnkiDroidError::busy("database is locked");
+        assert_eq!(err.category(), "busy");
         assert!(err.is_recoverable());
 
         let err = AnkiDroidError::permission_denied("test");
@@ -253,16 +366,86 @@ mod tests {
         let err = AnkiDroidError::jni_error("test");
         assert_eq!(err.category(), "jni");
         assert!(err.is_jni_error());
-        assert!(err.is_recoverable());
+        assert!(!err.is_recoverable());
     }
 
     #[test]
-    fn test_error_display() {
-        let err = AnkiDroidError::field_count_mismatch(3, 2);
+    fn test_from_java_exception() {
+        let exception = |class: &str| JavaException {
+            class_name: class.to_string(),
+            message: Some("denied".to_string()),
+            stack_trace: vec![
+                "com.ichi2.anki.provider.CardContentProvider.query(CardContentProvider.java:42)"
+                    .to_string(),
+            ],
+        };
+
+        let err =
+            AnkiDroidError::from_java_exception(exception("java.lang.SecurityException"), &[]);
+        assert!(err.is_permission_denied());
         assert_eq!(
             err.to_string(),
-            "Field count mismatch: expected 3, got 2"
+            "Permission denied: java.lang.SecurityException: denied"
+        );
+        let java = err.java_exception().unwrap();
+        assert_eq!(java.class_name, "java.lang.SecurityException");
+        assert_eq!(java.stack_trace.len(), 1);
+
+        // Subclasses map like the class they extend
+        let superclasses = [
+            "android.database.sqlite.SQLiteException".to_string(),
+            "android.database.SQLException".to_string(),
+            "java.lang.RuntimeException".to_string(),
+        ];
+        let err = AnkiDroidError::from_java_exception(
+            exception("android.database.sqlite.SQLiteConstraintException"),
+            &superclasses,
+        );
+        assert_eq!(err.category(), "database");
+        assert!(!err.is_recoverable());
+
+        // A locked database is worth retrying, unlike the SQLiteException it extends
+        let err = AnkiDroidError::from_java_exception(
+            exception("android.database.sqlite.SQLiteDatabaseLockedException"),
+            &superclasses,
+        );
+        assert_eq!(err.category(), "busy");
+        assert!(err.is_recoverable());
+        assert!(err.java_exception().is_some());
+
+        let err = AnkiDroidError::from_java_exception(
+            exception("java.lang.IllegalArgumentException"),
+            &[],
         );
+        assert_eq!(err.category(), "validation");
+        let err =
+            AnkiDroidError::from_java_exception(exception("java.io.FileNotFoundException"), &[]);
+        assert_eq!(err.category(), "io");
+
+        // Unmapped classes stay generic JNI errors, with the class in the message
+        let err = AnkiDroidError::from_java_exception(
+            JavaException {
+                class_name: "java.lang.NullPointerException".to_string(),
+                message: None,
+                stack_trace: Vec::new(),
+            },
+            &["java.lang.RuntimeException".to_string()],
+        );
+        assert!(
+            matches!(err, AnkiDroidError::JniError(ref msg) if msg == "java.lang.NullPointerException")
+        );
+        assert!(err.java_exception().is_none());
+        assert!(!err.is_recoverable());
+
+        assert!(AnkiDroidError::database_error("locked")
+            .java_exception()
+            .is_none());
+    }
+
+    #[test]
+    fn test_error_display() {
+        let err = AnkiDroidError::field_count_mismatch(3, 2);
+        assert_eq!(err.to_string(), "Field count mismatch: expected 3, got 2");
 
         let err = AnkiDroidError::invalid_model_id(123);
         assert_eq!(err.to_string(), "Invalid model ID: 123");
@@ -288,10 +471,14 @@ mod tests {
         assert!(matches!(converted, AnkiDroidError::JsonError(_)));
 
         // Test UTF-8 error conversion
-        let utf8_err = std::str::from_utf8(&[0xFF, 0xFE]);
+        let invalid_bytes = vec![0xFF, 0xFE];
+        let utf8_err = std::str::from_utf8(&invalid_bytes);
         assert!(utf8_err.is_err());
         let converted: AnkiDroidError = utf8_err.unwrap_err().into();
-        assert!(matches!(converted, AnkiDroidError::StringConversionError(_)));
+        assert!(matches!(
+            converted,
+            AnkiDroidError::StringConversionError(_)
+        ));
     }
 
     #[test]
@@ -303,8 +490,8 @@ mod tests {
         let result = example_function();
         assert!(result.is_err());
         match result {
-            Err(AnkiDroidError::ValidationError(msg)) => assert_eq!(msg, "test"),
+            Err(AnkiDroidError::ValidationError { message, .. }) => assert_eq!(message, "test"),
             _ => panic!("Wrong error type"),
         }
     }
-}
\ No newline at end of file
+}
@@ -10,13 +10,20 @@
 
 #[cfg(target_os = "android")]
 use crate::{
+    api::AnkiDroidApi,
+    changes::{self, NoteChanges},
+    contract::{self, card, card_template, deck, model, note},
+    deck_options::DeckDetails,
+    deck_tree::{DeckCounts, DeckTree},
     error::{AnkiDroidError, Result},
+    jni::cursor::Cursor,
     jni::helpers::ContentValuesBuilder,
-    models::Note,
-    contract::{self, note},
+    models::{Card, CardTemplate, Deck, ModelDetails, Note},
+    provisioning::{DriftPolicy, EnsureModelOutcome, ModelDrift, ModelSpec},
     utils::{join_fields, split_fields, split_tags},
-    api::AnkiDroidApi,
 };
+#[cfg(target_os = "android")]
+use std::collections::HashSet;
 
 /// /// EXTENDED API: Extended trait providing additional AnkiDroid API functionality
 ///
@@ -28,6 +35,13 @@ use crate::{
 /// - **List Notes**: Query and retrieve notes with their field data and tags
 /// - **Update Notes**: Modify existing note fields while preserving metadata
 /// - **Delete Notes**: Remove notes and their associated cards from the database
+/// - **Card Retrieval**: Read the question/answer HTML AnkiDroid renders for a note's cards
+/// - **Card Templates**: List, read, update and add the card templates of a note type
+/// - **Model Provisioning**: Create note types from a declarative spec and reconcile drift
+/// - **Deck Tree**: Read decks with their study counts, nested by `::` names
+/// - **Deck Options**: Read a deck's description, filtered flag and typed options
+/// - **Selection**: Read and change AnkiDroid's selected deck and read its current note type
+/// - **Change Tracking**: Find notes modified since a timestamp and notes deleted since a sync
 ///
 /// # Examples
 ///
@@ -83,6 +97,23 @@ pub trait AnkiDroidApiExtended {
     /// - `AnkiDroidError::StringConversionError` - Field parsing failed
     fn list_notes(&mut self) -> Result<Vec<Note>>;
 
+    /// /// EXTENDED API: Get a single note by ID
+    ///
+    /// # Arguments
+    ///
+    /// * `note_id` - ID of the note
+    ///
+    /// # Returns
+    ///
+    /// The note, or `None` if no note has this ID.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Database query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid values in the returned row
+    fn get_note(&mut self, note_id: i64) -> Result<Option<Note>>;
+
     /// /// EXTENDED API: Update an existing note's fields
     ///
     /// Updates the field values of an existing note identified by its ID.
@@ -144,6 +175,404 @@ pub trait AnkiDroidApiExtended {
     /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
     /// - `AnkiDroidError::ValidationError` - Invalid note ID
     fn delete_note(&mut self, note_id: i64) -> Result<bool>;
+
+    /// /// EXTENDED API: Get all cards generated from a note
+    ///
+    /// Queries the cards of a note and returns them with the question and answer
+    /// content as rendered by AnkiDroid, including the simplified and pure variants.
+    ///
+    /// # Arguments
+    ///
+    /// * `note_id` - The ID of the note whose cards should be returned
+    ///
+    /// # Returns
+    ///
+    /// A vector of Card structs ordered by template ordinal. The vector is empty
+    /// if the note has no cards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let cards = api.get_cards_for_note(12345)?;
+    /// for card in cards {
+    ///     println!("{} ({}): {}", card.name, card.ord, card.question_simple);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid values in the returned rows
+    fn get_cards_for_note(&mut self, note_id: i64) -> Result<Vec<Card>>;
+
+    /// /// EXTENDED API: Get a single card of a note by template ordinal
+    ///
+    /// # Arguments
+    ///
+    /// * `note_id` - The ID of the note the card belongs to
+    /// * `ord` - The ordinal of the card template that generated the card
+    ///
+    /// # Returns
+    ///
+    /// The rendered Card, or `None` if the note has no card with that ordinal.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// if let Some(card) = api.get_card(12345, 0)? {
+    ///     println!("Question: {}", card.question);
+    ///     println!("Answer: {}", card.answer_pure);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid values in the returned row
+    fn get_card(&mut self, note_id: i64, ord: i32) -> Result<Option<Card>>;
+
+    /// /// EXTENDED API: Get the decks a note's cards are in
+    ///
+    /// Only the deck column is requested, so AnkiDroid does not render the
+    /// cards. Use this instead of [`get_cards_for_note`](Self::get_cards_for_note)
+    /// when listing many notes.
+    ///
+    /// # Arguments
+    ///
+    /// * `note_id` - The ID of the note
+    ///
+    /// # Returns
+    ///
+    /// The deck IDs, sorted and without duplicates. Empty if the note has no cards.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid deck ID in the returned rows
+    fn get_note_deck_ids(&mut self, note_id: i64) -> Result<Vec<i64>>;
+
+    /// /// EXTENDED API: List the card templates of a note type
+    ///
+    /// # Arguments
+    ///
+    /// * `model_id` - The ID of the model whose templates should be returned
+    ///
+    /// # Returns
+    ///
+    /// A vector of CardTemplate structs ordered by template ordinal.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// for template in api.list_templates(model_id)? {
+    ///     println!("{}: {}", template.ord, template.name);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed or the model does not exist
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid values in the returned rows
+    fn list_templates(&mut self, model_id: i64) -> Result<Vec<CardTemplate>>;
+
+    /// /// EXTENDED API: Get a single card template of a note type
+    ///
+    /// # Arguments
+    ///
+    /// * `model_id` - The ID of the model the template belongs to
+    /// * `ord` - The ordinal of the template
+    ///
+    /// # Returns
+    ///
+    /// The CardTemplate, or `None` if the model has no template with that ordinal.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed or the model does not exist
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid values in the returned row
+    fn get_template(&mut self, model_id: i64, ord: i32) -> Result<Option<CardTemplate>>;
+
+    /// /// EXTENDED API: Update the formats of an existing card template
+    ///
+    /// Only the formats passed as `Some` are changed; the others keep their
+    /// current value. AnkiDroid regenerates the affected cards on its side.
+    ///
+    /// # Arguments
+    ///
+    /// * `model_id` - The ID of the model the template belongs to
+    /// * `ord` - The ordinal of the template to update
+    /// * `qfmt` - New question format
+    /// * `afmt` - New answer format
+    /// * `bqfmt` - New browser question format
+    /// * `bafmt` - New browser answer format
+    ///
+    /// # Returns
+    ///
+    /// `true` if AnkiDroid reported the template as updated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// api.update_template(model_id, 0, Some("{{Front}}"), Some("{{FrontSide}}<hr id=answer>{{Back}}"), None, None)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::ValidationError` - No format was given
+    /// - `AnkiDroidError::DatabaseError` - Update rejected by AnkiDroid
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    fn update_template(
+        &mut self,
+        model_id: i64,
+        ord: i32,
+        qfmt: Option<&str>,
+        afmt: Option<&str>,
+        bqfmt: Option<&str>,
+        bafmt: Option<&str>,
+    ) -> Result<bool>;
+
+    /// /// EXTENDED API: Add a new card template to a note type
+    ///
+    /// The template is appended after the existing ones. Older AnkiDroid versions
+    /// do not support inserting templates through the content provider; in that
+    /// case the insert is rejected and the error is returned unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `model_id` - The ID of the model to add the template to
+    /// * `name` - Name of the new template
+    /// * `qfmt` - Question format
+    /// * `afmt` - Answer format
+    ///
+    /// # Returns
+    ///
+    /// The ordinal assigned to the new template.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let ord = api.add_template(model_id, "Reverse", "{{Back}}", "{{FrontSide}}<hr id=answer>{{Front}}")?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::ValidationError` - Empty name or formats
+    /// - `AnkiDroidError::DatabaseError` - Insert rejected by AnkiDroid
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    fn add_template(&mut self, model_id: i64, name: &str, qfmt: &str, afmt: &str) -> Result<i32>;
+
+    /// /// EXTENDED API: Make sure a note type matching a spec exists
+    ///
+    /// Looks for a model with the spec's name, preferring one whose fields match
+    /// exactly. If none exists it is created with `add_new_custom_model`. If one
+    /// exists, its fields, CSS and templates are compared with the spec and the
+    /// drift is reported, or applied when `policy` is `DriftPolicy::Apply`. Field
+    /// differences and extra templates are reported but never changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `spec` - The declarative description of the note type
+    /// * `policy` - Whether drift is only reported or also applied
+    ///
+    /// # Returns
+    ///
+    /// The model ID along with whether it was created and what drift was found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let spec = ModelSpec::new("Vocab", vec!["Word".into(), "Meaning".into()])
+    ///     .template("Card 1", "{{Word}}", "{{FrontSide}}<hr id=answer>{{Meaning}}");
+    /// let outcome = api.ensure_model(&spec, DriftPolicy::Apply)?;
+    /// println!("Using model {} (created: {})", outcome.model_id, outcome.created);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::ValidationError` - The spec is invalid
+    /// - `AnkiDroidError::DatabaseError` - Query, insert or update failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    fn ensure_model(&mut self, spec: &ModelSpec, policy: DriftPolicy)
+        -> Result<EnsureModelOutcome>;
+
+    /// /// EXTENDED API: List all decks with their description and study counts
+    ///
+    /// # Returns
+    ///
+    /// A vector of Deck structs sorted by name. Counts that AnkiDroid does not
+    /// report are left at zero.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid deck ID in the returned rows
+    fn list_decks(&mut self) -> Result<Vec<Deck>>;
+
+    /// /// EXTENDED API: Get all decks nested by their `::`-separated names
+    ///
+    /// Each node carries the deck's own counts and the totals rolled up from
+    /// its subdecks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let tree = api.get_deck_tree()?;
+    /// for root in &tree.roots {
+    ///     println!("{}: {} new", root.name, root.totals.new);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`list_decks`](Self::list_decks).
+    fn get_deck_tree(&mut self) -> Result<DeckTree>;
+
+    /// /// EXTENDED API: Get a deck's description, filtered flag and typed options
+    ///
+    /// The details are read only; AnkiDroid's provider rejects updates to a
+    /// deck other than selecting it.
+    ///
+    /// # Arguments
+    ///
+    /// * `deck_id` - The ID of the deck
+    ///
+    /// # Returns
+    ///
+    /// The deck's details, or `None` if no deck has that ID.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::JsonError` - The options column is not a JSON object
+    fn get_deck_details(&mut self, deck_id: i64) -> Result<Option<DeckDetails>>;
+
+    /// /// EXTENDED API: Get the deck currently selected in AnkiDroid
+    ///
+    /// # Returns
+    ///
+    /// The selected deck with its counts, or `None` if AnkiDroid reports none.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid deck ID in the returned row
+    fn get_selected_deck(&mut self) -> Result<Option<Deck>>;
+
+    /// /// EXTENDED API: Select a deck in AnkiDroid
+    ///
+    /// This is the deck AnkiDroid treats as current, as if the user had picked it
+    /// in the deck list. It does not open AnkiDroid or start a review.
+    ///
+    /// # Arguments
+    ///
+    /// * `deck_id` - The ID of the deck to select
+    ///
+    /// # Returns
+    ///
+    /// `true` if AnkiDroid changed the selection.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Update failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    fn set_selected_deck(&mut self, deck_id: i64) -> Result<bool>;
+
+    /// /// EXTENDED API: Get the note type AnkiDroid currently uses for new notes
+    ///
+    /// # Returns
+    ///
+    /// The current model, or `None` if AnkiDroid reports none.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid values in the returned row
+    fn get_current_model(&mut self) -> Result<Option<ModelDetails>>;
+
+    /// /// EXTENDED API: Get a note type with its field names, CSS, sort field and note count
+    ///
+    /// # Arguments
+    ///
+    /// * `model_id` - ID of the note type
+    ///
+    /// # Returns
+    ///
+    /// The model details, or `None` if no note type has this ID.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid values in the returned row
+    fn get_model_details(&mut self, model_id: i64) -> Result<Option<ModelDetails>>;
+
+    /// /// EXTENDED API: List every note type with its details
+    ///
+    /// # Returns
+    ///
+    /// The note types sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    /// - `AnkiDroidError::ValidationError` - Invalid values in a returned row
+    fn list_models(&mut self) -> Result<Vec<ModelDetails>>;
+
+    /// /// EXTENDED API: List the notes modified at or after a timestamp
+    ///
+    /// The notes provider cannot filter by modification time, so every note is
+    /// read and filtered here.
+    ///
+    /// # Arguments
+    ///
+    /// * `mod_ts` - Modification time in seconds since the epoch
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Database query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    fn notes_changed_since(&mut self, mod_ts: i64) -> Result<Vec<Note>>;
+
+    /// /// EXTENDED API: Find which of the given note IDs no longer exist
+    ///
+    /// # Arguments
+    ///
+    /// * `known_ids` - IDs of the notes the caller knows about
+    ///
+    /// # Returns
+    ///
+    /// The deleted IDs, sorted.
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Database query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    fn deleted_note_ids(&mut self, known_ids: &[i64]) -> Result<Vec<i64>>;
+
+    /// /// EXTENDED API: Get the notes created, updated and deleted since a previous sync
+    ///
+    /// Reads the collection once and sorts it with [`NoteChanges::from_notes`].
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - Modification time of the previous sync in seconds since the epoch
+    /// * `known_ids` - IDs of the notes the caller knows about
+    ///
+    /// # Errors
+    ///
+    /// - `AnkiDroidError::DatabaseError` - Database query failed
+    /// - `AnkiDroidError::PermissionDenied` - Insufficient permissions
+    fn note_changes(&mut self, since: i64, known_ids: &[i64]) -> Result<NoteChanges>;
 }
 
 #[cfg(target_os = "android")]
@@ -154,19 +583,7 @@ impl<'local> AnkiDroidApiExtended for AnkiDroidApi<'local> {
         // Query the notes content provider
         let mut cursor = self.resolver_mut().query(
             &contract::build_note_uri(),
-            Some(vec![
-                note::_ID.to_string(),
-                note::GUID.to_string(),
-                note::MID.to_string(),
-                note::MOD.to_string(),
-                note::USN.to_string(),
-                note::TAGS.to_string(),
-                note::FLDS.to_string(),
-                note::SFLD.to_string(),
-                note::CSUM.to_string(),
-                note::FLAGS.to_string(),
-                note::DATA.to_string(),
-            ]),
+            Some(note_projection()),
             None,
             None,
             None,
@@ -175,70 +592,33 @@ impl<'local> AnkiDroidApiExtended for AnkiDroidApi<'local> {
         let mut notes = Vec::new();
 
         while cursor.move_to_next()? {
-            // Extract note data from cursor
-            let id_str = cursor.get_string_by_name(note::_ID)?;
-            let id = id_str.parse::<i64>()
-                .map_err(|_| AnkiDroidError::validation_error("Invalid note ID in database"))?;
-
-            let guid = cursor.get_string_by_name(note::GUID)?;
-            
-            let mid_str = cursor.get_string_by_name(note::MID)?;
-            let mid = mid_str.parse::<i64>()
-                .map_err(|_| AnkiDroidError::validation_error("Invalid model ID in database"))?;
-
-            let mod_str = cursor.get_string_by_name(note::MOD)?;
-            let mod_ = mod_str.parse::<i64>()
-                .map_err(|_| AnkiDroidError::validation_error("Invalid modification time in database"))?;
-
-            let usn_str = cursor.get_string_by_name(note::USN)?;
-            let usn = usn_str.parse::<i32>()
-                .map_err(|_| AnkiDroidError::validation_error("Invalid USN in database"))?;
-
-            let tags_str = cursor.get_string_by_name(note::TAGS)?;
-            let tags = if tags_str.trim().is_empty() {
-                Vec::new()
-            } else {
-                split_tags(&tags_str)
-            };
-
-            let flds_str = cursor.get_string_by_name(note::FLDS)?;
-            let fields = split_fields(&flds_str);
-
-            let sfld = cursor.get_string_by_name(note::SFLD)?;
-
-            let csum_str = cursor.get_string_by_name(note::CSUM)?;
-            let csum = csum_str.parse::<i64>()
-                .map_err(|_| AnkiDroidError::validation_error("Invalid checksum in database"))?;
-
-            let flags_str = cursor.get_string_by_name(note::FLAGS)?;
-            let flags = flags_str.parse::<i32>()
-                .map_err(|_| AnkiDroidError::validation_error("Invalid flags in database"))?;
-
-            let data = cursor.get_string_by_name(note::DATA)?;
-
-            // Create Note struct
-            let note = Note {
-                id,
-                guid,
-                mid,
-                mod_,
-                usn,
-                tags,
-                fields,
-                sfld,
-                csum,
-                flags,
-                data,
-            };
-
-            notes.push(note);
+            notes.push(read_note(&mut cursor)?);
         }
 
         log::info!("✅ Retrieved {} notes from database", notes.len());
         Ok(notes)
     }
 
-    fn update_note(&mut self, note_id: i64, fields: &[&str]) -> Result<()> {
+    fn get_note(&mut self, note_id: i64) -> Result<Option<Note>> {
+        log::info!("Querying note {}", note_id);
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_note_by_id_uri(note_id),
+            Some(note_projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        if !cursor.move_to_next()? {
+            log::warn!("Note {} not found", note_id);
+            return Ok(None);
+        }
+
+        Ok(Some(read_note(&mut cursor)?))
+    }
+
+    fn update_note(&mut self, note_id: i64, fields: &[&str]) -> Result<()> {
         log::info!("Updating note {} with {} fields", note_id, fields.len());
 
         // Validate inputs
@@ -258,10 +638,15 @@ impl<'local> AnkiDroidApiExtended for AnkiDroidApi<'local> {
             // Update sort field to first field
             .put_string(note::SFLD, fields.first().unwrap_or(&""))?
             // Update modification time (Unix timestamp)
-            .put_long(note::MOD, std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map_err(|_| AnkiDroidError::validation_error("Failed to get current timestamp"))?
-                .as_secs() as i64)?
+            .put_long(
+                note::MOD,
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_err(|_| {
+                        AnkiDroidError::validation_error("Failed to get current timestamp")
+                    })?
+                    .as_secs() as i64,
+            )?
             // Mark as modified for sync
             .put_int(note::USN, -1)?;
 
@@ -269,9 +654,10 @@ impl<'local> AnkiDroidApiExtended for AnkiDroidApi<'local> {
         let updated_count = self.resolver_mut().update(&note_uri, values, None, None)?;
 
         if updated_count == 0 {
-            return Err(AnkiDroidError::validation_error(
-                format!("Note with ID {} not found or could not be updated", note_id)
-            ));
+            return Err(AnkiDroidError::validation_error(format!(
+                "Note with ID {} not found or could not be updated",
+                note_id
+            )));
         }
 
         log::info!("✅ Note {} updated successfully", note_id);
@@ -288,7 +674,7 @@ impl<'local> AnkiDroidApiExtended for AnkiDroidApi<'local> {
         let deleted_count = self.resolver_mut().delete(&note_uri, None, None)?;
 
         let success = deleted_count > 0;
-        
+
         if success {
             log::info!("✅ Note {} deleted successfully", note_id);
         } else {
@@ -297,6 +683,860 @@ impl<'local> AnkiDroidApiExtended for AnkiDroidApi<'local> {
 
         Ok(success)
     }
+
+    fn get_cards_for_note(&mut self, note_id: i64) -> Result<Vec<Card>> {
+        log::info!("Querying cards for note {}", note_id);
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_cards_for_note_uri(note_id),
+            Some(card_projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        let mut cards = Vec::new();
+
+        while cursor.move_to_next()? {
+            cards.push(read_card(&mut cursor)?);
+        }
+
+        cards.sort_by_key(|c| c.ord);
+
+        log::info!("✅ Retrieved {} cards for note {}", cards.len(), note_id);
+        Ok(cards)
+    }
+
+    fn get_note_deck_ids(&mut self, note_id: i64) -> Result<Vec<i64>> {
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_cards_for_note_uri(note_id),
+            Some(vec![card::DECK_ID.to_string()]),
+            None,
+            None,
+            None,
+        )?;
+
+        let mut deck_ids = Vec::new();
+
+        while cursor.move_to_next()? {
+            let deck_id = cursor
+                .get_string_by_name(card::DECK_ID)?
+                .parse::<i64>()
+                .map_err(|_| AnkiDroidError::validation_error("Invalid deck ID in card row"))?;
+            deck_ids.push(deck_id);
+        }
+
+        deck_ids.sort_unstable();
+        deck_ids.dedup();
+        Ok(deck_ids)
+    }
+
+    fn get_card(&mut self, note_id: i64, ord: i32) -> Result<Option<Card>> {
+        log::info!("Querying card {} of note {}", ord, note_id);
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_specific_card_uri(note_id, &ord.to_string()),
+            Some(card_projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        if !cursor.move_to_next()? {
+            log::warn!("Card {} of note {} not found", ord, note_id);
+            return Ok(None);
+        }
+
+        Ok(Some(read_card(&mut cursor)?))
+    }
+
+    fn list_templates(&mut self, model_id: i64) -> Result<Vec<CardTemplate>> {
+        log::info!("Querying templates of model {}", model_id);
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_templates_uri(model_id),
+            Some(template_projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        let mut templates = Vec::new();
+
+        while cursor.move_to_next()? {
+            templates.push(read_template(&mut cursor)?);
+        }
+
+        templates.sort_by_key(|t| t.ord);
+
+        log::info!(
+            "✅ Retrieved {} templates of model {}",
+            templates.len(),
+            model_id
+        );
+        Ok(templates)
+    }
+
+    fn get_template(&mut self, model_id: i64, ord: i32) -> Result<Option<CardTemplate>> {
+        log::info!("Querying template {} of model {}", ord, model_id);
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_template_uri(model_id, ord),
+            Some(template_projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        if !cursor.move_to_next()? {
+            log::warn!("Template {} of model {} not found", ord, model_id);
+            return Ok(None);
+        }
+
+        Ok(Some(read_template(&mut cursor)?))
+    }
+
+    fn update_template(
+        &mut self,
+        model_id: i64,
+        ord: i32,
+        qfmt: Option<&str>,
+        afmt: Option<&str>,
+        bqfmt: Option<&str>,
+        bafmt: Option<&str>,
+    ) -> Result<bool> {
+        log::info!("Updating template {} of model {}", ord, model_id);
+
+        let formats = [
+            (card_template::QUESTION_FORMAT, qfmt),
+            (card_template::ANSWER_FORMAT, afmt),
+            (card_template::BROWSER_QUESTION_FORMAT, bqfmt),
+            (card_template::BROWSER_ANSWER_FORMAT, bafmt),
+        ];
+
+        if formats.iter().all(|(_, value)| value.is_none()) {
+            return Err(AnkiDroidError::validation_error(
+                "No template format to update",
+            ));
+        }
+
+        let mut values = ContentValuesBuilder::new(self.env_mut())?;
+        for (column, value) in formats {
+            if let Some(value) = value {
+                values = values.put_string(column, value)?;
+            }
+        }
+
+        let updated = self.resolver_mut().update(
+            &contract::build_template_uri(model_id, ord),
+            values,
+            None,
+            None,
+        )?;
+
+        if updated > 0 {
+            log::info!("✅ Template {} of model {} updated", ord, model_id);
+        } else {
+            log::warn!("Template {} of model {} was not updated", ord, model_id);
+        }
+
+        Ok(updated > 0)
+    }
+
+    fn add_template(&mut self, model_id: i64, name: &str, qfmt: &str, afmt: &str) -> Result<i32> {
+        log::info!("Adding template '{}' to model {}", name, model_id);
+
+        if name.trim().is_empty() {
+            return Err(AnkiDroidError::validation_error(
+                "Template name cannot be empty",
+            ));
+        }
+
+        if qfmt.trim().is_empty() || afmt.trim().is_empty() {
+            return Err(AnkiDroidError::validation_error(
+                "Template formats cannot be empty",
+            ));
+        }
+
+        let values = ContentValuesBuilder::new(self.env_mut())?
+            .put_string(card_template::NAME, name)?
+            .put_string(card_template::QUESTION_FORMAT, qfmt)?
+            .put_string(card_template::ANSWER_FORMAT, afmt)?;
+
+        let template_uri = self
+            .resolver_mut()
+            .insert(&contract::build_templates_uri(model_id), values)?;
+
+        let ord = template_uri
+            .rsplit('/')
+            .next()
+            .and_then(|segment| segment.parse::<i32>().ok())
+            .ok_or_else(|| {
+                AnkiDroidError::validation_error(format!(
+                    "Invalid template URI returned: {}",
+                    template_uri
+                ))
+            })?;
+
+        log::info!(
+            "✅ Template '{}' added to model {} with ord {}",
+            name,
+            model_id,
+            ord
+        );
+        Ok(ord)
+    }
+
+    fn ensure_model(
+        &mut self,
+        spec: &ModelSpec,
+        policy: DriftPolicy,
+    ) -> Result<EnsureModelOutcome> {
+        spec.validate()?;
+
+        log::info!(
+            "Ensuring model '{}' with {} fields",
+            spec.name,
+            spec.fields.len()
+        );
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_models_uri(),
+            Some(vec![
+                model::_ID.to_string(),
+                model::NAME.to_string(),
+                model::FIELD_NAMES.to_string(),
+                model::CSS.to_string(),
+            ]),
+            None,
+            None,
+            None,
+        )?;
+
+        let mut existing = None;
+
+        while cursor.move_to_next()? {
+            let name = cursor.get_string_by_name(model::NAME)?;
+            let field_names = split_fields(&cursor.get_string_by_name(model::FIELD_NAMES)?);
+
+            if name != spec.name {
+                continue;
+            }
+
+            let exact = spec.matches_signature(&name, &field_names);
+            if existing.is_none() || exact {
+                let id = cursor
+                    .get_string_by_name(model::_ID)?
+                    .parse::<i64>()
+                    .map_err(|_| {
+                        AnkiDroidError::validation_error("Invalid model ID in database")
+                    })?;
+                let css = cursor.get_string_by_name(model::CSS)?;
+                existing = Some((id, field_names, css));
+            }
+
+            if exact {
+                break;
+            }
+        }
+
+        drop(cursor);
+
+        let Some((model_id, field_names, css)) = existing else {
+            let fields: Vec<&str> = spec.fields.iter().map(|f| f.as_str()).collect();
+            let names: Vec<&str> = spec.templates.iter().map(|t| t.name.as_str()).collect();
+            let qfmt: Vec<&str> = spec.templates.iter().map(|t| t.qfmt.as_str()).collect();
+            let afmt: Vec<&str> = spec.templates.iter().map(|t| t.afmt.as_str()).collect();
+
+            let model_id = self
+                .add_new_custom_model(
+                    &spec.name,
+                    &fields,
+                    &names,
+                    &qfmt,
+                    &afmt,
+                    spec.css.as_deref(),
+                    None,
+                    Some(spec.sort_field),
+                )?
+                .ok_or_else(|| {
+                    AnkiDroidError::database_error(format!(
+                        "Failed to create model '{}'",
+                        spec.name
+                    ))
+                })?;
+
+            if spec.cloze {
+                // The models insert ignores the type, so mark cloze models afterwards
+                let values = ContentValuesBuilder::new(self.env_mut())?.put_int(model::TYPE, 1)?;
+                let updated = self.resolver_mut().update(
+                    &contract::build_model_by_id_uri(model_id),
+                    values,
+                    None,
+                    None,
+                )?;
+                if updated == 0 {
+                    return Err(AnkiDroidError::database_error(format!(
+                        "Failed to mark model {} as cloze",
+                        model_id
+                    )));
+                }
+            }
+
+            log::info!("✅ Model '{}' created with ID {}", spec.name, model_id);
+            return Ok(EnsureModelOutcome {
+                model_id,
+                created: true,
+                drift: Vec::new(),
+                applied: false,
+            });
+        };
+
+        let templates = self.list_templates(model_id)?;
+        let drift = spec.diff(&field_names, &css, &templates);
+
+        if drift.is_empty() {
+            log::info!("✅ Model '{}' ({}) matches its spec", spec.name, model_id);
+        } else {
+            log::warn!(
+                "Model '{}' ({}) has drifted from its spec: {:?}",
+                spec.name,
+                model_id,
+                drift
+            );
+        }
+
+        let mut written = 0;
+
+        if policy == DriftPolicy::Apply {
+            for item in &drift {
+                match item {
+                    ModelDrift::Css { expected, .. } => {
+                        let values = ContentValuesBuilder::new(self.env_mut())?
+                            .put_string(model::CSS, expected)?;
+                        let updated = self.resolver_mut().update(
+                            &contract::build_model_by_id_uri(model_id),
+                            values,
+                            None,
+                            None,
+                        )?;
+                        if updated == 0 {
+                            return Err(AnkiDroidError::database_error(format!(
+                                "Failed to update CSS of model {}",
+                                model_id
+                            )));
+                        }
+                        written += 1;
+                    }
+                    ModelDrift::TemplateChanged { ord, .. } => {
+                        let template = &spec.templates[*ord as usize];
+                        let updated = self.update_template(
+                            model_id,
+                            *ord,
+                            Some(&template.qfmt),
+                            Some(&template.afmt),
+                            None,
+                            None,
+                        )?;
+                        if !updated {
+                            return Err(AnkiDroidError::database_error(format!(
+                                "Failed to update template {} of model {}",
+                                ord, model_id
+                            )));
+                        }
+                        written += 1;
+                    }
+                    ModelDrift::TemplateMissing { ord, .. } => {
+                        let template = &spec.templates[*ord as usize];
+                        self.add_template(
+                            model_id,
+                            &template.name,
+                            &template.qfmt,
+                            &template.afmt,
+                        )?;
+                        written += 1;
+                    }
+                    ModelDrift::Fields { .. } => {
+                        log::warn!("Leaving the fields of model {} unchanged", model_id);
+                    }
+                    ModelDrift::ExtraTemplate { ord, name } => {
+                        log::warn!(
+                            "Leaving extra template '{}' ({}) on model {}",
+                            name,
+                            ord,
+                            model_id
+                        );
+                    }
+                }
+            }
+
+            if written > 0 {
+                log::info!("✅ Applied {} drift item(s) to model {}", written, model_id);
+            }
+        }
+
+        Ok(EnsureModelOutcome {
+            model_id,
+            created: false,
+            drift,
+            applied: written > 0,
+        })
+    }
+
+    fn list_decks(&mut self) -> Result<Vec<Deck>> {
+        log::info!("Querying decks with counts");
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_decks_uri(),
+            Some(deck_projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        let mut decks = Vec::new();
+
+        while cursor.move_to_next()? {
+            decks.push(read_deck(&mut cursor)?);
+        }
+
+        decks.sort_by(|a, b| a.name.cmp(&b.name));
+
+        log::info!("✅ Retrieved {} decks", decks.len());
+        Ok(decks)
+    }
+
+    fn get_deck_tree(&mut self) -> Result<DeckTree> {
+        let decks = self.list_decks()?;
+        Ok(DeckTree::build(&decks))
+    }
+
+    fn get_deck_details(&mut self, deck_id: i64) -> Result<Option<DeckDetails>> {
+        log::info!("Querying details of deck {}", deck_id);
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_deck_by_id_uri(deck_id),
+            Some(deck_projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        if !cursor.move_to_next()? {
+            log::warn!("Deck {} not found", deck_id);
+            return Ok(None);
+        }
+
+        let deck = read_deck(&mut cursor)?;
+        Ok(Some(DeckDetails::from_deck(&deck)?))
+    }
+
+    fn get_selected_deck(&mut self) -> Result<Option<Deck>> {
+        log::info!("Querying selected deck");
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_selected_deck_uri(),
+            Some(deck_projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        if !cursor.move_to_next()? {
+            log::warn!("AnkiDroid reported no selected deck");
+            return Ok(None);
+        }
+
+        Ok(Some(read_deck(&mut cursor)?))
+    }
+
+    fn set_selected_deck(&mut self, deck_id: i64) -> Result<bool> {
+        log::info!("Selecting deck {}", deck_id);
+
+        let values = ContentValuesBuilder::new(self.env_mut())?.put_long(deck::DECK_ID, deck_id)?;
+
+        let updated =
+            self.resolver_mut()
+                .update(&contract::build_selected_deck_uri(), values, None, None)?;
+
+        if updated > 0 {
+            log::info!("✅ Deck {} selected", deck_id);
+        } else {
+            log::warn!("Deck {} was not selected", deck_id);
+        }
+
+        Ok(updated > 0)
+    }
+
+    fn get_current_model(&mut self) -> Result<Option<ModelDetails>> {
+        log::info!("Querying current model");
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_current_model_uri(),
+            Some(ModelDetails::projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        if !cursor.move_to_next()? {
+            log::warn!("AnkiDroid reported no current model");
+            return Ok(None);
+        }
+
+        Ok(Some(read_model(&mut cursor)?))
+    }
+
+    fn get_model_details(&mut self, model_id: i64) -> Result<Option<ModelDetails>> {
+        log::info!("Querying details of model {}", model_id);
+
+        if model_id <= 0 {
+            return Err(AnkiDroidError::invalid_model_id(model_id));
+        }
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_model_by_id_uri(model_id),
+            Some(ModelDetails::projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        if !cursor.move_to_next()? {
+            log::warn!("Model {} not found", model_id);
+            return Ok(None);
+        }
+
+        Ok(Some(read_model(&mut cursor)?))
+    }
+
+    fn list_models(&mut self) -> Result<Vec<ModelDetails>> {
+        log::info!("Querying all models");
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_models_uri(),
+            Some(ModelDetails::projection()),
+            None,
+            None,
+            None,
+        )?;
+
+        let mut models = Vec::new();
+        while cursor.move_to_next()? {
+            models.push(read_model(&mut cursor)?);
+        }
+
+        models.sort_by(|a, b| a.model.name.cmp(&b.model.name));
+        Ok(models)
+    }
+
+    fn notes_changed_since(&mut self, mod_ts: i64) -> Result<Vec<Note>> {
+        log::info!("Querying notes modified since {}", mod_ts);
+
+        let notes: Vec<Note> = self
+            .list_notes()?
+            .into_iter()
+            .filter(|note| changes::modified_since(note, mod_ts))
+            .collect();
+
+        log::info!("Found {} modified notes", notes.len());
+        Ok(notes)
+    }
+
+    fn deleted_note_ids(&mut self, known_ids: &[i64]) -> Result<Vec<i64>> {
+        log::info!("Checking {} known notes for deletions", known_ids.len());
+
+        let mut cursor = self.resolver_mut().query(
+            &contract::build_note_uri(),
+            Some(vec![note::_ID.to_string()]),
+            None,
+            None,
+            None,
+        )?;
+
+        let mut current = HashSet::new();
+        while cursor.move_to_next()? {
+            let id = cursor
+                .get_string_by_name(note::_ID)?
+                .parse::<i64>()
+                .map_err(|_| AnkiDroidError::validation_error("Invalid note ID in database"))?;
+            current.insert(id);
+        }
+
+        Ok(changes::deleted_ids(known_ids, &current))
+    }
+
+    fn note_changes(&mut self, since: i64, known_ids: &[i64]) -> Result<NoteChanges> {
+        log::info!(
+            "Collecting note changes since {} ({} known notes)",
+            since,
+            known_ids.len()
+        );
+
+        let changes = NoteChanges::from_notes(self.list_notes()?, since, known_ids);
+
+        log::info!(
+            "Note changes: {} created, {} updated, {} deleted",
+            changes.created.len(),
+            changes.updated.len(),
+            changes.deleted.len()
+        );
+        Ok(changes)
+    }
+}
+
+/// Columns requested for note queries
+#[cfg(target_os = "android")]
+fn note_projection() -> Vec<String> {
+    [
+        note::_ID,
+        note::GUID,
+        note::MID,
+        note::MOD,
+        note::USN,
+        note::TAGS,
+        note::FLDS,
+        note::SFLD,
+        note::CSUM,
+        note::FLAGS,
+        note::DATA,
+    ]
+    .iter()
+    .map(|column| column.to_string())
+    .collect()
+}
+
+/// Read the note at the cursor's current row
+#[cfg(target_os = "android")]
+fn read_note(cursor: &mut Cursor<'_>) -> Result<Note> {
+    let id_str = cursor.get_string_by_name(note::_ID)?;
+    let id = id_str
+        .parse::<i64>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid note ID in database"))?;
+
+    let guid = cursor.get_string_by_name(note::GUID)?;
+
+    let mid_str = cursor.get_string_by_name(note::MID)?;
+    let mid = mid_str
+        .parse::<i64>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid model ID in database"))?;
+
+    let mod_str = cursor.get_string_by_name(note::MOD)?;
+    let mod_ = mod_str
+        .parse::<i64>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid modification time in database"))?;
+
+    let usn_str = cursor.get_string_by_name(note::USN)?;
+    let usn = usn_str
+        .parse::<i32>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid USN in database"))?;
+
+    let tags_str = cursor.get_string_by_name(note::TAGS)?;
+    let tags = if tags_str.trim().is_empty() {
+        Vec::new()
+    } else {
+        split_tags(&tags_str)
+    };
+
+    let flds_str = cursor.get_string_by_name(note::FLDS)?;
+    let fields = split_fields(&flds_str);
+
+    let sfld = cursor.get_string_by_name(note::SFLD)?;
+
+    let csum_str = cursor.get_string_by_name(note::CSUM)?;
+    let csum = csum_str
+        .parse::<i64>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid checksum in database"))?;
+
+    let flags_str = cursor.get_string_by_name(note::FLAGS)?;
+    let flags = flags_str
+        .parse::<i32>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid flags in database"))?;
+
+    let data = cursor.get_string_by_name(note::DATA)?;
+
+    Ok(Note {
+        id,
+        guid,
+        mid,
+        mod_,
+        usn,
+        tags,
+        fields,
+        sfld,
+        csum,
+        flags,
+        data,
+    })
+}
+
+/// Columns requested for card queries, including all rendered variants
+#[cfg(target_os = "android")]
+fn card_projection() -> Vec<String> {
+    [
+        card::NOTE_ID,
+        card::CARD_ORD,
+        card::CARD_NAME,
+        card::DECK_ID,
+        card::QUESTION,
+        card::ANSWER,
+        card::QUESTION_SIMPLE,
+        card::ANSWER_SIMPLE,
+        card::ANSWER_PURE,
+    ]
+    .iter()
+    .map(|column| column.to_string())
+    .collect()
+}
+
+/// Read the card at the cursor's current row
+#[cfg(target_os = "android")]
+fn read_card(cursor: &mut Cursor<'_>) -> Result<Card> {
+    let note_id = cursor
+        .get_string_by_name(card::NOTE_ID)?
+        .parse::<i64>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid note ID in card row"))?;
+
+    let ord = cursor
+        .get_string_by_name(card::CARD_ORD)?
+        .parse::<i32>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid card ordinal in card row"))?;
+
+    let deck_id = cursor
+        .get_string_by_name(card::DECK_ID)?
+        .parse::<i64>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid deck ID in card row"))?;
+
+    Ok(Card {
+        note_id,
+        ord,
+        name: cursor.get_string_by_name(card::CARD_NAME)?,
+        deck_id,
+        question: cursor.get_string_by_name(card::QUESTION)?,
+        answer: cursor.get_string_by_name(card::ANSWER)?,
+        question_simple: cursor.get_string_by_name(card::QUESTION_SIMPLE)?,
+        answer_simple: cursor.get_string_by_name(card::ANSWER_SIMPLE)?,
+        answer_pure: cursor.get_string_by_name(card::ANSWER_PURE)?,
+    })
+}
+
+/// Columns requested for card template queries
+#[cfg(target_os = "android")]
+fn template_projection() -> Vec<String> {
+    [
+        card_template::_ID,
+        card_template::MODEL_ID,
+        card_template::ORD,
+        card_template::NAME,
+        card_template::QUESTION_FORMAT,
+        card_template::ANSWER_FORMAT,
+        card_template::BROWSER_QUESTION_FORMAT,
+        card_template::BROWSER_ANSWER_FORMAT,
+        card_template::CARD_COUNT,
+    ]
+    .iter()
+    .map(|column| column.to_string())
+    .collect()
+}
+
+/// Read the card template at the cursor's current row
+#[cfg(target_os = "android")]
+fn read_template(cursor: &mut Cursor<'_>) -> Result<CardTemplate> {
+    let id = cursor
+        .get_string_by_name(card_template::_ID)?
+        .parse::<i64>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid template ID in template row"))?;
+
+    let model_id = cursor
+        .get_string_by_name(card_template::MODEL_ID)?
+        .parse::<i64>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid model ID in template row"))?;
+
+    let ord = cursor
+        .get_string_by_name(card_template::ORD)?
+        .parse::<i32>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid ordinal in template row"))?;
+
+    // The card count is informational; older providers may leave it empty
+    let card_count = cursor
+        .get_string_by_name(card_template::CARD_COUNT)?
+        .parse::<i32>()
+        .unwrap_or(0);
+
+    Ok(CardTemplate {
+        id,
+        model_id,
+        ord,
+        name: cursor.get_string_by_name(card_template::NAME)?,
+        question_format: cursor.get_string_by_name(card_template::QUESTION_FORMAT)?,
+        answer_format: cursor.get_string_by_name(card_template::ANSWER_FORMAT)?,
+        browser_question_format: cursor
+            .get_string_by_name(card_template::BROWSER_QUESTION_FORMAT)?,
+        browser_answer_format: cursor.get_string_by_name(card_template::BROWSER_ANSWER_FORMAT)?,
+        card_count,
+    })
+}
+
+/// Columns requested for deck queries
+#[cfg(target_os = "android")]
+fn deck_projection() -> Vec<String> {
+    [
+        deck::DECK_ID,
+        deck::DECK_NAME,
+        deck::DECK_DESC,
+        deck::DECK_COUNTS,
+        deck::OPTIONS,
+        deck::DECK_DYN,
+    ]
+    .iter()
+    .map(|column| column.to_string())
+    .collect()
+}
+
+/// Read the deck at the cursor's current row
+#[cfg(target_os = "android")]
+fn read_deck(cursor: &mut Cursor<'_>) -> Result<Deck> {
+    let id = cursor
+        .get_string_by_name(deck::DECK_ID)?
+        .parse::<i64>()
+        .map_err(|_| AnkiDroidError::validation_error("Invalid deck ID in deck row"))?;
+
+    let mut deck = Deck::new(id, cursor.get_string_by_name(deck::DECK_NAME)?);
+
+    // Counts, options and description are informational; keep the defaults
+    // rather than failing the whole listing when a provider omits them
+    let counts = cursor
+        .get_string_by_name(deck::DECK_COUNTS)
+        .unwrap_or_default();
+    match DeckCounts::from_json(&counts) {
+        Ok(parsed) => deck.counts = vec![parsed.learning, parsed.review, parsed.new],
+        Err(e) => log::warn!("Could not parse counts of deck {}: {}", id, e),
+    }
+
+    if let Ok(options) = cursor.get_string_by_name(deck::OPTIONS) {
+        if let Ok(options) = serde_json::from_str(&options) {
+            deck.options = options;
+        }
+    }
+
+    deck.desc = cursor
+        .get_string_by_name(deck::DECK_DESC)
+        .unwrap_or_default();
+    deck.dyn_ = matches!(
+        cursor
+            .get_string_by_name(deck::DECK_DYN)
+            .unwrap_or_default()
+            .as_str(),
+        "1" | "true"
+    );
+
+    Ok(deck)
+}
+
+/// Read the model at the cursor's current row
+#[cfg(target_os = "android")]
+fn read_model(cursor: &mut Cursor<'_>) -> Result<ModelDetails> {
+    ModelDetails::from_columns(|column| cursor.get_string_by_name(column).ok())
 }
 
 // Make sure we only export for Android targets
@@ -327,4 +1567,4 @@ mod tests {
 
     // Additional tests would require a mock JNI environment
     // which is complex to set up in unit tests
-}
\ No newline at end of file
+}
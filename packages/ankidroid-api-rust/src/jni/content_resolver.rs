@@ -36,12 +36,12 @@
 //! ```
 
 use crate::error::{AnkiDroidError, Result};
-use crate::jni::helpers::{ContentValuesBuilder, SafeJNIEnv, StringHelper, JniResultExt};
 use crate::jni::cursor::Cursor;
+use crate::jni::helpers::{ContentValuesBuilder, JniResultExt, SafeJNIEnv, StringHelper};
 use jni::objects::{JObject, JValue};
 
 /// Wrapper for Android ContentResolver with safe JNI operations
-/// 
+///
 /// This struct provides a safe interface to Android's ContentResolver,
 /// handling all JNI operations and error checking automatically.
 pub struct ContentResolver<'local> {
@@ -243,6 +243,11 @@ impl<'local> ContentResolver<'local> {
         ).check_exception(self.env.env_mut())?;
 
         let cursor_obj = cursor_result.l().map_err(AnkiDroidError::from)?;
+        // The resolver answers null when it cannot reach the provider, e.g. while
+        // AnkiDroid is still starting
+        if cursor_obj.is_null() {
+            return Err(AnkiDroidError::busy("Content provider returned no cursor"));
+        }
         Cursor::new(self.env.clone(), cursor_obj)
     }
 
@@ -268,11 +273,7 @@ impl<'local> ContentResolver<'local> {
     ///     values
     /// )?;
     /// ```
-    pub fn insert(
-        &mut self,
-        uri: &str,
-        values: ContentValuesBuilder<'local>,
-    ) -> Result<String> {
+    pub fn insert(&mut self, uri: &str, values: ContentValuesBuilder<'local>) -> Result<String> {
         let uri_obj = self.parse_uri(uri)?;
         let content_values = values.build();
 
@@ -515,7 +516,9 @@ impl<'local> ContentResolver<'local> {
         let uri_obj = self.parse_uri(uri)?;
 
         // Create array of ContentValues
-        let content_values_class = self.env.find_class_checked("android/content/ContentValues")?;
+        let content_values_class = self
+            .env
+            .find_class_checked("android/content/ContentValues")?;
         let values_array = self
             .env
             .env_mut()
@@ -605,4 +608,4 @@ mod tests {
         let error = AnkiDroidError::database_error("Test database error");
         assert!(error.to_string().contains("Database error"));
     }
-}
\ No newline at end of file
+}
@@ -41,7 +41,7 @@
 //! ```
 
 use crate::error::{AnkiDroidError, Result};
-use crate::jni::helpers::{SafeJNIEnv, StringHelper, JniResultExt};
+use crate::jni::helpers::{JniResultExt, SafeJNIEnv, StringHelper};
 use jni::objects::{JObject, JValue};
 use std::collections::HashMap;
 
@@ -192,7 +192,7 @@ impl<'local> Cursor<'local> {
             .check_exception(self.env.env_mut())?;
 
         let index = result.i().unwrap_or(-1);
-        
+
         if index < 0 {
             return Err(AnkiDroidError::validation_error(format!(
                 "Column '{}' not found in cursor",
@@ -201,7 +201,8 @@ impl<'local> Cursor<'local> {
         }
 
         // Cache the index for future lookups
-        self.column_index_cache.insert(column_name.to_string(), index);
+        self.column_index_cache
+            .insert(column_name.to_string(), index);
         Ok(index)
     }
 
@@ -298,12 +299,7 @@ impl<'local> Cursor<'local> {
         let result = self
             .env
             .env_mut()
-            .call_method(
-                &self.cursor,
-                "getInt",
-                "(I)I",
-                &[JValue::Int(column_index)],
-            )
+            .call_method(&self.cursor, "getInt", "(I)I", &[JValue::Int(column_index)])
             .check_exception(self.env.env_mut())?;
 
         Ok(result.i().unwrap_or(0))
@@ -393,12 +389,7 @@ impl<'local> Cursor<'local> {
         let result = self
             .env
             .env_mut()
-            .call_method(
-                &self.cursor,
-                "isNull",
-                "(I)Z",
-                &[JValue::Int(column_index)],
-            )
+            .call_method(&self.cursor, "isNull", "(I)Z", &[JValue::Int(column_index)])
             .check_exception(self.env.env_mut())?;
 
         Ok(result.z().unwrap_or(true))
@@ -655,10 +646,7 @@ impl<'a, 'local> Iterator for CursorIterator<'a, 'local> {
 ///     Ok(cursor.get_long_by_name("_id")?)
 /// })?;
 /// ```
-pub fn collect_cursor_results<T, F>(
-    mut cursor: Cursor,
-    mut row_processor: F,
-) -> Result<Vec<T>>
+pub fn collect_cursor_results<T, F>(mut cursor: Cursor, mut row_processor: F) -> Result<Vec<T>>
 where
     F: FnMut(&mut Cursor) -> Result<T>,
 {
@@ -710,7 +698,7 @@ mod tests {
         let mut cache: HashMap<String, i32> = HashMap::new();
         cache.insert("_id".to_string(), 0);
         cache.insert("name".to_string(), 1);
-        
+
         assert_eq!(cache.get("_id"), Some(&0));
         assert_eq!(cache.get("name"), Some(&1));
         assert_eq!(cache.get("missing"), None);
@@ -721,4 +709,4 @@ mod tests {
         let error = AnkiDroidError::validation_error("Test cursor error");
         assert!(error.to_string().contains("Validation error"));
     }
-}
\ No newline at end of file
+}
@@ -40,8 +40,8 @@
 //! }
 //! ```
 
-use crate::error::{AnkiDroidError, Result};
-use jni::objects::{JObject, JString, JValue};
+use crate::error::{AnkiDroidError, JavaException, Result};
+use jni::objects::{JObject, JObjectArray, JString, JValue};
 use jni::{JNIEnv, JavaVM};
 use std::ops::Deref;
 
@@ -224,10 +224,7 @@ impl<'local> SafeJNIEnv<'local> {
     /// ```rust
     /// let string_class = safe_env.find_class_checked("java/lang/String")?;
     /// ```
-    pub fn find_class_checked(
-        &mut self,
-        name: &str,
-    ) -> Result<jni::objects::JClass<'local>> {
+    pub fn find_class_checked(&mut self, name: &str) -> Result<jni::objects::JClass<'local>> {
         self.env.find_class(name).check_exception(&mut self.env)
     }
 
@@ -697,16 +694,16 @@ impl AttachGuard {
     /// ```
     pub fn new(vm: JavaVM) -> Result<(Self, JNIEnv<'static>)> {
         let attach_guard = vm.attach_current_thread().map_err(AnkiDroidError::from)?;
-        
+
         // Extract the JNIEnv from the AttachGuard using unsafe pointer manipulation
         // This is safe because we ensure the thread stays attached via our own guard
         let env_ptr = attach_guard.deref() as *const JNIEnv<'_>;
         let static_env = unsafe { std::ptr::read(env_ptr) };
         let static_env = unsafe { std::mem::transmute::<JNIEnv<'_>, JNIEnv<'static>>(static_env) };
-        
+
         // Forget the original attach guard so it doesn't detach
         std::mem::forget(attach_guard);
-        
+
         Ok((Self { vm }, static_env))
     }
 }
@@ -789,9 +786,7 @@ pub fn get_content_resolver<'local>(
     let content_resolver = result.l().map_err(AnkiDroidError::from)?;
 
     if content_resolver.is_null() {
-        return Err(AnkiDroidError::validation_error(
-            "ContentResolver is null",
-        ));
+        return Err(AnkiDroidError::validation_error("ContentResolver is null"));
     }
 
     Ok(content_resolver)
@@ -853,23 +848,26 @@ pub trait JniResultExt<T> {
 impl<T> JniResultExt<T> for jni::errors::Result<T> {
     fn check_exception(self, env: &mut jni::JNIEnv) -> Result<T> {
         match self {
-            Ok(value) => {
-                // Check if a Java exception occurred
-                if env.exception_check().unwrap_or(false) {
-                    let exception_msg = get_exception_message(env)
-                        .unwrap_or_else(|| "Unknown Java exception".to_string());
-                    env.exception_clear().ok();
-                    Err(AnkiDroidError::jni_error(exception_msg))
-                } else {
-                    Ok(value)
-                }
-            }
+            Ok(value) => match take_java_exception(env) {
+                Some(err) => Err(err),
+                None => Ok(value),
+            },
+            // The call threw, so the exception is still pending
+            Err(jni::errors::Error::JavaException) => Err(take_java_exception(env)
+                .unwrap_or_else(|| AnkiDroidError::jni_error("Unknown Java exception"))),
             Err(jni_error) => Err(AnkiDroidError::from(jni_error)),
         }
     }
 }
 
-/// Helper function to extract exception message from JNI environment
+/// Number of stack frames kept when describing a Java exception
+pub const STACK_TRACE_FRAMES: usize = 8;
+
+/// Take the pending Java exception, if any, and map it to an error
+///
+/// The exception is cleared before it is inspected, since JNI calls are not
+/// allowed while one is pending. See [`AnkiDroidError::from_java_exception`]
+/// for how exception classes map to error variants.
 ///
 /// # Arguments
 ///
@@ -877,24 +875,97 @@ impl<T> JniResultExt<T> for jni::errors::Result<T> {
 ///
 /// # Returns
 ///
-/// The exception message if available, or None if no exception or message couldn't be extracted
-fn get_exception_message(env: &mut jni::JNIEnv) -> Option<String> {
-    env.exception_occurred().ok().and_then(|exception| {
-        env.call_method(&exception, "getMessage", "()Ljava/lang/String;", &[])
-            .ok()
-            .and_then(|msg| msg.l().ok())
-            .and_then(|msg_obj| {
-                if !msg_obj.is_null() {
-                    env.get_string(&msg_obj.into())
-                        .ok()
-                        .map(|s| s.to_str().unwrap_or("").to_string())
-                } else {
-                    None
-                }
-            })
+/// The mapped error, or None if no exception was pending
+pub fn take_java_exception(env: &mut JNIEnv) -> Option<AnkiDroidError> {
+    if !env.exception_check().unwrap_or(false) {
+        return None;
+    }
+    let throwable = env.exception_occurred().ok()?;
+    env.exception_clear().ok();
+
+    let described = env.with_local_frame(32, |env| -> Result<_> {
+        Ok(describe_throwable(env, &throwable))
+    });
+    env.delete_local_ref(throwable).ok();
+
+    Some(match described {
+        Ok((exception, superclasses)) => {
+            AnkiDroidError::from_java_exception(exception, &superclasses)
+        }
+        Err(_) => AnkiDroidError::jni_error("Unknown Java exception"),
     })
 }
 
+/// Describe a throwable, returning it with the names of its superclasses
+fn describe_throwable(env: &mut JNIEnv, throwable: &JObject) -> (JavaException, Vec<String>) {
+    let mut class_names = Vec::new();
+    let mut class = env.get_object_class(throwable).ok();
+    clear_exception(env);
+    while let Some(current) = class {
+        // Everything above Throwable is shared by all exceptions
+        match call_string_method(env, &current, "getName") {
+            Some(name) if name != "java.lang.Throwable" => class_names.push(name),
+            _ => break,
+        }
+        class = env.get_superclass(&current).ok().flatten();
+        clear_exception(env);
+    }
+
+    let mut stack_trace = Vec::new();
+    let frames = env
+        .call_method(
+            throwable,
+            "getStackTrace",
+            "()[Ljava/lang/StackTraceElement;",
+            &[],
+        )
+        .and_then(|frames| frames.l());
+    clear_exception(env);
+    if let Ok(frames) = frames.map(JObjectArray::from) {
+        let length = env.get_array_length(&frames).unwrap_or(0).max(0) as usize;
+        for index in 0..length.min(STACK_TRACE_FRAMES) {
+            let frame = match env.get_object_array_element(&frames, index as i32) {
+                Ok(frame) => frame,
+                Err(_) => break,
+            };
+            if let Some(line) = call_string_method(env, &frame, "toString") {
+                stack_trace.push(line);
+            }
+        }
+        clear_exception(env);
+    }
+
+    let mut class_names = class_names.into_iter();
+    let exception = JavaException {
+        class_name: class_names
+            .next()
+            .unwrap_or_else(|| "java.lang.Throwable".to_string()),
+        message: call_string_method(env, throwable, "getMessage"),
+        stack_trace,
+    };
+    (exception, class_names.collect())
+}
+
+/// Call a no-argument method returning a string, clearing any exception it throws
+fn call_string_method(env: &mut JNIEnv, obj: &JObject, name: &str) -> Option<String> {
+    let value = env
+        .call_method(obj, name, "()Ljava/lang/String;", &[])
+        .and_then(|value| value.l());
+    clear_exception(env);
+
+    let value = value.ok().filter(|value| !value.is_null())?;
+    let text = env.get_string(&JString::from(value)).ok().map(String::from);
+    clear_exception(env);
+    text
+}
+
+/// Clear a pending exception thrown while describing another one
+fn clear_exception(env: &mut JNIEnv) {
+    if env.exception_check().unwrap_or(false) {
+        env.exception_clear().ok();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -935,4 +1006,4 @@ mod tests {
         // We can't test actual frame creation without a JNIEnv
         assert!(true);
     }
-}
\ No newline at end of file
+}
@@ -22,4 +22,4 @@ pub use utils::*;
 pub use content_resolver::*;
 
 #[cfg(target_os = "android")]
-pub use cursor::*;
\ No newline at end of file
+pub use cursor::*;
@@ -27,9 +27,9 @@ pub fn string_to_jstring<'a>(env: &mut JNIEnv<'a>, s: &str) -> Result<JString<'a
 pub fn get_long_field(env: &mut JNIEnv, obj: &JObject, field_name: &str) -> Result<i64> {
     let _class = env.get_object_class(obj)?;
     let value = env.get_field(obj, field_name, "J")?;
-    Ok(value.j().map_err(|_| {
-        Error::validation_error(format!("Expected long field: {}", field_name))
-    })?)
+    Ok(value
+        .j()
+        .map_err(|_| Error::validation_error(format!("Expected long field: {}", field_name)))?)
 }
 
 #[cfg(target_os = "android")]
@@ -37,9 +37,9 @@ pub fn get_long_field(env: &mut JNIEnv, obj: &JObject, field_name: &str) -> Resu
 pub fn get_string_field(env: &mut JNIEnv, obj: &JObject, field_name: &str) -> Result<String> {
     let _class = env.get_object_class(obj)?;
     let value = env.get_field(obj, field_name, "Ljava/lang/String;")?;
-    let obj = value.l().map_err(|_| {
-        Error::validation_error(format!("Expected string field: {}", field_name))
-    })?;
+    let obj = value
+        .l()
+        .map_err(|_| Error::validation_error(format!("Expected string field: {}", field_name)))?;
     let jstr = JString::from(obj);
     jstring_to_string(env, &jstr)
 }
@@ -49,7 +49,7 @@ pub fn get_string_field(env: &mut JNIEnv, obj: &JObject, field_name: &str) -> Re
 pub fn get_int_field(env: &mut JNIEnv, obj: &JObject, field_name: &str) -> Result<i32> {
     let _class = env.get_object_class(obj)?;
     let value = env.get_field(obj, field_name, "I")?;
-    Ok(value.i().map_err(|_| {
-        Error::validation_error(format!("Expected int field: {}", field_name))
-    })?)
-}
\ No newline at end of file
+    Ok(value
+        .i()
+        .map_err(|_| Error::validation_error(format!("Expected int field: {}", field_name)))?)
+}
@@ -1,31 +1,31 @@
 //! # AnkiDroid API Rust
-//! 
+//!
 //! Type-safe Rust bindings for the AnkiDroid API FlashCardsContract.
-//! 
+//!
 //! This crate provides comprehensive access to AnkiDroid's content provider API,
 //! allowing Android applications to interact with AnkiDroid's database and functionality
 //! in a type-safe manner. The API includes support for managing notes, cards, decks,
 //! models (note types), and performing review operations.
-//! 
+//!
 //! ## Features
-//! 
+//!
 //! - **Type Safety**: All API operations use strongly-typed Rust structs and enums
 //! - **Error Handling**: Comprehensive error types with detailed error information
 //! - **Android Integration**: Full JNI support for Android applications
 //! - **Content Provider Access**: Direct access to AnkiDroid's FlashCardsContract
 //! - **Serialization Support**: Built-in serde support for all data types
-//! 
+//!
 //! ## Platform Support
-//! 
+//!
 //! This crate is designed specifically for Android applications. The JNI and API
 //! modules are only available when compiling for Android targets.
-//! 
+//!
 //! ## Basic Usage
-//! 
+//!
 //! ```rust,ignore
 //! // This example only works on Android targets
 //! use ankidroid_api_rust::{AnkiDroidApi, Note, Result};
-//! 
+//!
 //! #[cfg(target_os = "android")]
 //! fn example_usage(vm: jni::JavaVM) -> Result<()> {
 //!     let api = AnkiDroidApi::new(vm);
@@ -48,28 +48,28 @@
 //!     Ok(())
 //! }
 //! ```
-//! 
+//!
 //! ## URI Constants
-//! 
+//!
 //! The crate provides all necessary constants for interacting with AnkiDroid's
 //! content provider:
-//! 
+//!
 //! ```rust
 //! use ankidroid_api_rust::{AUTHORITY, READ_WRITE_PERMISSION, DEFAULT_DECK_ID};
-//! 
+//!
 //! assert_eq!(AUTHORITY, "com.ichi2.anki.flashcards");
 //! assert_eq!(DEFAULT_DECK_ID, 1);
 //! ```
-//! 
+//!
 //! ## Error Handling
-//! 
+//!
 //! All fallible operations return a `Result<T, AnkiDroidError>`. The error type
 //! provides detailed information about what went wrong and includes helper methods
 //! for categorizing errors:
-//! 
+//!
 //! ```rust
 //! use ankidroid_api_rust::{AnkiDroidError, Result};
-//! 
+//!
 //! fn handle_error(result: Result<()>) {
 //!     match result {
 //!         Ok(()) => println!("Success!"),
@@ -86,9 +86,14 @@
 //! ```
 
 // Core module declarations
+pub mod changes;
 pub mod contract;
+pub mod deck_options;
+pub mod deck_tree;
 pub mod error;
+pub mod media;
 pub mod models;
+pub mod provisioning;
 pub mod utils;
 
 // Android-specific modules (only available on Android targets)
@@ -102,28 +107,52 @@ pub mod api;
 pub mod extended;
 
 // Re-exports for convenience and public API
-pub use error::{AnkiDroidError, Result};
+pub use changes::NoteChanges;
+pub use deck_options::{DeckDetails, DeckOptions};
+pub use deck_tree::{DeckCounts, DeckNode, DeckTree, DECK_SEPARATOR};
+pub use error::{AnkiDroidError, JavaException, Result};
+pub use media::{extract_media_refs, missing_media, note_media_refs, MediaRef, MediaRefKind};
 pub use models::{
-    Note, Card, Deck, Model, NoteInfo, Ease,
-    NoteBuilder, BasicModel, Basic2Model
+    Basic2Model, BasicModel, Card, CardTemplate, Deck, Ease, Model, ModelDetails, Note,
+    NoteBuilder, NoteInfo,
 };
+pub use provisioning::{DriftPolicy, EnsureModelOutcome, ModelDrift, ModelSpec, TemplateSpec};
 pub use utils::{
-    join_fields, split_fields, join_tags, split_tags,
-    strip_html, strip_html_entities
+    join_fields, join_tags, split_fields, split_tags, strip_html, strip_html_entities,
 };
 
-#[cfg(target_os = "android")]
-pub use utils::field_checksum;
 pub use contract::{
-    AUTHORITY, READ_WRITE_PERMISSION, DEFAULT_DECK_ID, FIELD_SEPARATOR,
+    anki_media,
     // URI builder functions
-    build_authority_uri, build_note_uri, build_note_by_id_uri, build_cards_for_note_uri,
-    build_specific_card_uri, build_notes_v2_uri, build_models_uri, build_model_by_id_uri,
-    build_current_model_uri, build_templates_uri, build_decks_uri, build_selected_deck_uri,
-    build_schedule_uri, build_media_uri,
+    build_authority_uri,
+    build_cards_for_note_uri,
+    build_current_model_uri,
+    build_deck_by_id_uri,
+    build_decks_uri,
+    build_media_uri,
+    build_model_by_id_uri,
+    build_models_uri,
+    build_note_by_id_uri,
+    build_note_uri,
+    build_notes_v2_uri,
+    build_schedule_uri,
+    build_selected_deck_uri,
+    build_specific_card_uri,
+    build_templates_uri,
+    card,
+    card_template,
+    deck,
+    model,
     // Column constant modules
-    note, card, deck, model, card_template, review_info, anki_media
+    note,
+    review_info,
+    AUTHORITY,
+    DEFAULT_DECK_ID,
+    FIELD_SEPARATOR,
+    READ_WRITE_PERMISSION,
 };
+#[cfg(target_os = "android")]
+pub use utils::field_checksum;
 
 // Android-specific re-exports
 #[cfg(target_os = "android")]
@@ -140,14 +169,12 @@ pub const CRATE_NAME: &str = env!("CARGO_PKG_NAME");
 
 /// Feature flags for optional functionality
 pub mod features {
-    //! Feature flags for conditional compilation
-    
     /// Whether JNI support is available (Android only)
     pub const HAS_JNI: bool = cfg!(target_os = "android");
-    
-    /// Whether API support is available (Android only) 
+
+    /// Whether API support is available (Android only)
     pub const HAS_API: bool = cfg!(target_os = "android");
-    
+
     /// Whether serde support is enabled (always true in this version)
     pub const HAS_SERDE: bool = true;
 }
@@ -155,47 +182,37 @@ pub mod features {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
     fn test_version_info() {
         assert!(!VERSION.is_empty());
         assert_eq!(CRATE_NAME, "ankidroid-api-rust");
     }
-    
+
     #[test]
     fn test_feature_flags() {
-        // JNI should only be available on Android
-        #[cfg(target_os = "android")]
-        assert!(features::HAS_JNI);
-        
-        #[cfg(not(target_os = "android"))]
-        assert!(!features::HAS_JNI);
-        
-        // API should only be available on Android
-        #[cfg(target_os = "android")]
-        assert!(features::HAS_API);
-        
-        #[cfg(not(target_os = "android"))]
-        assert!(!features::HAS_API);
-        
-        // Serde should always be available
-        assert!(features::HAS_SERDE);
+        // JNI and API are only available on Android, serde always is
+        let android = cfg!(target_os = "android");
+        assert_eq!(
+            (features::HAS_JNI, features::HAS_API, features::HAS_SERDE),
+            (android, android, true)
+        );
     }
-    
+
     #[test]
     fn test_public_api_availability() {
         // These should always be available
-        use crate::{AUTHORITY, DEFAULT_DECK_ID, VERSION, AnkiDroidError};
-        
+        use crate::{AnkiDroidError, AUTHORITY, DEFAULT_DECK_ID, VERSION};
+
         assert_eq!(AUTHORITY, "com.ichi2.anki.flashcards");
         assert_eq!(DEFAULT_DECK_ID, 1);
         assert!(!VERSION.is_empty());
-        
+
         // Test error creation
         let err = AnkiDroidError::validation_error("test");
-        assert!(matches!(err, AnkiDroidError::ValidationError(_)));
+        assert!(matches!(err, AnkiDroidError::ValidationError { .. }));
     }
-    
+
     #[test]
     fn test_model_builders() {
         let note = Note::builder(123)
@@ -203,13 +220,13 @@ mod tests {
             .field("Back".to_string())
             .tag("test".to_string())
             .build();
-            
+
         assert_eq!(note.mid, 123);
         assert_eq!(note.fields.len(), 2);
         assert_eq!(note.tags.len(), 1);
         assert_eq!(note.key(), "Front");
     }
-    
+
     #[cfg(target_os = "android")]
     #[test]
     fn test_android_specific_imports() {
@@ -217,4 +234,4 @@ mod tests {
         use crate::AnkiDroidApi;
         // If this compiles, the import is available
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,346 @@
+//! Media references in note fields
+//!
+//! Notes refer to files in AnkiDroid's media folder by name, through `<img>`
+//! tags, `[sound:...]` tags, `<audio>`/`<video>` elements and CSS `url()`.
+//! [`extract_media_refs`] lists those references so an export can bundle
+//! exactly the files a note needs, and [`missing_media`] reports the ones that
+//! are not among a set of known files. Only references to collection media are
+//! returned; remote URLs and `data:` URIs are skipped.
+
+use crate::utils::strip_html_entities;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// How a field refers to a media file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaRefKind {
+    /// `<img src="...">`
+    Image,
+    /// `[sound:...]`, Anki's own audio and video tag
+    Sound,
+    /// `<audio src>` or a `<source>` inside `<audio>`
+    Audio,
+    /// `<video src>` or a `<source>` inside `<video>`
+    Video,
+    /// CSS `url(...)` in a style attribute or block
+    CssUrl,
+}
+
+impl MediaRefKind {
+    /// Get the name the kind serializes to
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MediaRefKind::Image => "image",
+            MediaRefKind::Sound => "sound",
+            MediaRefKind::Audio => "audio",
+            MediaRefKind::Video => "video",
+            MediaRefKind::CssUrl => "css_url",
+        }
+    }
+}
+
+/// A media file referenced from a field
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MediaRef {
+    pub kind: MediaRefKind,
+    /// Name of the file in the media folder, with HTML and URL escapes decoded
+    pub filename: String,
+}
+
+static SOUND_PATTERN: OnceLock<Regex> = OnceLock::new();
+static MEDIA_TAG_PATTERN: OnceLock<Regex> = OnceLock::new();
+static SRC_ATTRIBUTE_PATTERN: OnceLock<Regex> = OnceLock::new();
+static CSS_URL_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+fn get_sound_pattern() -> &'static Regex {
+    SOUND_PATTERN.get_or_init(|| Regex::new(r"\[sound:([^\]]+)\]").unwrap())
+}
+
+fn get_media_tag_pattern() -> &'static Regex {
+    MEDIA_TAG_PATTERN
+        .get_or_init(|| Regex::new(r"(?is)<(/?)(img|audio|video|source)\b([^>]*)>").unwrap())
+}
+
+fn get_src_attribute_pattern() -> &'static Regex {
+    SRC_ATTRIBUTE_PATTERN.get_or_init(|| {
+        Regex::new(r#"(?is)(?:^|\s)src\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+    })
+}
+
+fn get_css_url_pattern() -> &'static Regex {
+    CSS_URL_PATTERN.get_or_init(|| {
+        Regex::new(r#"(?i)url\(\s*(?:"([^"]*)"|'([^']*)'|([^)"'\s]+))\s*\)"#).unwrap()
+    })
+}
+
+/// Get the first capture group that matched
+fn first_group<'t>(captures: &regex::Captures<'t>) -> Option<&'t str> {
+    (1..captures.len())
+        .find_map(|i| captures.get(i))
+        .map(|m| m.as_str())
+}
+
+/// List the media files a field refers to, in order of appearance
+///
+/// Each file is listed once per kind of reference.
+///
+/// # Example
+/// ```
+/// use ankidroid_api_rust::media::{extract_media_refs, MediaRefKind};
+///
+/// let refs = extract_media_refs(r#"猫 <img src="neko.jpg"> [sound:neko.mp3]"#);
+/// assert_eq!(refs.len(), 2);
+/// assert_eq!(refs[0].kind, MediaRefKind::Image);
+/// assert_eq!(refs[1].filename, "neko.mp3");
+/// ```
+pub fn extract_media_refs(field: &str) -> Vec<MediaRef> {
+    let mut found: Vec<(usize, MediaRefKind, String)> = Vec::new();
+
+    for captures in get_sound_pattern().captures_iter(field) {
+        let position = captures.get(0).map_or(0, |m| m.start());
+        found.push((
+            position,
+            MediaRefKind::Sound,
+            captures[1].trim().to_string(),
+        ));
+    }
+
+    // A <source> belongs to the <audio> or <video> it is nested in
+    let mut container: Option<MediaRefKind> = None;
+    for captures in get_media_tag_pattern().captures_iter(field) {
+        let position = captures.get(0).map_or(0, |m| m.start());
+        let closing = !captures[1].is_empty();
+        let tag = captures[2].to_ascii_lowercase();
+
+        let kind = match (tag.as_str(), closing) {
+            ("audio" | "video", true) => {
+                container = None;
+                continue;
+            }
+            (_, true) => continue,
+            ("img", _) => MediaRefKind::Image,
+            ("audio", _) => {
+                container = Some(MediaRefKind::Audio);
+                MediaRefKind::Audio
+            }
+            ("video", _) => {
+                container = Some(MediaRefKind::Video);
+                MediaRefKind::Video
+            }
+            _ => match container {
+                Some(kind) => kind,
+                None => continue,
+            },
+        };
+
+        if let Some(src) = get_src_attribute_pattern()
+            .captures(&captures[3])
+            .as_ref()
+            .and_then(first_group)
+        {
+            found.push((position, kind, decode_reference(src)));
+        }
+    }
+
+    for captures in get_css_url_pattern().captures_iter(field) {
+        let position = captures.get(0).map_or(0, |m| m.start());
+        if let Some(url) = first_group(&captures) {
+            found.push((position, MediaRefKind::CssUrl, decode_reference(url)));
+        }
+    }
+
+    found.sort_by_key(|(position, _, _)| *position);
+
+    let mut seen = HashSet::new();
+    found
+        .into_iter()
+        .filter(|(_, _, filename)| is_collection_media(filename))
+        .map(|(_, kind, filename)| MediaRef { kind, filename })
+        .filter(|media| seen.insert(media.clone()))
+        .collect()
+}
+
+/// List the media files a note's fields refer to, each file once
+pub fn note_media_refs<S: AsRef<str>>(fields: &[S]) -> Vec<MediaRef> {
+    let mut seen = HashSet::new();
+    fields
+        .iter()
+        .flat_map(|field| extract_media_refs(field.as_ref()))
+        .filter(|media| seen.insert(media.filename.clone()))
+        .collect()
+}
+
+/// Get the references whose file is not among `known_files`
+///
+/// # Example
+/// ```
+/// use ankidroid_api_rust::media::{extract_media_refs, missing_media};
+/// use std::collections::HashSet;
+///
+/// let refs = extract_media_refs(r#"<img src="a.png"><img src="b.png">"#);
+/// let known: HashSet<String> = ["a.png".to_string()].into_iter().collect();
+/// let missing = missing_media(&refs, &known);
+/// assert_eq!(missing[0].filename, "b.png");
+/// ```
+pub fn missing_media<'a>(refs: &'a [MediaRef], known_files: &HashSet<String>) -> Vec<&'a MediaRef> {
+    refs.iter()
+        .filter(|media| !known_files.contains(&media.filename))
+        .collect()
+}
+
+/// Check whether a reference points into the media folder
+fn is_collection_media(reference: &str) -> bool {
+    let lower = reference.to_ascii_lowercase();
+    !reference.is_empty()
+        && !reference.starts_with('/')
+        && !reference.starts_with('#')
+        && !lower.starts_with("data:")
+        && !lower.contains("://")
+}
+
+/// Decode HTML entities and URL escapes in an attribute value
+fn decode_reference(value: &str) -> String {
+    percent_decode(strip_html_entities(value.trim()).as_str())
+}
+
+/// Decode %XX escapes, keeping the input if the result is not UTF-8
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8(decoded).unwrap_or_else(|_| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn media(kind: MediaRefKind, filename: &str) -> MediaRef {
+        MediaRef {
+            kind,
+            filename: filename.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_extract_all_kinds_in_order() {
+        let field = concat!(
+            r#"<div style="background: url('paper.png')">"#,
+            r#"<IMG class=big SRC=flag.svg>"#,
+            r#"[sound:neko.mp3]"#,
+            r#"<audio controls><source src="word.ogg" type="audio/ogg"></audio>"#,
+            r#"<video src='clip.mp4' poster="x.jpg"><source src="clip.webm"></video>"#,
+            r#"<source src="orphan.ogg">"#,
+            r#"</div>"#
+        );
+
+        assert_eq!(
+            extract_media_refs(field),
+            vec![
+                media(MediaRefKind::CssUrl, "paper.png"),
+                media(MediaRefKind::Image, "flag.svg"),
+                media(MediaRefKind::Sound, "neko.mp3"),
+                media(MediaRefKind::Audio, "word.ogg"),
+                media(MediaRefKind::Video, "clip.mp4"),
+                media(MediaRefKind::Video, "clip.webm"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escapes_are_decoded() {
+        let refs =
+            extract_media_refs(r#"<img src="my%20cat&amp;dog.jpg"><img src="%E7%8C%AB.png">"#);
+        assert_eq!(refs[0].filename, "my cat&dog.jpg");
+        assert_eq!(refs[1].filename, "猫.png");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%FF"), "%FF");
+    }
+
+    #[test]
+    fn test_remote_and_inline_media_skipped() {
+        let field = concat!(
+            r#"<img src="https://example.com/a.png">"#,
+            r#"<img src="//cdn.example.com/b.png">"#,
+            r#"<img src="data:image/png;base64,AAAA">"#,
+            r#"<div style="background: url(#gradient)"></div>"#,
+            r#"<img src="">"#,
+            r#"<img src="local.png">"#
+        );
+        assert_eq!(
+            extract_media_refs(field),
+            vec![media(MediaRefKind::Image, "local.png")]
+        );
+    }
+
+    #[test]
+    fn test_duplicates_removed() {
+        let refs = extract_media_refs("[sound:a.mp3] [sound:a.mp3] <audio src=a.mp3>");
+        assert_eq!(
+            refs,
+            vec![
+                media(MediaRefKind::Sound, "a.mp3"),
+                media(MediaRefKind::Audio, "a.mp3")
+            ]
+        );
+
+        let fields = [
+            "[sound:a.mp3]".to_string(),
+            r#"<img src="b.png"> [sound:a.mp3]"#.to_string(),
+        ];
+        let refs = note_media_refs(&fields);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[1].filename, "b.png");
+    }
+
+    #[test]
+    fn test_plain_text_has_no_refs() {
+        assert!(extract_media_refs("Just text with src=x.png and url").is_empty());
+        assert!(extract_media_refs("").is_empty());
+    }
+
+    #[test]
+    fn test_missing_media() {
+        let refs = note_media_refs(&[r#"<img src="a.png"> [sound:b.mp3] <img src="c.png">"#]);
+        let known: HashSet<String> = ["a.png", "c.png"].iter().map(|s| s.to_string()).collect();
+
+        let missing = missing_media(&refs, &known);
+        assert_eq!(missing, vec![&media(MediaRefKind::Sound, "b.mp3")]);
+    }
+
+    #[test]
+    fn test_kind_names_match_serde() {
+        for kind in [
+            MediaRefKind::Image,
+            MediaRefKind::Sound,
+            MediaRefKind::Audio,
+            MediaRefKind::Video,
+            MediaRefKind::CssUrl,
+        ] {
+            assert_eq!(serde_json::to_value(kind).unwrap(), kind.as_str());
+        }
+    }
+}
@@ -1,9 +1,12 @@
 //! Data models for AnkiDroid entities
 //!
 //! This module provides Rust representations of AnkiDroid data structures including
-//! notes, cards, decks, and models. All structures include serde support for 
+//! notes, cards, decks, and models. All structures include serde support for
 //! serialization and deserialization.
 
+use crate::contract::model;
+use crate::error::{AnkiDroidError, Result};
+use crate::utils::split_fields;
 use serde::{Deserialize, Serialize};
 
 /// Represents a note in AnkiDroid
@@ -41,7 +44,7 @@ impl Note {
     pub fn new(mid: i64, fields: Vec<String>) -> Self {
         let sfld = fields.first().cloned().unwrap_or_default();
         Self {
-            id: 0, // Will be assigned by AnkiDroid
+            id: 0,               // Will be assigned by AnkiDroid
             guid: String::new(), // Will be generated by AnkiDroid
             mid,
             mod_: 0, // Will be set by AnkiDroid
@@ -266,6 +269,129 @@ impl Model {
     }
 }
 
+/// A note type together with the details the content provider reports for it
+///
+/// Built from a row of the models table, where every column is read as a
+/// string: field names are separated by [`FIELD_SEPARATOR`](crate::FIELD_SEPARATOR)
+/// and numeric columns may be empty.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDetails {
+    /// The note type itself
+    #[serde(flatten)]
+    pub model: Model,
+    /// Number of notes using this note type (None if not reported)
+    pub note_count: Option<i64>,
+}
+
+impl ModelDetails {
+    /// Columns to request when querying model details
+    pub fn projection() -> Vec<String> {
+        model::DEFAULT_PROJECTION
+            .iter()
+            .chain(std::iter::once(&model::NOTE_COUNT))
+            .map(|column| column.to_string())
+            .collect()
+    }
+
+    /// Parse a models row, reading each column through `column`
+    ///
+    /// `column` returns None for columns missing from the row. The ID and name
+    /// are required; the other columns fall back to [`Model::new`] defaults.
+    pub fn from_columns<F>(mut column: F) -> Result<Self>
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let id = column(model::_ID)
+            .and_then(|value| value.parse::<i64>().ok())
+            .ok_or_else(|| AnkiDroidError::validation_error("Invalid model ID in model row"))?;
+        let name = column(model::NAME)
+            .ok_or_else(|| AnkiDroidError::validation_error("Missing model name in model row"))?;
+        let field_names = column(model::FIELD_NAMES)
+            .map(|value| split_fields(&value))
+            .unwrap_or_default();
+
+        let mut parsed = Model::new(id, name, field_names);
+        let mut int = |name: &str| column(name).and_then(|value| value.trim().parse::<i64>().ok());
+
+        if let Some(num_cards) = int(model::NUM_CARDS) {
+            parsed.num_cards = num_cards as i32;
+        }
+        parsed.sort_field_index = int(model::SORT_FIELD_INDEX).unwrap_or(0) as i32;
+        parsed.type_ = int(model::TYPE).unwrap_or(0) as i32;
+        // A model without a default deck reports an empty value
+        parsed.deck_id = int(model::DECK_ID);
+        let note_count = int(model::NOTE_COUNT);
+
+        parsed.css = column(model::CSS).unwrap_or_default();
+        parsed.latex_pre = column(model::LATEX_PRE).unwrap_or_default();
+        parsed.latex_post = column(model::LATEX_POST).unwrap_or_default();
+
+        Ok(Self {
+            model: parsed,
+            note_count,
+        })
+    }
+
+    /// Get the position of a field by name
+    pub fn field_index(&self, name: &str) -> Option<usize> {
+        self.model
+            .field_names
+            .iter()
+            .position(|field| field == name)
+    }
+
+    /// Get the name of the field notes are sorted by
+    pub fn sort_field_name(&self) -> Option<&str> {
+        usize::try_from(self.model.sort_field_index)
+            .ok()
+            .and_then(|index| self.model.field_names.get(index))
+            .map(String::as_str)
+    }
+}
+
+/// Represents a card template of a note type in AnkiDroid
+///
+/// Templates define how the fields of a note are rendered into the question
+/// and answer of each generated card.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CardTemplate {
+    /// Unique identifier for the template
+    pub id: i64,
+    /// ID of the model this template belongs to
+    pub model_id: i64,
+    /// Ordinal position of the template within its model
+    pub ord: i32,
+    /// Display name of the template
+    pub name: String,
+    /// Question format (front template)
+    pub question_format: String,
+    /// Answer format (back template)
+    pub answer_format: String,
+    /// Question format used in the card browser (empty for default)
+    pub browser_question_format: String,
+    /// Answer format used in the card browser (empty for default)
+    pub browser_answer_format: String,
+    /// Number of cards generated from this template
+    pub card_count: i32,
+}
+
+impl CardTemplate {
+    /// Create a new card template
+    pub fn new(model_id: i64, ord: i32, name: String) -> Self {
+        Self {
+            id: 0,
+            model_id,
+            ord,
+            name,
+            question_format: String::new(),
+            answer_format: String::new(),
+            browser_question_format: String::new(),
+            browser_answer_format: String::new(),
+            card_count: 0,
+        }
+    }
+}
+
 /// Simplified note information for API compatibility
 ///
 /// This matches the NoteInfo class from the Kotlin API and provides
@@ -343,17 +469,15 @@ pub struct BasicModel;
 impl BasicModel {
     /// Field names for basic model
     pub const FIELDS: &'static [&'static str] = &["Front", "Back"];
-    
+
     /// Card names for basic model
     pub const CARD_NAMES: &'static [&'static str] = &["Card 1"];
-    
+
     /// Question format template
     pub const QFMT: &'static [&'static str] = &["{{Front}}"];
-    
+
     /// Answer format template
-    pub const AFMT: &'static [&'static str] = &[
-        "{{FrontSide}}\n\n<hr id=\"answer\">\n\n{{Back}}"
-    ];
+    pub const AFMT: &'static [&'static str] = &["{{FrontSide}}\n\n<hr id=\"answer\">\n\n{{Back}}"];
 }
 
 /// Constants and templates for the basic with reverse note type
@@ -363,17 +487,17 @@ pub struct Basic2Model;
 impl Basic2Model {
     /// Field names for basic with reverse model
     pub const FIELDS: &'static [&'static str] = &["Front", "Back"];
-    
+
     /// Card names for basic with reverse model
     pub const CARD_NAMES: &'static [&'static str] = &["Card 1", "Card 2"];
-    
+
     /// Question format templates
     pub const QFMT: &'static [&'static str] = &["{{Front}}", "{{Back}}"];
-    
+
     /// Answer format templates
     pub const AFMT: &'static [&'static str] = &[
         "{{FrontSide}}\n\n<hr id=\"answer\">\n\n{{Back}}",
-        "{{FrontSide}}\n\n<hr id=\"answer\">\n\n{{Front}}"
+        "{{FrontSide}}\n\n<hr id=\"answer\">\n\n{{Front}}",
     ];
 }
 
@@ -399,7 +523,7 @@ mod tests {
         assert_eq!(Ease::from_value(1), Some(Ease::Ease1));
         assert_eq!(Ease::from_value(4), Some(Ease::Ease4));
         assert_eq!(Ease::from_value(5), None);
-        
+
         assert_eq!(Ease::Ease3.value(), 3);
     }
 
@@ -425,7 +549,7 @@ mod tests {
         let model = Model::new(
             456,
             "Test Model".to_string(),
-            vec!["Field1".to_string(), "Field2".to_string()]
+            vec!["Field1".to_string(), "Field2".to_string()],
         );
 
         assert_eq!(model.field_count(), 2);
@@ -460,4 +584,77 @@ mod tests {
         assert_eq!(Basic2Model::QFMT.len(), 2);
         assert_eq!(Basic2Model::AFMT.len(), 2);
     }
-}
\ No newline at end of file
+
+    fn model_row(column: &str) -> Option<String> {
+        let value = match column {
+            model::_ID => "1607392319495",
+            model::NAME => "Vocabulary",
+            model::FIELD_NAMES => "Word\u{001f}Reading\u{001f}Meaning",
+            model::NUM_CARDS => "2",
+            model::CSS => ".card { font-size: 20px; }",
+            model::SORT_FIELD_INDEX => "1",
+            model::TYPE => "0",
+            model::DECK_ID => "",
+            model::NOTE_COUNT => "42",
+            _ => return None,
+        };
+        Some(value.to_string())
+    }
+
+    #[test]
+    fn test_model_details_from_columns() {
+        let details = ModelDetails::from_columns(model_row).unwrap();
+        assert_eq!(details.model.id, 1607392319495);
+        assert_eq!(
+            details.model.field_names,
+            vec!["Word", "Reading", "Meaning"]
+        );
+        assert_eq!(details.model.num_cards, 2);
+        assert_eq!(details.model.css, ".card { font-size: 20px; }");
+        assert_eq!(details.model.deck_id, None);
+        assert_eq!(details.model.latex_pre, "");
+        assert_eq!(details.note_count, Some(42));
+        assert_eq!(details.sort_field_name(), Some("Reading"));
+        assert_eq!(details.field_index("Meaning"), Some(2));
+        assert_eq!(details.field_index("Back"), None);
+
+        let json = serde_json::to_value(&details).unwrap();
+        assert_eq!(json["name"], "Vocabulary");
+        assert_eq!(json["note_count"], 42);
+    }
+
+    #[test]
+    fn test_model_details_missing_columns() {
+        let details = ModelDetails::from_columns(|column| match column {
+            model::_ID => Some("5".to_string()),
+            model::NAME => Some("Cloze".to_string()),
+            model::TYPE => Some("1".to_string()),
+            model::DECK_ID => Some("1".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert!(details.model.is_cloze());
+        assert!(details.model.field_names.is_empty());
+        assert_eq!(details.model.num_cards, 1);
+        assert_eq!(details.model.deck_id, Some(1));
+        assert_eq!(details.note_count, None);
+        assert_eq!(details.sort_field_name(), None);
+
+        assert!(ModelDetails::from_columns(|column| match column {
+            model::_ID => Some("not a number".to_string()),
+            _ => Some(String::new()),
+        })
+        .is_err());
+        assert!(ModelDetails::projection().contains(&model::NOTE_COUNT.to_string()));
+    }
+
+    #[test]
+    fn test_card_template_new() {
+        let template = CardTemplate::new(42, 1, "Card 2".to_string());
+        assert_eq!(template.model_id, 42);
+        assert_eq!(template.ord, 1);
+        assert_eq!(template.name, "Card 2");
+        assert!(template.question_format.is_empty());
+        assert_eq!(template.card_count, 0);
+    }
+}
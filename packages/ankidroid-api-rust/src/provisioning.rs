@@ -0,0 +1,421 @@
+//! Declarative note type provisioning
+//!
+//! This module lets an application describe the note types it owns as a
+//! [`ModelSpec`] and compare that description against what AnkiDroid currently
+//! has. The comparison is pure so it can run on any target; the Android-only
+//! `ensure_model` method of [`AnkiDroidApiExtended`](crate::AnkiDroidApiExtended)
+//! uses it to create missing models and to report or apply drift.
+
+use crate::error::{AnkiDroidError, Result};
+use crate::models::{BasicModel, CardTemplate};
+use serde::{Deserialize, Serialize};
+
+/// Description of a single card template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateSpec {
+    /// Template name (e.g. "Card 1")
+    pub name: String,
+    /// Question format
+    pub qfmt: String,
+    /// Answer format
+    pub afmt: String,
+}
+
+impl TemplateSpec {
+    /// Create a new template description
+    pub fn new(name: impl Into<String>, qfmt: impl Into<String>, afmt: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            qfmt: qfmt.into(),
+            afmt: afmt.into(),
+        }
+    }
+}
+
+/// Declarative description of a note type owned by the application
+///
+/// A model is identified by its name. Its ordered field names, CSS and
+/// templates are compared against the existing model, templates by ordinal.
+///
+/// # Examples
+///
+/// ```rust
+/// use ankidroid_api_rust::ModelSpec;
+///
+/// let spec = ModelSpec::new("Vocabulary", vec!["Word".to_string(), "Meaning".to_string()])
+///     .template("Recognition", "{{Word}}", "{{FrontSide}}<hr id=answer>{{Meaning}}")
+///     .css(".card { font-size: 24px; }");
+///
+/// assert!(spec.validate().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelSpec {
+    /// Name of the note type
+    pub name: String,
+    /// Ordered field names
+    pub fields: Vec<String>,
+    /// Card templates in ordinal order
+    pub templates: Vec<TemplateSpec>,
+    /// CSS shared by all templates (None leaves AnkiDroid's default untouched)
+    #[serde(default)]
+    pub css: Option<String>,
+    /// Index of the sort field
+    #[serde(default)]
+    pub sort_field: i32,
+    /// Whether this is a cloze note type
+    #[serde(default)]
+    pub cloze: bool,
+}
+
+impl ModelSpec {
+    /// Create a new spec with the given fields and no templates
+    pub fn new(name: impl Into<String>, fields: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            fields,
+            templates: Vec::new(),
+            css: None,
+            sort_field: 0,
+            cloze: false,
+        }
+    }
+
+    /// Spec matching the basic front/back note type
+    pub fn basic(name: impl Into<String>) -> Self {
+        let fields = BasicModel::FIELDS.iter().map(|f| f.to_string()).collect();
+        let mut spec = Self::new(name, fields);
+        for ((card, qfmt), afmt) in BasicModel::CARD_NAMES
+            .iter()
+            .zip(BasicModel::QFMT)
+            .zip(BasicModel::AFMT)
+        {
+            spec = spec.template(*card, *qfmt, *afmt);
+        }
+        spec
+    }
+
+    /// Add a card template
+    pub fn template(
+        mut self,
+        name: impl Into<String>,
+        qfmt: impl Into<String>,
+        afmt: impl Into<String>,
+    ) -> Self {
+        self.templates.push(TemplateSpec::new(name, qfmt, afmt));
+        self
+    }
+
+    /// Set the CSS
+    pub fn css(mut self, css: impl Into<String>) -> Self {
+        self.css = Some(css.into());
+        self
+    }
+
+    /// Set the sort field index
+    pub fn sort_field(mut self, index: i32) -> Self {
+        self.sort_field = index;
+        self
+    }
+
+    /// Mark the note type as cloze
+    pub fn cloze(mut self, cloze: bool) -> Self {
+        self.cloze = cloze;
+        self
+    }
+
+    /// Validate the spec before it is sent to AnkiDroid
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(AnkiDroidError::validation_error(
+                "Model name cannot be empty",
+            ));
+        }
+
+        if self.fields.is_empty() {
+            return Err(AnkiDroidError::validation_error(
+                "Model must have at least one field",
+            ));
+        }
+
+        if self.fields.iter().any(|f| f.trim().is_empty()) {
+            return Err(AnkiDroidError::validation_error(
+                "Field names cannot be empty",
+            ));
+        }
+
+        for (i, field) in self.fields.iter().enumerate() {
+            if self.fields[..i].contains(field) {
+                return Err(AnkiDroidError::validation_error(format!(
+                    "Duplicate field name: {}",
+                    field
+                )));
+            }
+        }
+
+        if self.templates.is_empty() {
+            return Err(AnkiDroidError::validation_error(
+                "Model must have at least one template",
+            ));
+        }
+
+        if self.sort_field < 0 || self.sort_field as usize >= self.fields.len() {
+            return Err(AnkiDroidError::validation_error(format!(
+                "Sort field index {} out of range for {} fields",
+                self.sort_field,
+                self.fields.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Check whether an existing model has this spec's name and field signature
+    pub fn matches_signature(&self, name: &str, field_names: &[String]) -> bool {
+        self.name == name && self.fields == field_names
+    }
+
+    /// Compare the spec against an existing model's fields, CSS and templates
+    ///
+    /// Templates are compared by ordinal. Only the question and answer formats
+    /// are considered; template names are cosmetic and ignored.
+    pub fn diff(
+        &self,
+        field_names: &[String],
+        css: &str,
+        templates: &[CardTemplate],
+    ) -> Vec<ModelDrift> {
+        let mut drift = Vec::new();
+
+        if self.fields != field_names {
+            drift.push(ModelDrift::Fields {
+                expected: self.fields.clone(),
+                actual: field_names.to_vec(),
+            });
+        }
+
+        if let Some(expected) = &self.css {
+            if expected.trim() != css.trim() {
+                drift.push(ModelDrift::Css {
+                    expected: expected.clone(),
+                    actual: css.to_string(),
+                });
+            }
+        }
+
+        for (ord, spec) in self.templates.iter().enumerate() {
+            let ord = ord as i32;
+            match templates.iter().find(|t| t.ord == ord) {
+                Some(existing) => {
+                    if existing.question_format.trim() != spec.qfmt.trim()
+                        || existing.answer_format.trim() != spec.afmt.trim()
+                    {
+                        drift.push(ModelDrift::TemplateChanged {
+                            ord,
+                            name: spec.name.clone(),
+                        });
+                    }
+                }
+                None => drift.push(ModelDrift::TemplateMissing {
+                    ord,
+                    name: spec.name.clone(),
+                }),
+            }
+        }
+
+        for existing in templates {
+            if existing.ord < 0 || existing.ord as usize >= self.templates.len() {
+                drift.push(ModelDrift::ExtraTemplate {
+                    ord: existing.ord,
+                    name: existing.name.clone(),
+                });
+            }
+        }
+
+        drift
+    }
+}
+
+/// A difference between a [`ModelSpec`] and the model stored in AnkiDroid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ModelDrift {
+    /// The model's field names differ from the spec (never changed)
+    Fields {
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+    /// The model's CSS differs from the spec
+    Css { expected: String, actual: String },
+    /// The question or answer format of a template differs from the spec
+    TemplateChanged { ord: i32, name: String },
+    /// The spec has a template the model lacks
+    TemplateMissing { ord: i32, name: String },
+    /// The model has a template the spec does not describe (never removed)
+    ExtraTemplate { ord: i32, name: String },
+}
+
+/// What `ensure_model` does when the existing model has drifted from its spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftPolicy {
+    /// Only report the drift
+    #[default]
+    Report,
+    /// Update CSS and templates to match the spec
+    Apply,
+}
+
+/// Result of provisioning a model from a [`ModelSpec`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsureModelOutcome {
+    /// ID of the existing or newly created model
+    pub model_id: i64,
+    /// Whether the model was created by this call
+    pub created: bool,
+    /// Drift found on an existing model
+    pub drift: Vec<ModelDrift>,
+    /// Whether any drift was written to the model
+    pub applied: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(ord: i32, name: &str, qfmt: &str, afmt: &str) -> CardTemplate {
+        let mut template = CardTemplate::new(1, ord, name.to_string());
+        template.question_format = qfmt.to_string();
+        template.answer_format = afmt.to_string();
+        template
+    }
+
+    fn vocab_spec() -> ModelSpec {
+        ModelSpec::new("Vocab", vec!["Word".to_string(), "Meaning".to_string()])
+            .template("Forward", "{{Word}}", "{{Meaning}}")
+            .template("Reverse", "{{Meaning}}", "{{Word}}")
+            .css(".card { color: black; }")
+    }
+
+    #[test]
+    fn test_basic_spec() {
+        let spec = ModelSpec::basic("Basic");
+        assert_eq!(spec.fields, vec!["Front", "Back"]);
+        assert_eq!(spec.templates.len(), 1);
+        assert_eq!(spec.templates[0].qfmt, "{{Front}}");
+        assert!(spec.css.is_none());
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(vocab_spec().validate().is_ok());
+
+        let no_templates = ModelSpec::new("Vocab", vec!["Word".to_string()]);
+        assert!(no_templates.validate().is_err());
+
+        let duplicate_fields =
+            ModelSpec::new("Vocab", vec!["Word".to_string(), "Word".to_string()])
+                .template("Card 1", "{{Word}}", "{{Word}}");
+        assert!(duplicate_fields.validate().is_err());
+
+        let bad_sort = vocab_spec().sort_field(2);
+        assert!(bad_sort.validate().is_err());
+
+        let empty_name = ModelSpec::new(" ", vec!["Word".to_string()])
+            .template("Card 1", "{{Word}}", "{{Word}}");
+        assert!(empty_name.validate().is_err());
+    }
+
+    #[test]
+    fn test_matches_signature() {
+        let spec = vocab_spec();
+        assert!(spec.matches_signature("Vocab", &["Word".to_string(), "Meaning".to_string()]));
+        assert!(!spec.matches_signature("Vocab", &["Meaning".to_string(), "Word".to_string()]));
+        assert!(!spec.matches_signature("vocab", &["Word".to_string(), "Meaning".to_string()]));
+    }
+
+    #[test]
+    fn test_diff_no_drift() {
+        let spec = vocab_spec();
+        let templates = vec![
+            template(0, "Forward", "{{Word}}", "{{Meaning}}"),
+            template(1, "Renamed", "{{Meaning}}", "{{Word}}\n"),
+        ];
+        assert!(spec
+            .diff(&spec.fields, ".card { color: black; }\n", &templates)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_drift() {
+        let spec = vocab_spec();
+        let templates = vec![
+            template(0, "Forward", "{{Word}}!", "{{Meaning}}"),
+            template(2, "Extra", "{{Word}}", "{{Word}}"),
+        ];
+
+        let drift = spec.diff(&["Word".to_string()], "", &templates);
+        assert_eq!(drift.len(), 5);
+        assert_eq!(
+            drift[0],
+            ModelDrift::Fields {
+                expected: vec!["Word".to_string(), "Meaning".to_string()],
+                actual: vec!["Word".to_string()],
+            }
+        );
+        assert!(matches!(drift[1], ModelDrift::Css { .. }));
+        assert_eq!(
+            drift[2],
+            ModelDrift::TemplateChanged {
+                ord: 0,
+                name: "Forward".to_string()
+            }
+        );
+        assert_eq!(
+            drift[3],
+            ModelDrift::TemplateMissing {
+                ord: 1,
+                name: "Reverse".to_string()
+            }
+        );
+        assert_eq!(
+            drift[4],
+            ModelDrift::ExtraTemplate {
+                ord: 2,
+                name: "Extra".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_ignores_css_without_spec() {
+        let spec = ModelSpec::basic("Basic");
+        let templates = vec![template(
+            0,
+            "Card 1",
+            BasicModel::QFMT[0],
+            BasicModel::AFMT[0],
+        )];
+        assert!(spec.diff(&spec.fields, "anything", &templates).is_empty());
+    }
+
+    #[test]
+    fn test_spec_deserialize_defaults() {
+        let json = r#"{"name": "Vocab", "fields": ["Word"], "templates": [{"name": "Card 1", "qfmt": "{{Word}}", "afmt": "{{Word}}"}]}"#;
+        let spec: ModelSpec = serde_json::from_str(json).unwrap();
+        assert_eq!(spec.sort_field, 0);
+        assert!(!spec.cloze);
+        assert!(spec.css.is_none());
+    }
+
+    #[test]
+    fn test_drift_serialization() {
+        let drift = ModelDrift::TemplateMissing {
+            ord: 1,
+            name: "Reverse".to_string(),
+        };
+        let json = serde_json::to_value(&drift).unwrap();
+        assert_eq!(json["kind"], "template_missing");
+        assert_eq!(json["ord"], 1);
+    }
+}
@@ -6,7 +6,6 @@ package app.tauri.plugin
 
 import android.app.Activity
 import android.content.Intent
-import android.content.SharedPreferences
 import android.webkit.WebView
 import androidx.activity.result.IntentSenderRequest
 import androidx.core.app.ActivityCompat
@@ -18,8 +17,15 @@ import app.tauri.annotation.PermissionCallback
 import app.tauri.annotation.TauriPlugin
 import com.fasterxml.jackson.databind.ObjectMapper
 import java.lang.reflect.Method
+import androidx.core.content.edit
 
-class PluginHandle(private val manager: PluginManager, val name: String, val instance: Plugin, val config: String, val jsonMapper: ObjectMapper) {
+class PluginHandle(
+  private val manager: PluginManager,
+  val name: String,
+  val instance: Plugin,
+  val config: String,
+  val jsonMapper: ObjectMapper
+) {
   private val commands: HashMap<String, CommandData> = HashMap()
   private val permissionCallbackMethods: HashMap<String, Method> = HashMap()
   private val startActivityCallbackMethods: HashMap<String, Method> = HashMap()
@@ -47,7 +53,11 @@ class PluginHandle(private val manager: PluginManager, val name: String, val ins
     }
   }
 
-  fun startIntentSenderForResult(invoke: Invoke, intentSender: IntentSenderRequest, callbackName: String) {
+  fun startIntentSenderForResult(
+    invoke: Invoke,
+    intentSender: IntentSenderRequest,
+    callbackName: String
+  ) {
     manager.startIntentSenderForResult(intentSender) { result ->
       val method = startActivityCallbackMethods[callbackName]
       if (method != null) {
@@ -84,7 +94,7 @@ class PluginHandle(private val manager: PluginManager, val name: String, val ins
     invoke: Invoke,
     permissions: Map<String, Boolean>
   ): Boolean {
-    val activity = manager.activity
+    val activity = manager.activity!!
     val prefs =
       activity.getSharedPreferences("PluginPermStates", Activity.MODE_PRIVATE)
     for ((permString, isGranted) in permissions) {
@@ -92,24 +102,24 @@ class PluginHandle(private val manager: PluginManager, val name: String, val ins
         // Permission granted. If previously denied, remove cached state
         val state = prefs.getString(permString, null)
         if (state != null) {
-          val editor: SharedPreferences.Editor = prefs.edit()
-          editor.remove(permString)
-          editor.apply()
+          prefs.edit {
+            remove(permString)
+          }
         }
       } else {
-        val editor: SharedPreferences.Editor = prefs.edit()
-        if (ActivityCompat.shouldShowRequestPermissionRationale(
-            activity,
-            permString
-          )
-        ) {
-          // Permission denied, can prompt again with rationale
-          editor.putString(permString, PermissionState.PROMPT_WITH_RATIONALE.toString())
-        } else {
-          // Permission denied permanently, store this state for future reference
-          editor.putString(permString, PermissionState.DENIED.toString())
+        prefs.edit {
+          if (ActivityCompat.shouldShowRequestPermissionRationale(
+              activity,
+              permString
+            )
+          ) {
+            // Permission denied, can prompt again with rationale
+            putString(permString, PermissionState.PROMPT_WITH_RATIONALE.toString())
+          } else {
+            // Permission denied permanently, store this state for future reference
+            putString(permString, PermissionState.DENIED.toString())
+          }
         }
-        editor.apply()
       }
     }
     val permStrings = permissions.keys.toTypedArray()
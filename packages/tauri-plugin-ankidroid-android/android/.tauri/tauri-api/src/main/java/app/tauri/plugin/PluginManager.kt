@@ -4,7 +4,7 @@
 
 package app.tauri.plugin
 
-import android.app.PendingIntent
+import android.content.res.Configuration
 import android.content.Context
 import android.content.Intent
 import android.webkit.WebView
@@ -17,13 +17,16 @@ import app.tauri.annotation.InvokeArg
 import app.tauri.FsUtils
 import app.tauri.JniMethod
 import app.tauri.Logger
+import com.fasterxml.jackson.annotation.JsonAutoDetect
+import com.fasterxml.jackson.annotation.PropertyAccessor
 import com.fasterxml.jackson.databind.DeserializationFeature
 import com.fasterxml.jackson.databind.JsonNode
 import com.fasterxml.jackson.databind.ObjectMapper
+import com.fasterxml.jackson.datatype.jsonorg.JsonOrgModule
 import com.fasterxml.jackson.databind.module.SimpleModule
 import java.lang.reflect.InvocationTargetException
 
-class PluginManager(val activity: AppCompatActivity) {
+object PluginManager {
   fun interface RequestPermissionsCallback {
     fun onResult(permissions: Map<String, Boolean>)
   }
@@ -32,50 +35,69 @@ class PluginManager(val activity: AppCompatActivity) {
     fun onResult(result: ActivityResult)
   }
 
+  /** The result launchers belonging to one activity. */
+  private class ResultLaunchers(
+    val startActivityForResult: ActivityResultLauncher<Intent>,
+    val startIntentSenderForResult: ActivityResultLauncher<IntentSenderRequest>,
+    val requestPermissions: ActivityResultLauncher<Array<String>>
+  )
+
+  // Insertion ordered, so the activity taken over when the current one goes away is the oldest
+  // surviving one rather than an arbitrary member of a hash set.
+  private val launchers: LinkedHashMap<AppCompatActivity, ResultLaunchers> = LinkedHashMap()
+  var activity: AppCompatActivity? = null
   private val plugins: HashMap<String, PluginHandle> = HashMap()
-  private val startActivityForResultLauncher: ActivityResultLauncher<Intent>
-  private val startIntentSenderForResultLauncher: ActivityResultLauncher<IntentSenderRequest>
-  private val requestPermissionsLauncher: ActivityResultLauncher<Array<String>>
   private var requestPermissionsCallback: RequestPermissionsCallback? = null
   private var startActivityForResultCallback: ActivityResultCallback? = null
   private var startIntentSenderForResultCallback: ActivityResultCallback? = null
-  private var jsonMapper: ObjectMapper
+  private var jsonMapper: ObjectMapper = ObjectMapper()
+    .disable(DeserializationFeature.FAIL_ON_UNKNOWN_PROPERTIES)
+    .enable(DeserializationFeature.FAIL_ON_NULL_FOR_PRIMITIVES)
+    .setVisibility(PropertyAccessor.FIELD, JsonAutoDetect.Visibility.ANY)
 
   init {
-    startActivityForResultLauncher =
+    val channelDeserializer = ChannelDeserializer({ channelId, payload ->
+      sendChannelData(channelId, payload)
+    }, jsonMapper)
+    jsonMapper
+      .registerModule(SimpleModule().addDeserializer(Channel::class.java, channelDeserializer))
+      // Without it, the mapper's field visibility serializes an org.json value through
+      // its private fields: a `JSArray` as `{"values":[...]}` and a `JSObject` as
+      // `{"nameValuePairs":{...}}`.
+      .registerModule(JsonOrgModule())
+  }
+
+  fun onCreate(activity: AppCompatActivity) {
+    // Every activity gets its own launchers, and gets them here: registerForActivityResult must
+    // be called before its owner reaches STARTED, so an activity that is already running can
+    // never be given launchers later.
+    launchers[activity] = registerResultLaunchers(activity)
+    if (this.activity == null) {
+      this.activity = activity
+    }
+  }
+
+  private fun registerResultLaunchers(activity: AppCompatActivity): ResultLaunchers =
+    ResultLaunchers(
       activity.registerForActivityResult(ActivityResultContracts.StartActivityForResult()
       ) { result ->
-        if (startActivityForResultCallback != null) {
-          startActivityForResultCallback!!.onResult(result)
-        }
-      }
+        startActivityForResultCallback?.onResult(result)
+      },
 
-    startIntentSenderForResultLauncher =
       activity.registerForActivityResult(ActivityResultContracts.StartIntentSenderForResult()
       ) { result ->
-        if (startIntentSenderForResultCallback != null) {
-          startIntentSenderForResultCallback!!.onResult(result)
-        }
-      }
+        startIntentSenderForResultCallback?.onResult(result)
+      },
 
-    requestPermissionsLauncher =
       activity.registerForActivityResult(ActivityResultContracts.RequestMultiplePermissions()
       ) { result ->
-        if (requestPermissionsCallback != null) {
-          requestPermissionsCallback!!.onResult(result)
-        }
+        requestPermissionsCallback?.onResult(result)
       }
+    )
 
-    jsonMapper = ObjectMapper()
-      .disable(DeserializationFeature.FAIL_ON_UNKNOWN_PROPERTIES)
-      .enable(DeserializationFeature.FAIL_ON_NULL_FOR_PRIMITIVES)
-
-    val channelDeserializer = ChannelDeserializer({ channelId, payload ->
-      sendChannelData(channelId, payload)
-    }, jsonMapper)
-    jsonMapper
-      .registerModule(SimpleModule().addDeserializer(Channel::class.java, channelDeserializer))
-  }
+  private val currentLaunchers: ResultLaunchers
+    get() = launchers[activity]
+      ?: throw IllegalStateException("the plugin manager has no activity to launch from")
 
   fun onNewIntent(intent: Intent) {
     for (plugin in plugins.values) {
@@ -83,26 +105,58 @@ class PluginManager(val activity: AppCompatActivity) {
     }
   }
 
-  fun onPause() {
+  fun onPause(activity: AppCompatActivity) {
+    for (plugin in plugins.values) {
+      plugin.instance.triggerOnPause(activity)
+    }
+  }
+
+  fun onResume(activity: AppCompatActivity) {
     for (plugin in plugins.values) {
-      plugin.instance.onPause()
+      plugin.instance.triggerOnResume(activity)
     }
   }
 
-  fun onResume() {
+  fun onRestart(activity: AppCompatActivity) {
     for (plugin in plugins.values) {
-      plugin.instance.onResume()
+      plugin.instance.triggerOnRestart(activity)
+    }
+  }
+
+  fun onStop(activity: AppCompatActivity) {
+    for (plugin in plugins.values) {
+      plugin.instance.triggerOnStop(activity)
+    }
+  }
+
+  fun onDestroy(activity: AppCompatActivity) {
+    for (plugin in plugins.values) {
+      plugin.instance.triggerOnDestroy(activity)
+    }
+
+    launchers.remove(activity)
+    if (this.activity == activity) {
+      // Whatever is left already holds its own launchers, registered when it was created. Moving
+      // this activity's launchers over instead would mean registering against an activity that is
+      // already running, which registerForActivityResult rejects with an IllegalStateException.
+      this.activity = launchers.keys.firstOrNull()
+    }
+  }
+
+  fun onConfigurationChanged(newConfig: Configuration) {
+    for (plugin in plugins.values) {
+      plugin.instance.onConfigurationChanged(newConfig)
     }
   }
 
   fun startActivityForResult(intent: Intent, callback: ActivityResultCallback) {
     startActivityForResultCallback = callback
-    startActivityForResultLauncher.launch(intent)
+    currentLaunchers.startActivityForResult.launch(intent)
   }
 
   fun startIntentSenderForResult(intent: IntentSenderRequest, callback: ActivityResultCallback) {
     startIntentSenderForResultCallback = callback
-    startIntentSenderForResultLauncher.launch(intent)
+    currentLaunchers.startIntentSenderForResult.launch(intent)
   }
 
   fun requestPermissions(
@@ -110,7 +164,7 @@ class PluginManager(val activity: AppCompatActivity) {
     callback: RequestPermissionsCallback
   ) {
     requestPermissionsCallback = callback
-    requestPermissionsLauncher.launch(permissionStrings)
+    currentLaunchers.requestPermissions.launch(permissionStrings)
   }
 
   @JniMethod
@@ -173,14 +227,12 @@ class PluginManager(val activity: AppCompatActivity) {
     }
   }
 
-  companion object {
-    fun<T> loadConfig(context: Context, plugin: String, cls: Class<T>): T {
-      val tauriConfigJson = FsUtils.readAsset(context.assets, "tauri.conf.json")
-      val mapper = ObjectMapper()
-        .configure(DeserializationFeature.FAIL_ON_UNKNOWN_PROPERTIES, false)
-      val config = mapper.readValue(tauriConfigJson, Config::class.java)
-      return mapper.readValue(config.plugins[plugin].toString(), cls)
-    }
+  fun<T> loadConfig(context: Context, plugin: String, cls: Class<T>): T {
+    val tauriConfigJson = FsUtils.readAsset(context.assets, "tauri.conf.json")
+    val mapper = ObjectMapper()
+      .configure(DeserializationFeature.FAIL_ON_UNKNOWN_PROPERTIES, false)
+    val config = mapper.readValue(tauriConfigJson, Config::class.java)
+    return mapper.readValue(config.plugins[plugin].toString(), cls)
   }
 
   private external fun handlePluginResponse(id: Int, success: String?, error: String?)
@@ -1 +1,12 @@
-# Consumer ProGuard rules for tauri-plugin-ankidroid
\ No newline at end of file
+# Consumer ProGuard rules for tauri-plugin-ankidroid
+
+# Created and called from Rust through JNI
+-keep class app.tauri.ankidroid.CollectionObserver { *; }
+
+# Registered from Rust as the plugin's Kotlin class
+-keep class app.tauri.ankidroid.AnkiDroidPlugin { *; }
+
+# Looked up from Rust through JNI to share staged media
+-keep class androidx.core.content.FileProvider {
+    public static android.net.Uri getUriForFile(android.content.Context, java.lang.String, java.io.File);
+}
@@ -0,0 +1,11 @@
+package app.tauri.ankidroid
+
+import androidx.core.content.FileProvider
+
+/**
+ * Shares media staged in the app cache with AnkiDroid.
+ *
+ * A subclass so the plugin's provider does not clash with a FileProvider the app
+ * declares itself.
+ */
+class AnkiDroidFileProvider : FileProvider()
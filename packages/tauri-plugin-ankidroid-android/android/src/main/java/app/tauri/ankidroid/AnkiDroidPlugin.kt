@@ -0,0 +1,24 @@
+package app.tauri.ankidroid
+
+import android.app.Activity
+import app.tauri.annotation.Permission
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Plugin
+
+/**
+ * Kotlin side of the plugin, registered from Rust when the plugin is set up.
+ *
+ * AnkiDroid's database permission is declared here so that Tauri's built-in
+ * `checkPermissions` and `requestPermissions` commands report and request it
+ * under the `ankidroid` alias. `requestPermissions` resolves once the user has
+ * answered the system dialog. Everything else goes through JNI from Rust.
+ */
+@TauriPlugin(
+    permissions = [
+        Permission(
+            strings = ["com.ichi2.anki.permission.READ_WRITE_DATABASE"],
+            alias = "ankidroid"
+        )
+    ]
+)
+class AnkiDroidPlugin(private val activity: Activity) : Plugin(activity)
@@ -0,0 +1,25 @@
+package app.tauri.ankidroid
+
+import android.database.ContentObserver
+import android.net.Uri
+import android.os.Handler
+import android.os.Looper
+
+/**
+ * Forwards changes to an AnkiDroid content provider URI to the Rust side of the plugin.
+ *
+ * Instances are created, registered and unregistered from Rust through JNI.
+ */
+class CollectionObserver(private val watchedUri: String) :
+    ContentObserver(Handler(Looper.getMainLooper())) {
+
+    override fun onChange(selfChange: Boolean) {
+        onChange(selfChange, null)
+    }
+
+    override fun onChange(selfChange: Boolean, uri: Uri?) {
+        nativeOnChange(uri?.toString() ?: watchedUri)
+    }
+
+    private external fun nativeOnChange(uri: String)
+}
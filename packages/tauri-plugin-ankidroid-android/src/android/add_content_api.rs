@@ -16,17 +16,14 @@ impl<'local> AddContentApi<'local> {
     /// Instead, it prepares a wrapper for ContentResolver-based operations
     pub fn new(env: SafeJNIEnv<'local>, context: &JObject<'local>) -> AndroidResult<Self> {
         log::info!("Creating AddContentApi wrapper (ContentResolver-based)...");
-        
+
         // We don't instantiate AddContentApi class here since it's in AnkiDroid app
         // Instead, we'll use ContentResolver for all operations
         // For now, just store a copy of the context reference as our "api_instance"
         let api_instance = unsafe { JObject::from_raw(context.as_raw()) };
-        
+
         log::info!("✅ AddContentApi wrapper created");
-        Ok(Self {
-            api_instance,
-            env,
-        })
+        Ok(Self { api_instance, env })
     }
 
     /// Add a note to AnkiDroid
@@ -66,7 +63,7 @@ impl<'local> AddContentApi<'local> {
     pub fn is_available(env: &mut SafeJNIEnv, context: &JObject) -> bool {
         // For external apps, check if AnkiDroid is installed via PackageManager
         // We don't check for AddContentApi class since it's in AnkiDroid app, not ours
-        
+
         // Try to get PackageManager to check if AnkiDroid is installed
         let pm_result = env.env_mut().call_method(
             context,
@@ -74,7 +71,7 @@ impl<'local> AddContentApi<'local> {
             "()Landroid/content/pm/PackageManager;",
             &[],
         );
-        
+
         let package_manager = match pm_result {
             Ok(result) => match result.l() {
                 Ok(pm) if !pm.is_null() => pm,
@@ -82,13 +79,13 @@ impl<'local> AddContentApi<'local> {
             },
             Err(_) => return false,
         };
-        
+
         // Check if AnkiDroid package is installed
         let package_name = match env.new_string_checked("com.ichi2.anki") {
             Ok(name) => name,
             Err(_) => return false,
         };
-        
+
         // Try to get package info (will fail if not installed)
         let package_info_result = env.env_mut().call_method(
             &package_manager,
@@ -96,22 +93,22 @@ impl<'local> AddContentApi<'local> {
             "(Ljava/lang/String;I)Landroid/content/pm/PackageInfo;",
             &[JValue::Object(&package_name.into()), JValue::Int(0)],
         );
-        
+
         match package_info_result {
             Ok(result) => match result.l() {
                 Ok(info) => {
                     log::debug!("AnkiDroid package found: com.ichi2.anki");
                     !info.is_null()
-                },
+                }
                 Err(_) => {
                     log::debug!("AnkiDroid package not found");
                     false
-                },
+                }
             },
             Err(_) => {
                 log::debug!("Failed to check for AnkiDroid package");
                 false
-            },
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,7 @@
+use crate::android::constants::ANKIDROID_PACKAGE;
 use crate::android::error::{AndroidError, AndroidResult, JniResultExt};
 use crate::android::jni_helpers::SafeJNIEnv;
+use ankidroid_api_rust::READ_WRITE_PERMISSION;
 use jni::objects::{JObject, JValue};
 
 /// Check if AnkiDroid is available on the device
@@ -34,18 +36,14 @@ pub fn check_ankidroid_available(env: &mut SafeJNIEnv, context: &JObject) -> And
     Ok(is_available)
 }
 
-/// Check if we have permission to access AnkiDroid
+/// Check if we have permission to access AnkiDroid's database
+///
+/// Requesting the permission goes through Tauri's `requestPermissions` on the
+/// Kotlin side of the plugin, which resolves once the user has answered.
 pub fn check_permission(env: &mut SafeJNIEnv, context: &JObject) -> AndroidResult<bool> {
     log::debug!("Checking AnkiDroid permission");
 
-    // Check if AnkiDroid is available first
-    if !check_ankidroid_available(env, context)? {
-        return Ok(false);
-    }
-
-    // Check for permission using checkSelfPermission
-    let permission_string =
-        env.new_string_checked("com.ichi2.anki.permission.READ_WRITE_DATABASE")?;
+    let permission_string = env.new_string_checked(READ_WRITE_PERMISSION)?;
 
     let permission_result = env
         .env_mut()
@@ -61,48 +59,11 @@ pub fn check_permission(env: &mut SafeJNIEnv, context: &JObject) -> AndroidResul
 
     // PackageManager.PERMISSION_GRANTED = 0
     let has_permission = permission_result == 0;
-    log::info!("AnkiDroid permission granted: {}", has_permission);
+    log::debug!("AnkiDroid permission granted: {}", has_permission);
 
     Ok(has_permission)
 }
 
-/// Request permission to access AnkiDroid
-pub fn request_permission(env: &mut SafeJNIEnv, activity: &JObject) -> AndroidResult<()> {
-    log::info!("Requesting AnkiDroid permission");
-
-    // Create string array with the permission
-    let permission_string =
-        env.new_string_checked("com.ichi2.anki.permission.READ_WRITE_DATABASE")?;
-    let permission_string_2 =
-        env.new_string_checked("com.ichi2.anki.permission.READ_WRITE_DATABASE")?;
-    let _string_array_class = env.find_class_checked("[Ljava/lang/String;")?;
-    let permissions_array = env
-        .env_mut()
-        .new_object_array(1, "java/lang/String", &JObject::from(permission_string))
-        .check_exception(env.env_mut())?;
-
-    env.env_mut()
-        .set_object_array_element(&permissions_array, 0, JObject::from(permission_string_2))
-        .check_exception(env.env_mut())?;
-
-    // Request the permission
-    let request_code = 1337; // Arbitrary request code
-    env.env_mut()
-        .call_method(
-            activity,
-            "requestPermissions",
-            "([Ljava/lang/String;I)V",
-            &[
-                JValue::Object(&permissions_array.into()),
-                JValue::Int(request_code),
-            ],
-        )
-        .check_exception(env.env_mut())?;
-
-    log::info!("Permission request initiated");
-    Ok(())
-}
-
 /// Get the API version of AnkiDroid
 pub fn get_api_version(env: &mut SafeJNIEnv, context: &JObject) -> AndroidResult<Option<i32>> {
     log::debug!("Getting AnkiDroid API version");
@@ -256,15 +217,25 @@ pub fn is_ankidroid_running(env: &mut SafeJNIEnv, context: &JObject) -> AndroidR
     Ok(false)
 }
 
-/// Launch AnkiDroid if it's not running
-pub fn launch_ankidroid(env: &mut SafeJNIEnv, context: &JObject) -> AndroidResult<()> {
-    log::info!("Launching AnkiDroid");
+/// Activity AnkiDroid routes launcher, shortcut and reminder intents through
+const INTENT_HANDLER: &str = "com.ichi2.anki.IntentHandler";
 
-    if !check_ankidroid_available(env, context)? {
-        return Err(AndroidError::AnkiDroidNotAvailable(
-            "AnkiDroid is not installed".to_string(),
-        ));
-    }
+/// Extra that makes `IntentHandler` select a deck and open the reviewer on it
+///
+/// AnkiDroid's deck shortcuts are built the same way.
+const EXTRA_DECK_ID: &str = "EXTRA_DECK_ID";
+
+/// Launch AnkiDroid, in the reviewer of a deck when one is given
+///
+/// With a deck ID, `IntentHandler` selects the deck and starts the reviewer on
+/// it. Without one AnkiDroid starts as if opened from the launcher, which shows
+/// the deck list.
+pub fn launch_ankidroid(
+    env: &mut SafeJNIEnv,
+    context: &JObject,
+    deck_id: Option<i64>,
+) -> AndroidResult<()> {
+    log::info!("Launching AnkiDroid (deck: {:?})", deck_id);
 
     // Create intent to launch AnkiDroid
     let intent_class = env.find_class_checked("android/content/Intent")?;
@@ -273,27 +244,32 @@ pub fn launch_ankidroid(env: &mut SafeJNIEnv, context: &JObject) -> AndroidResul
         .new_object(&intent_class, "()V", &[])
         .check_exception(env.env_mut())?;
 
-    // Set action to MAIN
-    let action_main = env.new_string_checked("android.intent.action.MAIN")?;
+    // Target AnkiDroid's intent handler by component
+    let package_name = env.new_string_checked(ANKIDROID_PACKAGE)?;
+    let class_name = env.new_string_checked(INTENT_HANDLER)?;
     env.env_mut()
         .call_method(
             &intent,
-            "setAction",
-            "(Ljava/lang/String;)Landroid/content/Intent;",
-            &[JValue::Object(&action_main.into())],
+            "setClassName",
+            "(Ljava/lang/String;Ljava/lang/String;)Landroid/content/Intent;",
+            &[
+                JValue::Object(&package_name.into()),
+                JValue::Object(&class_name.into()),
+            ],
         )
         .check_exception(env.env_mut())?;
 
-    // Set package to AnkiDroid
-    let package_name = env.new_string_checked("com.ichi2.anki")?;
-    env.env_mut()
-        .call_method(
-            &intent,
-            "setPackage",
-            "(Ljava/lang/String;)Landroid/content/Intent;",
-            &[JValue::Object(&package_name.into())],
-        )
-        .check_exception(env.env_mut())?;
+    if let Some(deck_id) = deck_id {
+        let extra = env.new_string_checked(EXTRA_DECK_ID)?;
+        env.env_mut()
+            .call_method(
+                &intent,
+                "putExtra",
+                "(Ljava/lang/String;J)Landroid/content/Intent;",
+                &[JValue::Object(&extra.into()), JValue::Long(deck_id)],
+            )
+            .check_exception(env.env_mut())?;
+    }
 
     // Add FLAG_ACTIVITY_NEW_TASK
     let flag = 0x10000000; // FLAG_ACTIVITY_NEW_TASK
@@ -306,7 +282,7 @@ pub fn launch_ankidroid(env: &mut SafeJNIEnv, context: &JObject) -> AndroidResul
         )
         .check_exception(env.env_mut())?;
 
-    // Start the activity
+    // Start the activity, which throws ActivityNotFoundException without AnkiDroid
     env.env_mut()
         .call_method(
             context,
@@ -314,7 +290,14 @@ pub fn launch_ankidroid(env: &mut SafeJNIEnv, context: &JObject) -> AndroidResul
             "(Landroid/content/Intent;)V",
             &[JValue::Object(&intent)],
         )
-        .check_exception(env.env_mut())?;
+        .check_exception(env.env_mut())
+        .map_err(|e| {
+            if e.to_string().contains("ActivityNotFoundException") {
+                AndroidError::AnkiDroidNotAvailable("AnkiDroid is not installed".to_string())
+            } else {
+                e
+            }
+        })?;
 
     log::info!("AnkiDroid launch initiated");
     Ok(())
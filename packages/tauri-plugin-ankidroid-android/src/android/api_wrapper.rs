@@ -1,61 +1,86 @@
-use ankidroid_api_rust::{AnkiDroidApi, AnkiDroidError};
-use jni::{objects::JObject, JavaVM};
-use std::ops::{Deref, DerefMut};
+use super::worker;
+use crate::error::PluginError;
+use crate::retry;
+use ankidroid_api_rust::AnkiDroidApi;
+use std::sync::Arc;
 
-/// Get an AnkiDroid API instance using a callback to avoid lifetime issues
-/// The callback receives the API instance and should perform all operations within it
-pub fn with_api_instance<F, R>(callback: F) -> Result<R, String>
+/// Run a callback against the shared AnkiDroid API instance
+///
+/// The callback is sent to the JNI worker thread, which owns the attached
+/// environment and a long-lived `AnkiDroidApi`. Callbacks run one at a time, so
+/// concurrent commands are serialized. The callback must own everything it uses
+/// because it runs on another thread.
+pub async fn with_api_instance<F, R>(callback: F) -> Result<R, PluginError>
 where
-    F: for<'local> FnOnce(&mut AnkiDroidApi<'local>) -> Result<R, String>,
+    F: for<'local> FnOnce(&mut AnkiDroidApi<'local>) -> Result<R, PluginError> + Send + 'static,
+    R: Send + 'static,
 {
-    // Get Android context from ndk_context
-    let ctx = ndk_context::android_context();
-    
-    // Attach current thread to JVM
-    let vm = unsafe { JavaVM::from_raw(ctx.vm() as _) }
-        .map_err(|e| format!("Failed to create JavaVM: {}", e))?;
-    let mut env_guard = vm.attach_current_thread()
-        .map_err(|e| format!("Failed to attach thread: {}", e))?;
-
-    // Create JObject from raw context pointer within the proper lifetime
-    let context = unsafe { 
-        JObject::from_raw(ctx.context() as jni::sys::jobject) 
-    };
-    
-    // Get a mutable reference to the JNIEnv from the guard
-    let env = env_guard.deref_mut();
-    
-    // Create the API instance with proper lifetimes
-    // Clone the JNIEnv to get an owned value
-    let env_owned = unsafe { std::ptr::read(env as *const _) };
-    let mut api = AnkiDroidApi::try_new(env_owned, &context)
-        .map_err(|e| format!("Failed to initialize AnkiDroid API: {}", e))?;
-
-    // Execute the callback with the API instance
-    callback(&mut api)
+    let (reply, result) = worker::reply_channel();
+
+    worker::global()?.submit(Box::new(move |api| {
+        reply.send(api.and_then(callback));
+    }))?;
+
+    result.await
+}
+
+/// Run a callback against the shared API instance, retrying transient failures
+///
+/// Follows the policy in [`retry::settings`]. Each attempt is a separate job, so
+/// other commands can run between attempts and a failed API initialization is
+/// retried too. Backoff waits on the async runtime's timer, which holds up
+/// neither the worker thread nor a runtime thread. Only use this for reads and
+/// updates keyed by ID, which are safe to repeat.
+pub async fn with_api_retry<F, R>(callback: F) -> Result<R, PluginError>
+where
+    F: for<'local> Fn(&mut AnkiDroidApi<'local>) -> Result<R, PluginError> + Send + Sync + 'static,
+    R: Send + 'static,
+{
+    let policy = *retry::settings();
+    let callback = Arc::new(callback);
+
+    retry::retry(
+        &policy,
+        || {
+            let callback = callback.clone();
+            with_api_instance(move |api| callback(api))
+        },
+        tokio::time::sleep,
+    )
+    .await
+}
+
+/// Queue a callback on the worker thread without waiting for it
+///
+/// For callers without an async context, such as plugin teardown. Failures are
+/// logged.
+pub fn submit_to_api_instance<F>(callback: F) -> Result<(), PluginError>
+where
+    F: for<'local> FnOnce(&mut AnkiDroidApi<'local>) -> Result<(), PluginError> + Send + 'static,
+{
+    worker::global()?.submit(Box::new(move |api| {
+        if let Err(e) = api.and_then(callback) {
+            log::error!("Queued AnkiDroid job failed: {}", e);
+        }
+    }))
+}
+
+/// Queue work on the worker thread that does not need the API instance
+///
+/// It runs even if the API cannot be created, e.g. before the permission is
+/// granted.
+pub fn submit_to_worker<F>(callback: F) -> Result<(), PluginError>
+where
+    F: FnOnce() + Send + 'static,
+{
+    worker::global()?.submit(Box::new(move |_api| callback()))
 }
 
 /// Legacy function that returns individual components (deprecated)
 /// This is kept for backward compatibility but has lifetime issues
 pub fn get_api_instance() -> Result<Box<dyn Fn() -> Result<(), String>>, String> {
-    Err("This function is deprecated due to lifetime issues. Use with_api_instance instead.".to_string())
+    Err(
+        "This function is deprecated due to lifetime issues. Use with_api_instance instead."
+            .to_string(),
+    )
 }
-
-/// Convert AnkiDroidError to a user-friendly error message
-pub fn format_error(error: AnkiDroidError) -> String {
-    match error {
-        AnkiDroidError::AnkiDroidNotAvailable(msg) => format!("AnkiDroid is not available: {}", msg),
-        AnkiDroidError::PermissionDenied(msg) => format!("Permission denied: {}", msg),
-        AnkiDroidError::InvalidModelId(id) => format!("Invalid model ID: {}", id),
-        AnkiDroidError::InvalidDeckId(id) => format!("Invalid deck ID: {}", id),
-        AnkiDroidError::DuplicateNote(msg) => format!("Duplicate note: {}", msg),
-        AnkiDroidError::FieldCountMismatch { expected, actual } => format!("Field count mismatch: expected {}, got {}", expected, actual),
-        AnkiDroidError::JniError(msg) => format!("JNI error: {}", msg),
-        AnkiDroidError::NullPointer(msg) => format!("Null pointer: {}", msg),
-        AnkiDroidError::StringConversionError(msg) => format!("String conversion error: {}", msg),
-        AnkiDroidError::DatabaseError(msg) => format!("Database error: {}", msg),
-        AnkiDroidError::ValidationError(msg) => format!("Validation error: {}", msg),
-        AnkiDroidError::IoError(msg) => format!("I/O error: {}", msg),
-        AnkiDroidError::JsonError(msg) => format!("JSON error: {}", msg),
-    }
-}
\ No newline at end of file
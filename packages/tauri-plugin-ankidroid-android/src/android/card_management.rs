@@ -11,8 +11,7 @@ pub fn suspend_card(mut env: SafeJNIEnv, activity: &JObject, card_id: i64) -> An
 
     // Build ContentValues with suspend flag
     let mut env_for_values = env.clone();
-    let values = ContentValuesBuilder::new(&mut env_for_values)?
-        .put_int("queue", -1)?; // -1 indicates suspended
+    let values = ContentValuesBuilder::new(&mut env_for_values)?.put_int("queue", -1)?; // -1 indicates suspended
 
     // Update the card
     let updated_rows = update(env, CARDS_URI)
@@ -35,8 +34,7 @@ pub fn unsuspend_card(
 
     // Build ContentValues to restore to new queue
     let mut env_for_values = env.clone();
-    let values = ContentValuesBuilder::new(&mut env_for_values)?
-        .put_int("queue", 0)?; // 0 indicates new/learning
+    let values = ContentValuesBuilder::new(&mut env_for_values)?.put_int("queue", 0)?; // 0 indicates new/learning
 
     // Update the card
     let updated_rows = update(env, CARDS_URI)
@@ -55,8 +53,7 @@ pub fn bury_card(mut env: SafeJNIEnv, activity: &JObject, card_id: i64) -> Andro
 
     // Build ContentValues with bury queue
     let mut env_for_values = env.clone();
-    let values = ContentValuesBuilder::new(&mut env_for_values)?
-        .put_int("queue", -2)?; // -2 indicates buried
+    let values = ContentValuesBuilder::new(&mut env_for_values)?.put_int("queue", -2)?; // -2 indicates buried
 
     // Update the card
     let updated_rows = update(env, CARDS_URI)
@@ -75,8 +72,7 @@ pub fn unbury_card(mut env: SafeJNIEnv, activity: &JObject, card_id: i64) -> And
 
     // Build ContentValues to restore to new queue
     let mut env_for_values = env.clone();
-    let values = ContentValuesBuilder::new(&mut env_for_values)?
-        .put_int("queue", 0)?; // 0 indicates new/learning
+    let values = ContentValuesBuilder::new(&mut env_for_values)?.put_int("queue", 0)?; // 0 indicates new/learning
 
     // Update the card
     let updated_rows = update(env, CARDS_URI)
@@ -100,8 +96,7 @@ pub fn change_card_deck(
 
     // Build ContentValues with new deck ID
     let mut env_for_values = env.clone();
-    let values = ContentValuesBuilder::new(&mut env_for_values)?
-        .put_long("did", deck_id)?;
+    let values = ContentValuesBuilder::new(&mut env_for_values)?.put_long("did", deck_id)?;
 
     // Update the card
     let updated_rows = update(env, CARDS_URI)
@@ -214,8 +209,7 @@ pub fn set_card_due_date(
 
     // Build ContentValues with new due date
     let mut env_for_values = env.clone();
-    let values = ContentValuesBuilder::new(&mut env_for_values)?
-        .put_int("due", due_value)?;
+    let values = ContentValuesBuilder::new(&mut env_for_values)?.put_int("due", due_value)?;
 
     // Update the card
     let updated_rows = update(env, CARDS_URI)
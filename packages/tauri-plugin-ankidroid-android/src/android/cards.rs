@@ -6,6 +6,7 @@ use crate::android::decks::get_or_create_deck_id;
 use crate::android::error::{AndroidError, AndroidResult};
 use crate::android::jni_helpers::{ContentValuesBuilder, SafeJNIEnv};
 use crate::android::models::{find_basic_model_id, validate_model_for_cards};
+use crate::decks::DeckMatch;
 use jni::objects::JObject;
 
 /// Create a new card (note) in AnkiDroid
@@ -41,7 +42,7 @@ pub fn create_card(
 
     // Get or create deck - AVOID cloning env here to prevent reference issues
     log::info!("🏗️ Getting or creating deck: {:?}", deck_name);
-    let deck_id = match get_or_create_deck_id(&mut env, activity, deck_name) {
+    let deck_id = match get_or_create_deck_id(&mut env, activity, deck_name, DeckMatch::Exact) {
         Ok(id) => {
             log::info!("✅ Deck operation successful - using deck ID: {}", id);
             id
@@ -49,7 +50,7 @@ pub fn create_card(
         Err(e) => {
             log::error!("❌ CRITICAL: Deck operation failed with error: {}", e);
             log::error!("❌ Error type: {:?}", e);
-            
+
             // Check for and clear any JNI exceptions
             if env.env().exception_check().unwrap_or(false) {
                 log::error!("🔥 JNI EXCEPTION DETECTED during deck operation!");
@@ -91,63 +92,63 @@ pub fn create_card(
 
     // Try direct ContentProvider approach with mid in ContentValues
     log::info!("🎯 Attempting to create note using direct ContentProvider with mid...");
-    
+
     // Format fields with proper separator (0x1f)
     let fields = format!("{}{}{}", front, FIELD_SEPARATOR, back);
     let tags_str = tags.unwrap_or("").trim();
     log::info!("📝 Formatted fields: '{}', tags: '{}'", fields, tags_str);
-    
+
     // Create ContentValues INCLUDING the model ID (mid)
     log::info!("🏗️ Creating ContentValues with mid={}...", model_id);
     let mut env_for_values = env.clone();
     let values_builder = match ContentValuesBuilder::new(&mut env_for_values) {
         Ok(mut builder) => {
             log::info!("✅ ContentValuesBuilder created");
-            
+
             // IMPORTANT: Include mid (model ID) in the ContentValues
             builder = builder.put_long(note_columns::MID, model_id)?;
             log::info!("✅ Added mid={} to ContentValues", model_id);
-            
+
             // Add the fields
             builder = builder.put_string(note_columns::FLDS, &fields)?;
             log::info!("✅ Added flds to ContentValues");
-            
+
             // Add tags if provided
             if !tags_str.is_empty() {
                 builder = builder.put_string(note_columns::TAGS, tags_str)?;
                 log::info!("✅ Added tags to ContentValues");
             }
-            
+
             // Add other required fields that might be needed
             let timestamp = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .map(|d| d.as_secs() as i64)
                 .unwrap_or(0);
-            
+
             builder = builder.put_long(note_columns::MOD, timestamp)?;
             log::info!("✅ Added mod (timestamp) to ContentValues");
-            
+
             // guid is usually generated by AnkiDroid, but we can provide one
             let guid = format!("tpa{}", timestamp); // tauri-plugin-ankidroid prefix
             builder = builder.put_string(note_columns::GUID, &guid)?;
             log::info!("✅ Added guid to ContentValues");
-            
+
             // sfld (sort field) is usually the first field
             builder = builder.put_string(note_columns::SFLD, front)?;
             log::info!("✅ Added sfld to ContentValues");
-            
+
             // usn (update sequence number) - 0 for new notes
             builder = builder.put_int(note_columns::USQN, 0)?;
             log::info!("✅ Added usn to ContentValues");
-            
+
             // flags - default to 0
             builder = builder.put_int(note_columns::FLAGS, 0)?;
             log::info!("✅ Added flags to ContentValues");
-            
+
             // data - empty string for now
             builder = builder.put_string(note_columns::DATA, "")?;
             log::info!("✅ Added data to ContentValues");
-            
+
             builder
         }
         Err(e) => {
@@ -186,7 +187,11 @@ pub fn create_card(
             id
         }
         Err(e) => {
-            log::error!("❌ Failed to extract note ID from URI '{}': {}", result_uri, e);
+            log::error!(
+                "❌ Failed to extract note ID from URI '{}': {}",
+                result_uri,
+                e
+            );
             return Err(e);
         }
     };
@@ -198,7 +203,10 @@ pub fn create_card(
         env.env().exception_clear().ok();
     }
 
-    log::info!("🎉 CREATE_CARD COMPLETED SUCCESSFULLY - Note ID: {}", note_id);
+    log::info!(
+        "🎉 CREATE_CARD COMPLETED SUCCESSFULLY - Note ID: {}",
+        note_id
+    );
     Ok(note_id)
 }
 
@@ -212,7 +220,7 @@ pub fn list_cards(
 
     // First, try a simple query to test basic connectivity
     log::info!("Testing basic AnkiDroid connectivity...");
-    
+
     let projection = vec![
         note_columns::ID.to_string(),
         note_columns::FLDS.to_string(),
@@ -282,7 +290,7 @@ pub fn list_cards(
             id: note_id,
             front,
             back,
-            deck_id: 1, // Default deck ID, since notes don't have deck ID directly
+            deck_id: 1,  // Default deck ID, since notes don't have deck ID directly
             model_id: 1, // Default model ID
             tags,
         })
@@ -300,7 +308,11 @@ pub fn list_cards(
     // Apply limit after fetching results
     let limited_cards = if let Some(limit_val) = limit {
         let limit_size = limit_val as usize;
-        log::info!("Applying limit of {} to {} cards", limit_size, all_cards.len());
+        log::info!(
+            "Applying limit of {} to {} cards",
+            limit_size,
+            all_cards.len()
+        );
         all_cards.into_iter().take(limit_size).collect()
     } else {
         all_cards
@@ -351,7 +363,12 @@ pub fn update_card(
     // We would need to update the cards table separately if we want to change deck
     if let Some(deck_name) = deck_name {
         let mut env_for_deck = env.clone();
-        let _deck_id = get_or_create_deck_id(&mut env_for_deck, activity, Some(deck_name))?;
+        let _deck_id = get_or_create_deck_id(
+            &mut env_for_deck,
+            activity,
+            Some(deck_name),
+            DeckMatch::Exact,
+        )?;
         // TODO: Update the deck ID in the cards table, not the notes table
     }
 
@@ -4,13 +4,15 @@ use crate::android::content_provider::{insert, query};
 use crate::android::cursor::collect_cursor_results;
 use crate::android::error::{AndroidError, AndroidResult};
 use crate::android::jni_helpers::{ContentValuesBuilder, SafeJNIEnv};
+use crate::decks::{plan_deck_path, resolve_deck, DeckMatch, DeckPath, EnsuredDeck};
 use jni::objects::JObject;
 
-/// Find a deck ID by name
+/// Find a deck ID by name using the given match mode
 pub fn find_deck_id_by_name(
     env: SafeJNIEnv,
     activity: &JObject,
     deck_name: &str,
+    mode: DeckMatch,
 ) -> AndroidResult<Option<i64>> {
     log::info!("Searching for deck: {}", deck_name);
 
@@ -41,29 +43,14 @@ pub fn find_deck_id_by_name(
         Ok((deck_id, name))
     })?;
 
-    // Look for exact name match first
-    for (id, name) in &decks {
-        if name == deck_name {
-            log::info!("Found exact deck match: {} (ID: {})", name, id);
-            return Ok(Some(*id));
-        }
-    }
-
-    // Look for case-insensitive match
-    let deck_name_lower = deck_name.to_lowercase();
-    for (id, name) in &decks {
-        if name.to_lowercase() == deck_name_lower {
-            log::info!("Found case-insensitive deck match: {} (ID: {})", name, id);
-            return Ok(Some(*id));
-        }
-    }
-
-    // Look for partial match
-    for (id, name) in &decks {
-        if name.to_lowercase().contains(&deck_name_lower) {
-            log::info!("Found partial deck match: {} (ID: {})", name, id);
-            return Ok(Some(*id));
-        }
+    if let Some(id) = resolve_deck(&decks, deck_name, mode)? {
+        log::info!(
+            "Found {:?} deck match for '{}' (ID: {})",
+            mode,
+            deck_name,
+            id
+        );
+        return Ok(Some(id));
     }
 
     log::info!("Deck '{}' not found", deck_name);
@@ -71,106 +58,89 @@ pub fn find_deck_id_by_name(
 }
 
 /// Create a deck if it doesn't exist, otherwise return existing deck ID
+///
+/// Missing parent decks are created as well, see [`ensure_deck_path`].
 pub fn create_deck_if_not_exists(
-    mut env: SafeJNIEnv,
+    env: SafeJNIEnv,
     activity: &JObject,
     deck_name: &str,
+    mode: DeckMatch,
 ) -> AndroidResult<i64> {
-    log::info!("🏗️ CREATE_DECK_IF_NOT_EXISTS: '{}'", deck_name);
-
-    // First check if deck already exists
-    log::info!("🔍 Step 1: Checking if deck already exists...");
-    let env_clone = env.clone();
-    match find_deck_id_by_name(env_clone, activity, deck_name) {
-        Ok(Some(existing_id)) => {
-            log::info!("✅ Deck '{}' already exists with ID: {}", deck_name, existing_id);
-            return Ok(existing_id);
-        }
-        Ok(None) => {
-            log::info!("🔍 Deck '{}' not found in initial search", deck_name);
-        }
-        Err(e) => {
-            log::error!("❌ Error during initial deck search: {}", e);
-            // Continue with creation attempt
-        }
-    }
+    ensure_deck_path(env, activity, deck_name, mode).map(|deck| deck.deck_id)
+}
 
-    // Double check by listing all decks to make sure we didn't miss it
-    log::info!("🔍 Step 2: Double-checking by listing all decks...");
-    let env_for_list = env.clone();
-    match list_decks(env_for_list, activity) {
-        Ok(decks) => {
-            log::info!("✅ Found {} total decks", decks.len());
-            for (id, name) in &decks {
-                log::info!("  - Deck: '{}' (ID: {})", name, id);
-                if name.eq_ignore_ascii_case(deck_name) {
-                    log::info!("✅ Found existing deck '{}' with ID {} (case insensitive match)", name, id);
-                    return Ok(*id);
-                }
-            }
-            log::info!("🔍 No case-insensitive match found for '{}'", deck_name);
-        }
-        Err(e) => {
-            log::error!("❌ Failed to list decks during double-check: {}", e);
+/// Make sure every deck along a `::`-separated path exists
+///
+/// The name is normalized first ("Lang :: Japanese:: N5" becomes
+/// "Lang::Japanese::N5"). Each segment is checked for an existing deck and the
+/// missing ones are created parents first; their IDs are reported in the result.
+pub fn ensure_deck_path(
+    mut env: SafeJNIEnv,
+    activity: &JObject,
+    deck_name: &str,
+    mode: DeckMatch,
+) -> AndroidResult<EnsuredDeck> {
+    let path = DeckPath::parse(deck_name).map_err(AndroidError::validation_error)?;
+    log::info!("🏗️ ENSURE_DECK_PATH: '{}' ({:?})", path, mode);
+
+    let decks = list_decks(env.clone(), activity)?;
+    let plan = plan_deck_path(&decks, &path, mode)?;
+
+    if plan.missing.is_empty() {
+        if let Some((deck_id, name)) = plan.existing {
+            log::info!("✅ Deck '{}' already exists with ID: {}", name, deck_id);
+            return Ok(EnsuredDeck {
+                deck_id,
+                name,
+                created: Vec::new(),
+            });
         }
     }
 
-    // Try to create the deck only if we're absolutely sure it doesn't exist
-    log::info!("🏗️ Step 3: Deck '{}' does not exist, attempting to create it", deck_name);
-    let env_for_create = env.clone();
-    
-    // Check for JNI exceptions before creation attempt
-    if env.env().exception_check().unwrap_or(false) {
-        log::error!("🔥 JNI EXCEPTION PRESENT before deck creation attempt!");
-        env.env().exception_describe().ok();
-        env.env().exception_clear().ok();
-    }
-    
-    match create_deck(env_for_create, activity, deck_name) {
-        Ok(deck_id) => {
-            log::info!("✅ Created new deck '{}' with ID: {}", deck_name, deck_id);
-            Ok(deck_id)
+    let mut deck_id = DEFAULT_DECK_ID;
+    let mut created = Vec::new();
+
+    for name in &plan.missing {
+        // Check for JNI exceptions before each creation attempt
+        if env.env().exception_check().unwrap_or(false) {
+            log::error!("🔥 JNI EXCEPTION PRESENT before deck creation attempt!");
+            env.env().exception_describe().ok();
+            env.env().exception_clear().ok();
         }
-        Err(e) => {
-            log::error!("❌ CRITICAL: Failed to create deck '{}': {}", deck_name, e);
-            log::error!("❌ Error details: {:?}", e);
-
-            // Check for JNI exceptions after creation failure
-            if env.env().exception_check().unwrap_or(false) {
-                log::error!("🔥 JNI EXCEPTION DETECTED after deck creation failure!");
-                if let Ok(exception) = env.env().exception_occurred() {
-                    log::error!("🔥 Exception object found: {}", exception.as_raw() as usize);
-                    env.env().exception_describe().ok();
-                    env.env().exception_clear().ok();
-                }
-            }
 
-            // Check if this is a "deck already exists" error
-            let error_message = e.to_string();
-            if error_message.contains("already exists") || error_message.contains("Deck name already exists") {
-                log::info!("🔄 Deck '{}' seems to already exist (race condition?), searching again...", deck_name);
-                // Check one more time in case another process created it
-                let env_for_find = env.clone();
-                match find_deck_id_by_name(env_for_find, activity, deck_name) {
-                    Ok(Some(existing_id)) => {
-                        log::info!("✅ Found existing deck '{}' with ID: {} after creation failure", deck_name, existing_id);
-                        return Ok(existing_id);
-                    }
-                    Ok(None) => {
-                        log::error!("❌ Still could not find deck '{}' after creation failure", deck_name);
-                    }
-                    Err(find_error) => {
-                        log::error!("❌ Error searching for deck after creation failure: {}", find_error);
-                    }
-                }
+        match create_deck(env.clone(), activity, name) {
+            Ok(id) => {
+                log::info!("✅ Created deck '{}' with ID: {}", name, id);
+                deck_id = id;
+                created.push(id);
+            }
+            Err(e) if e.to_string().contains("already exists") => {
+                // Another process created it since the deck list was read, possibly
+                // spelled differently since AnkiDroid compares names ignoring case
+                log::info!(
+                    "🔄 Deck '{}' already exists (race condition?), searching again...",
+                    name
+                );
+                deck_id =
+                    find_deck_id_by_name(env.clone(), activity, name, DeckMatch::CaseInsensitive)?
+                        .ok_or(e)?;
+            }
+            Err(e) => {
+                log::error!("❌ Failed to create deck '{}': {}", name, e);
+                return Err(e);
             }
-
-            // For automated testing, let's NOT fall back to default deck immediately
-            // Instead, propagate the error so we can see what's really happening
-            log::error!("🔥 PROPAGATING ERROR instead of using default deck to debug the issue");
-            return Err(e);
         }
     }
+
+    Ok(EnsuredDeck {
+        deck_id,
+        name: plan
+            .missing
+            .last()
+            .cloned()
+            .unwrap_or_else(|| path.to_string()),
+        created,
+    })
 }
 
 /// Create a new deck
@@ -184,7 +154,7 @@ pub fn create_deck(mut env: SafeJNIEnv, activity: &JObject, deck_name: &str) ->
     // Skip AddContentApi for now since it's causing crashes
     // The AddContentApi class is in AnkiDroid app, not ours, so we can't instantiate it
     // We'll use ContentProvider directly instead
-    
+
     // Use ContentProvider method
     log::info!("Using ContentProvider to create deck...");
     let mut env_for_values = env.clone();
@@ -203,13 +173,15 @@ pub fn list_decks(env: SafeJNIEnv, activity: &JObject) -> AndroidResult<Vec<(i64
     log::info!("Listing all available decks");
 
     // Try querying without any projection first to see what columns are available
-    let cursor = query(env, DECKS_URI)
-        .execute(activity)?;
+    let cursor = query(env, DECKS_URI).execute(activity)?;
 
     collect_cursor_results(cursor, |cursor| {
         // Try to get column count and names for debugging
-        log::info!("Cursor column count: {}", cursor.get_column_count().unwrap_or(0));
-        
+        log::info!(
+            "Cursor column count: {}",
+            cursor.get_column_count().unwrap_or(0)
+        );
+
         // Try different possible column name combinations
         let deck_id = cursor
             .get_long_by_name("_id")  // Most common Android ID column
@@ -293,6 +265,8 @@ pub fn validate_deck_name(deck_name: &str) -> AndroidResult<()> {
         return Err(AndroidError::validation_error("Deck name cannot be empty"));
     }
 
+    DeckPath::parse(deck_name).map_err(AndroidError::validation_error)?;
+
     if deck_name.len() > 100 {
         return Err(AndroidError::validation_error(
             "Deck name too long (max 100 characters)",
@@ -332,12 +306,13 @@ pub fn get_or_create_deck_id(
     env: &mut SafeJNIEnv,
     activity: &JObject,
     deck_name: Option<&str>,
+    mode: DeckMatch,
 ) -> AndroidResult<i64> {
     match deck_name {
         Some(name) => {
             validate_deck_name(name)?;
             let env_clone = env.clone();
-            create_deck_if_not_exists(env_clone, activity, name)
+            create_deck_if_not_exists(env_clone, activity, name, mode)
         }
         None => {
             log::info!(
@@ -367,6 +342,8 @@ mod tests {
         assert!(validate_deck_name("Name\0WithNull").is_err());
         assert!(validate_deck_name("Name\nWithNewline").is_err());
         assert!(validate_deck_name(&"x".repeat(101)).is_err());
+        assert!(validate_deck_name("Lang::::N5").is_err());
+        assert!(validate_deck_name("Lang:: ").is_err());
     }
 
     #[test]
@@ -396,6 +373,18 @@ mod tests {
         assert!("Test Deck".to_lowercase().contains(&deck_name_lower));
     }
 
+    #[test]
+    fn test_ambiguous_match_is_an_error() {
+        let decks = vec![
+            (2, "Spanish".to_string()),
+            (3, "Spanish::Archive".to_string()),
+        ];
+        let err = AndroidError::from(resolve_deck(&decks, "span", DeckMatch::Fuzzy).unwrap_err());
+        assert!(
+            matches!(err, AndroidError::Ambiguous { ref candidates, .. } if candidates.len() == 2)
+        );
+    }
+
     #[test]
     fn test_get_or_create_deck_id_logic() {
         // Test with None
@@ -1,5 +1,9 @@
-use thiserror::Error;
+use crate::decks::AmbiguousDeck;
+use crate::download::DownloadError;
+use crate::error::{ErrorCode, ErrorDetails, PluginError};
+use ankidroid_api_rust::jni::helpers::take_java_exception;
 use ankidroid_api_rust::AnkiDroidError;
+use thiserror::Error;
 
 /// Custom error types for AnkiDroid plugin operations
 #[derive(Error, Debug)]
@@ -10,6 +14,11 @@ pub enum AndroidError {
     #[error("Java exception occurred: {0}")]
     JavaException(String),
 
+    /// A Java exception mapped to a permission, validation, database, I/O or
+    /// busy error, keeping its class name and stack trace
+    #[error("{0}")]
+    MappedException(AnkiDroidError),
+
     #[error("ContentProvider error: {0}")]
     ContentProviderError(String),
 
@@ -31,6 +40,12 @@ pub enum AndroidError {
     #[error("Deck not found: {0}")]
     DeckNotFound(String),
 
+    #[error("Ambiguous deck name '{query}', candidates: {}", candidates.join(", "))]
+    Ambiguous {
+        query: String,
+        candidates: Vec<String>,
+    },
+
     #[error("Note not found: {0}")]
     NoteNotFound(String),
 
@@ -68,22 +83,139 @@ impl From<AndroidError> for String {
     }
 }
 
+impl From<AmbiguousDeck> for AndroidError {
+    fn from(err: AmbiguousDeck) -> Self {
+        AndroidError::Ambiguous {
+            query: err.query,
+            candidates: err.candidates,
+        }
+    }
+}
+
+impl From<DownloadError> for AndroidError {
+    fn from(err: DownloadError) -> Self {
+        AndroidError::MediaError(err.to_string())
+    }
+}
+
 impl From<AnkiDroidError> for AndroidError {
     fn from(err: AnkiDroidError) -> Self {
         match err {
             AnkiDroidError::AnkiDroidNotAvailable(msg) => AndroidError::AnkiDroidNotAvailable(msg),
-            AnkiDroidError::PermissionDenied(msg) => AndroidError::PermissionDenied(msg),
-            AnkiDroidError::InvalidModelId(id) => AndroidError::ValidationError(format!("Invalid model ID: {}", id)),
-            AnkiDroidError::InvalidDeckId(id) => AndroidError::ValidationError(format!("Invalid deck ID: {}", id)),
-            AnkiDroidError::DuplicateNote(msg) => AndroidError::ValidationError(format!("Duplicate note: {}", msg)),
-            AnkiDroidError::FieldCountMismatch { expected, actual } => AndroidError::ValidationError(format!("Field count mismatch: expected {}, got {}", expected, actual)),
-            AnkiDroidError::JniError(msg) => AndroidError::ContentProviderError(format!("JNI error: {}", msg)),
-            AnkiDroidError::NullPointer(msg) => AndroidError::ContentProviderError(format!("Null pointer: {}", msg)),
+            AnkiDroidError::PermissionDenied { message, .. } => {
+                AndroidError::PermissionDenied(message)
+            }
+            AnkiDroidError::InvalidModelId(id) => {
+                AndroidError::ValidationError(format!("Invalid model ID: {}", id))
+            }
+            AnkiDroidError::InvalidDeckId(id) => {
+                AndroidError::ValidationError(format!("Invalid deck ID: {}", id))
+            }
+            AnkiDroidError::DuplicateNote(msg) => {
+                AndroidError::ValidationError(format!("Duplicate note: {}", msg))
+            }
+            AnkiDroidError::FieldCountMismatch { expected, actual } => {
+                AndroidError::ValidationError(format!(
+                    "Field count mismatch: expected {}, got {}",
+                    expected, actual
+                ))
+            }
+            AnkiDroidError::JniError(msg) => {
+                AndroidError::ContentProviderError(format!("JNI error: {}", msg))
+            }
+            AnkiDroidError::NullPointer(msg) => {
+                AndroidError::ContentProviderError(format!("Null pointer: {}", msg))
+            }
             AnkiDroidError::StringConversionError(msg) => AndroidError::StringConversionError(msg),
-            AnkiDroidError::DatabaseError(msg) => AndroidError::DatabaseError(msg),
-            AnkiDroidError::ValidationError(msg) => AndroidError::ValidationError(msg),
-            AnkiDroidError::IoError(msg) => AndroidError::ContentProviderError(format!("I/O error: {}", msg)),
-            AnkiDroidError::JsonError(msg) => AndroidError::ContentProviderError(format!("JSON error: {}", msg)),
+            AnkiDroidError::DatabaseError { message, .. } => AndroidError::DatabaseError(message),
+            AnkiDroidError::ValidationError { message, .. } => {
+                AndroidError::ValidationError(message)
+            }
+            AnkiDroidError::IoError { message, .. } => {
+                AndroidError::ContentProviderError(format!("I/O error: {}", message))
+            }
+            AnkiDroidError::JsonError(msg) => {
+                AndroidError::ContentProviderError(format!("JSON error: {}", msg))
+            }
+            // Kept whole so it stays recoverable
+            busy @ AnkiDroidError::Busy { .. } => AndroidError::MappedException(busy),
+        }
+    }
+}
+
+impl From<AnkiDroidError> for PluginError {
+    fn from(err: AnkiDroidError) -> Self {
+        let code = match &err {
+            AnkiDroidError::AnkiDroidNotAvailable(_) => ErrorCode::Availability,
+            other => ErrorCode::from_category(other.category()),
+        };
+        let details = match &err {
+            AnkiDroidError::FieldCountMismatch { expected, actual } => {
+                Some(ErrorDetails::FieldCount {
+                    expected: *expected,
+                    actual: *actual,
+                })
+            }
+            AnkiDroidError::InvalidModelId(id) => Some(ErrorDetails::InvalidId {
+                resource: "model".to_string(),
+                id: *id,
+            }),
+            AnkiDroidError::InvalidDeckId(id) => Some(ErrorDetails::InvalidId {
+                resource: "deck".to_string(),
+                id: *id,
+            }),
+            other => other
+                .java_exception()
+                .map(|exception| ErrorDetails::JavaException {
+                    class_name: exception.class_name.clone(),
+                    stack_trace: exception.stack_trace.clone(),
+                }),
+        };
+
+        PluginError {
+            code,
+            message: err.to_string(),
+            recoverable: err.is_recoverable(),
+            details,
+            attempts: Vec::new(),
+        }
+    }
+}
+
+impl From<AndroidError> for PluginError {
+    fn from(err: AndroidError) -> Self {
+        let code = match &err {
+            AndroidError::JniError(_)
+            | AndroidError::ResourceCleanupError(_)
+            | AndroidError::LocalReferenceLimitExceeded => ErrorCode::Jni,
+            AndroidError::JavaException(_) => ErrorCode::JavaException,
+            AndroidError::ContentProviderError(_) | AndroidError::CardOperationError(_) => {
+                ErrorCode::ContentProvider
+            }
+            AndroidError::ValidationError(_) | AndroidError::InvalidFieldFormat(_) => {
+                ErrorCode::Validation
+            }
+            AndroidError::AnkiDroidNotInstalled => ErrorCode::NotInstalled,
+            AndroidError::AnkiDroidNotAvailable(_) => ErrorCode::Availability,
+            AndroidError::PermissionDenied(_) => ErrorCode::Permission,
+            AndroidError::DatabaseError(_) | AndroidError::CursorError(_) => ErrorCode::Database,
+            AndroidError::ModelNotFound(_)
+            | AndroidError::DeckNotFound(_)
+            | AndroidError::NoteNotFound(_) => ErrorCode::NotFound,
+            AndroidError::Ambiguous { .. } => ErrorCode::Ambiguous,
+            AndroidError::StringConversionError(_) => ErrorCode::Conversion,
+            AndroidError::MediaError(_) => ErrorCode::Media,
+            AndroidError::SyncError(_) => ErrorCode::Sync,
+            AndroidError::MappedException(err) => ErrorCode::from_category(err.category()),
+        };
+
+        let error = PluginError::new(code, err.to_string());
+        match err {
+            AndroidError::MappedException(err) => PluginError::from(err),
+            AndroidError::Ambiguous { query, candidates } => {
+                error.with_details(ErrorDetails::Ambiguous { query, candidates })
+            }
+            _ => error,
         }
     }
 }
@@ -91,7 +223,11 @@ impl From<AnkiDroidError> for AndroidError {
 impl AndroidError {
     /// Check if the error is due to permission denial
     pub fn is_permission_error(&self) -> bool {
-        matches!(self, AndroidError::PermissionDenied(_))
+        match self {
+            AndroidError::PermissionDenied(_) => true,
+            AndroidError::MappedException(err) => err.is_permission_denied(),
+            _ => false,
+        }
     }
 
     /// Check if the error is due to AnkiDroid not being installed
@@ -152,43 +288,30 @@ pub trait JniResultExt<T> {
 impl<T> JniResultExt<T> for jni::errors::Result<T> {
     fn check_exception(self, env: &mut jni::JNIEnv) -> AndroidResult<T> {
         match self {
-            Ok(value) => {
-                // Check if a Java exception occurred
-                if env.exception_check().unwrap_or(false) {
-                    let exception_msg = get_exception_message(env)
-                        .unwrap_or_else(|| "Unknown Java exception".to_string());
-                    env.exception_clear().ok();
-                    Err(AndroidError::java_exception(exception_msg))
-                } else {
-                    Ok(value)
-                }
-            }
+            Ok(value) => match take_exception(env) {
+                Some(err) => Err(err),
+                None => Ok(value),
+            },
+            // The call threw, so the exception is still pending
+            Err(jni::errors::Error::JavaException) => Err(take_exception(env)
+                .unwrap_or_else(|| AndroidError::java_exception("Unknown Java exception"))),
             Err(jni_error) => Err(AndroidError::from(jni_error)),
         }
     }
 }
 
-/// Helper function to extract exception message from JNI environment
-fn get_exception_message(env: &mut jni::JNIEnv) -> Option<String> {
-    env.exception_occurred().ok().and_then(|exception| {
-        env.call_method(&exception, "getMessage", "()Ljava/lang/String;", &[])
-            .ok()
-            .and_then(|msg| msg.l().ok())
-            .and_then(|msg_obj| {
-                if !msg_obj.is_null() {
-                    env.get_string(&msg_obj.into())
-                        .ok()
-                        .map(|s| s.to_str().unwrap_or("").to_string())
-                } else {
-                    None
-                }
-            })
+/// Take the pending Java exception, mapping its class as the core crate does
+fn take_exception(env: &mut jni::JNIEnv) -> Option<AndroidError> {
+    take_java_exception(env).map(|err| match err {
+        AnkiDroidError::JniError(msg) => AndroidError::JavaException(msg),
+        mapped => AndroidError::MappedException(mapped),
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ankidroid_api_rust::JavaException;
 
     #[test]
     fn test_error_creation() {
@@ -216,12 +339,100 @@ mod tests {
         assert!(err.to_string().contains("Deck not found: Test Deck"));
     }
 
+    #[test]
+    fn test_ambiguous_deck_error() {
+        let err = AndroidError::from(AmbiguousDeck {
+            query: "span".to_string(),
+            candidates: vec!["Spanish".to_string(), "Spanish::Archive".to_string()],
+        });
+        assert!(err
+            .to_string()
+            .contains("candidates: Spanish, Spanish::Archive"));
+    }
+
     #[test]
     fn test_validation_error() {
         let err = AndroidError::validation_error("Invalid input");
         assert!(err.to_string().contains("Validation error: Invalid input"));
     }
 
+    #[test]
+    fn test_plugin_error_codes() {
+        let err = PluginError::from(AndroidError::AnkiDroidNotInstalled);
+        assert_eq!(err.code, ErrorCode::NotInstalled);
+        assert!(!err.recoverable);
+
+        let err = PluginError::from(AndroidError::permission_denied("READ_WRITE_DATABASE"));
+        assert_eq!(err.code, ErrorCode::Permission);
+
+        let err = PluginError::from(AndroidError::Ambiguous {
+            query: "span".to_string(),
+            candidates: vec!["Spanish".to_string()],
+        });
+        assert_eq!(err.code, ErrorCode::Ambiguous);
+        assert!(err.details.is_some());
+    }
+
+    #[test]
+    fn test_plugin_error_from_api_error() {
+        let err = PluginError::from(AnkiDroidError::field_count_mismatch(3, 2));
+        assert_eq!(err.code, ErrorCode::Validation);
+        assert_eq!(err.message, "Field count mismatch: expected 3, got 2");
+        assert_eq!(
+            err.details,
+            Some(ErrorDetails::FieldCount {
+                expected: 3,
+                actual: 2
+            })
+        );
+
+        let err = PluginError::from(AnkiDroidError::database_error("UNIQUE constraint failed"));
+        assert_eq!(err.code, ErrorCode::Database);
+        assert!(!err.recoverable);
+
+        let err = PluginError::from(AndroidError::from(AnkiDroidError::busy(
+            "database is locked",
+        )));
+        assert_eq!(err.code, ErrorCode::Busy);
+        assert!(err.recoverable);
+
+        let err = PluginError::from(AnkiDroidError::not_available("no provider"));
+        assert_eq!(err.code, ErrorCode::Availability);
+
+        let err = PluginError::from(AnkiDroidError::invalid_deck_id(7));
+        assert_eq!(
+            err.details,
+            Some(ErrorDetails::InvalidId {
+                resource: "deck".to_string(),
+                id: 7
+            })
+        );
+    }
+
+    #[test]
+    fn test_plugin_error_from_java_exception() {
+        let mapped = AnkiDroidError::from_java_exception(
+            JavaException {
+                class_name: "java.lang.SecurityException".to_string(),
+                message: Some("Permission Denial".to_string()),
+                stack_trace: vec!["android.os.Parcel.createException(Parcel.java:2071)".to_string()],
+            },
+            &[],
+        );
+        let err = AndroidError::MappedException(mapped);
+        assert!(err.is_permission_error());
+
+        let err = PluginError::from(err);
+        assert_eq!(err.code, ErrorCode::Permission);
+        assert_eq!(
+            err.details,
+            Some(ErrorDetails::JavaException {
+                class_name: "java.lang.SecurityException".to_string(),
+                stack_trace: vec!["android.os.Parcel.createException(Parcel.java:2071)".to_string()],
+            })
+        );
+    }
+
     #[test]
     fn test_error_types() {
         let permission_err = AndroidError::permission_denied("test");
@@ -1,5 +1,5 @@
 use crate::android::error::{AndroidError, AndroidResult, JniResultExt};
-use jni::objects::{JObject, JString, JValue};
+use jni::objects::{JClass, JObject, JString, JValue};
 use jni::{JNIEnv, JavaVM};
 use ndk_context;
 use std::ops::Deref;
@@ -318,16 +318,16 @@ impl AttachGuard {
     pub fn new() -> AndroidResult<(Self, JNIEnv<'static>)> {
         let (vm, _) = get_android_context()?;
         let attach_guard = vm.attach_current_thread().map_err(AndroidError::from)?;
-        
+
         // Extract the JNIEnv from the AttachGuard using unsafe pointer manipulation
         // This is safe because we ensure the thread stays attached via our own guard
         let env_ptr = attach_guard.deref() as *const JNIEnv<'_>;
         let static_env = unsafe { std::ptr::read(env_ptr) };
         let static_env = unsafe { std::mem::transmute::<JNIEnv<'_>, JNIEnv<'static>>(static_env) };
-        
+
         // Forget the original attach guard so it doesn't detach
         std::mem::forget(attach_guard);
-        
+
         Ok((Self { vm }, static_env))
     }
 }
@@ -406,6 +406,38 @@ pub fn parse_uri<'local>(
     result.l().map_err(AndroidError::from)
 }
 
+/// Load a class through the app's class loader
+///
+/// `FindClass` on threads attached from native code only sees system classes, so
+/// classes from the app or its libraries (e.g. androidx) must be loaded this way.
+pub fn load_app_class<'local>(
+    env: &mut SafeJNIEnv<'local>,
+    context: &JObject,
+    class_name: &str,
+) -> AndroidResult<JClass<'local>> {
+    let loader = env
+        .env_mut()
+        .call_method(context, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
+        .check_exception(env.env_mut())?
+        .l()
+        .map_err(AndroidError::from)?;
+
+    let name = env.new_string_checked(class_name)?;
+    let class = env
+        .env_mut()
+        .call_method(
+            &loader,
+            "loadClass",
+            "(Ljava/lang/String;)Ljava/lang/Class;",
+            &[JValue::Object(&name)],
+        )
+        .check_exception(env.env_mut())?
+        .l()
+        .map_err(AndroidError::from)?;
+
+    Ok(JClass::from(class))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
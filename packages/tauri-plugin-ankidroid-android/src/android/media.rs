@@ -3,14 +3,67 @@ use crate::android::constants::{
 };
 use crate::android::error::{AndroidError, AndroidResult, JniResultExt};
 use crate::android::jni_helpers::{
-    attach_current_thread, get_android_context, get_content_resolver, parse_uri, SafeJNIEnv,
-    StringHelper,
+    attach_current_thread, get_android_context, get_content_resolver, load_app_class, parse_uri,
+    SafeJNIEnv, StringHelper,
 };
+use crate::download::{download, Download, DownloadError, DownloadLimits};
+use crate::media::{media_key, MediaIndex};
+use crate::preprocess::{
+    self, is_preprocessed, preprocess_image, with_extension, ImagePreprocessing,
+};
+use crate::staging::{sweep, StagedFile, SweepLimits, SweepReport};
 use jni::objects::{JObject, JValue};
 use log::{debug, info, warn};
 use std::fs::{create_dir_all, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// Subdirectory of the app cache media is staged in
+const MEDIA_CACHE_DIR: &str = "media";
+
+/// File in the app's files directory mapping media keys to AnkiDroid filenames
+const MEDIA_INDEX_FILE: &str = "ankidroid/media_index.json";
+
+/// FileProvider class used to share staged media files with AnkiDroid
+const FILE_PROVIDER_CLASS: &str = "androidx.core.content.FileProvider";
+
+/// Suffix appended to the app's package name to form the FileProvider authority
+///
+/// Must match the `<provider>` declared in the plugin's AndroidManifest.xml.
+const FILE_PROVIDER_AUTHORITY_SUFFIX: &str = ".ankidroid.fileprovider";
+
+/// Get the FileProvider authority for the app with the given package name
+///
+/// Authorities must be unique on a device, so each app gets its own.
+pub fn file_provider_authority(package_name: &str) -> String {
+    format!("{}{}", package_name, FILE_PROVIDER_AUTHORITY_SUFFIX)
+}
+
+/// Index of media already added to AnkiDroid, see [`media_index`]
+static MEDIA_INDEX: OnceLock<Mutex<MediaIndex>> = OnceLock::new();
+
+/// Lock the index of media already added to AnkiDroid
+///
+/// Opened on first use from the app's files directory, or kept in memory if
+/// that cannot be found. Finding the directory takes JNI calls, so the first
+/// use must be on the JNI worker; see [`MediaHandler::open_media_index`].
+fn media_index() -> MutexGuard<'static, MediaIndex> {
+    MEDIA_INDEX
+        .get_or_init(|| {
+            let index = match MediaHandler::get_files_dir() {
+                Ok(dir) => MediaIndex::open(dir.join(MEDIA_INDEX_FILE)),
+                Err(e) => {
+                    warn!("Media index will not be saved: {}", e);
+                    MediaIndex::in_memory()
+                }
+            };
+            Mutex::new(index)
+        })
+        .lock()
+        // Entries are independent, so a poisoned lock is still usable
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
 
 /// Media operations handler for AnkiDroid integration
 pub struct MediaHandler;
@@ -18,6 +71,9 @@ pub struct MediaHandler;
 impl MediaHandler {
     /// Add media from raw bytes data
     ///
+    /// Same as [`prepare_media`](Self::prepare_media) followed by
+    /// [`insert_media`](Self::insert_media) on the calling thread.
+    ///
     /// # Arguments
     /// * `data` - Raw bytes of the media file
     /// * `filename` - Preferred filename for the media
@@ -30,6 +86,33 @@ impl MediaHandler {
         filename: &str,
         mime_type: &str,
     ) -> AndroidResult<MediaAddResult> {
+        let prepared = Self::prepare_media(data, filename, mime_type)?;
+        Self::insert_media(prepared)
+    }
+
+    /// Check media against the index and preprocess it if it is new
+    ///
+    /// The index is keyed by the hash of the bytes as given together with the
+    /// preprocessing settings, so a duplicate is found before an image is
+    /// decoded and re-encoded, and new settings are applied to images added
+    /// before they changed.
+    ///
+    /// Preprocessing is slow for large photos, so call this off the JNI worker.
+    /// It makes no JNI calls once the index is open, so open it on the worker
+    /// first with [`open_media_index`](Self::open_media_index).
+    ///
+    /// # Arguments
+    /// * `data` - Raw bytes of the media file
+    /// * `filename` - Preferred filename for the media
+    /// * `mime_type` - MIME type of the media (e.g., "image/png", "audio/mpeg")
+    ///
+    /// # Returns
+    /// * `AndroidResult<PreparedMedia>` - The known filename, or the media to insert
+    pub fn prepare_media(
+        data: Vec<u8>,
+        filename: &str,
+        mime_type: &str,
+    ) -> AndroidResult<PreparedMedia> {
         info!(
             "Adding media from bytes: filename={}, size={} bytes, mime_type={}",
             filename,
@@ -47,12 +130,58 @@ impl MediaHandler {
             ));
         }
 
+        // Read the settings once so the key matches the processing applied below
+        let options = (*preprocess::settings()).filter(|_| is_preprocessed(mime_type));
+
+        // Identical content is already in the collection, so reference it again
+        let hash = media_key(&data, options.as_ref());
+        if let Some(existing) = media_index().get(&hash) {
+            info!(
+                "Media already in AnkiDroid as {}, skipping insert",
+                existing
+            );
+            return Ok(PreparedMedia::Known(existing.to_string()));
+        }
+
+        let (data, filename) = Self::preprocess(data, filename, options);
+        Ok(PreparedMedia::New {
+            data,
+            filename,
+            hash,
+        })
+    }
+
+    /// Insert prepared media into AnkiDroid
+    ///
+    /// # Arguments
+    /// * `prepared` - Media returned by [`prepare_media`](Self::prepare_media)
+    ///
+    /// # Returns
+    /// * `AndroidResult<MediaAddResult>` - Result containing the URI and actual filename used
+    pub fn insert_media(prepared: PreparedMedia) -> AndroidResult<MediaAddResult> {
+        let (data, filename, hash) = match prepared {
+            PreparedMedia::Known(filename) => {
+                return Ok(MediaAddResult {
+                    uri: String::new(),
+                    filename,
+                    reused: true,
+                })
+            }
+            PreparedMedia::New {
+                data,
+                filename,
+                hash,
+            } => (data, filename, hash),
+        };
+
         // Save to cache directory first
-        let cache_file_path = Self::save_to_cache(data, filename)?;
-        debug!("Media saved to cache: {}", cache_file_path.display());
+        // AnkiDroid copies the file during the insert, so the staged copy is
+        // deleted when this function returns, whether or not the insert worked
+        let staged = StagedFile::new(Self::save_to_cache(data, &filename)?);
+        debug!("Media saved to cache: {}", staged.path().display());
 
         // Get FileProvider URI and add to AnkiDroid
-        let file_uri = Self::get_file_provider_uri(&cache_file_path)?;
+        let file_uri = Self::get_file_provider_uri(staged.path())?;
         debug!("FileProvider URI created: {}", file_uri);
 
         // Grant permission to AnkiDroid
@@ -60,15 +189,20 @@ impl MediaHandler {
         debug!("URI permission granted to AnkiDroid");
 
         // Add to AnkiDroid using ContentProvider
-        let actual_filename = Self::insert_media_content(&file_uri, filename)?;
+        let actual_filename = Self::insert_media_content(&file_uri, &filename)?;
         debug!(
             "Media added to AnkiDroid with filename: {}",
             actual_filename
         );
 
+        if let Err(e) = media_index().insert(&hash, &actual_filename) {
+            warn!("Failed to save media index: {}", e);
+        }
+
         Ok(MediaAddResult {
             uri: file_uri,
             filename: actual_filename,
+            reused: false,
         })
     }
 
@@ -88,16 +222,46 @@ impl MediaHandler {
         }
 
         // Download the media data
-        let data = Self::download_from_url(url)?;
-        debug!("Downloaded {} bytes from URL", data.len());
+        let download = Self::download_from_url(url)?;
+        debug!("Downloaded {} bytes from URL", download.bytes.len());
 
-        // Determine MIME type from file extension or URL
-        let mime_type = Self::determine_mime_type_from_filename(filename)
-            .or_else(|| Self::determine_mime_type_from_url(url))
-            .unwrap_or("application/octet-stream");
+        let (filename, mime_type) = Self::name_downloaded_media(&download, filename);
 
         // Use the existing add_media function
-        Self::add_media(data, filename, mime_type)
+        Self::add_media(download.bytes, &filename, &mime_type)
+    }
+
+    /// Download media from an http(s) URL within the default limits
+    ///
+    /// Blocks until the download finishes, so keep it off the JNI worker.
+    pub fn download_from_url(url: &str) -> Result<Download, DownloadError> {
+        debug!("Downloading from URL: {}", url);
+
+        download(url, &DownloadLimits::default())
+    }
+
+    /// Pick the filename and MIME type for downloaded media
+    ///
+    /// The filename's extension wins, then the response's Content-Type or the
+    /// sniffed body, then the extension in the final URL. A filename without an
+    /// extension gets one for its type, since AnkiDroid renders media by it.
+    pub fn name_downloaded_media(download: &Download, filename: &str) -> (String, String) {
+        if let Some(mime_type) = Self::determine_mime_type_from_filename(filename) {
+            return (filename.to_string(), mime_type.to_string());
+        }
+
+        let mime_type = download
+            .mime_type()
+            .or_else(|| Self::determine_mime_type_from_url(&download.final_url).map(str::to_string))
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        let has_extension = Path::new(filename).extension().is_some();
+        let filename = match Self::extension_for_mime_type(&mime_type) {
+            Some(extension) if !has_extension => format!("{}.{}", filename, extension),
+            _ => filename.to_string(),
+        };
+
+        (filename, mime_type)
     }
 
     /// Add media from base64 encoded data
@@ -139,44 +303,77 @@ impl MediaHandler {
     /// * `AndroidResult<PathBuf>` - Path to the cache directory
     pub fn get_cache_dir() -> AndroidResult<PathBuf> {
         debug!("Getting app cache directory");
+        Self::get_context_dir("getCacheDir")
+    }
 
-        let env = attach_current_thread()?;
-        let mut safe_env = SafeJNIEnv::new(env);
-        let (_, activity) = get_android_context()?;
+    /// Get the app's private files directory
+    ///
+    /// # Returns
+    /// * `AndroidResult<PathBuf>` - Path to the files directory
+    pub fn get_files_dir() -> AndroidResult<PathBuf> {
+        debug!("Getting app files directory");
+        Self::get_context_dir("getFilesDir")
+    }
 
-        // Get cache directory from Android context
-        let result = safe_env
-            .env_mut()
-            .call_method(&activity, "getCacheDir", "()Ljava/io/File;", &[])
-            .check_exception(safe_env.env_mut())?;
+    /// Get the cache subdirectory media is staged in for AnkiDroid to copy
+    ///
+    /// # Returns
+    /// * `AndroidResult<PathBuf>` - Path to the staging directory
+    pub fn get_media_cache_dir() -> AndroidResult<PathBuf> {
+        Ok(Self::get_cache_dir()?.join(MEDIA_CACHE_DIR))
+    }
 
-        let cache_dir_file = result.l().map_err(AndroidError::from)?;
+    /// Open the index of media already added to AnkiDroid, if it is not yet
+    ///
+    /// Opening looks up the app's files directory over JNI, so call this on the
+    /// JNI worker before media is prepared on any other thread.
+    pub fn open_media_index() {
+        drop(media_index());
+    }
 
-        if cache_dir_file.is_null() {
-            return Err(AndroidError::MediaError(
-                "Cache directory is null".to_string(),
-            ));
-        }
+    /// Check whether the media index has been opened
+    pub fn is_media_index_open() -> bool {
+        MEDIA_INDEX.get().is_some()
+    }
 
-        // Get the absolute path
-        let path_result = safe_env
-            .env_mut()
-            .call_method(
-                &cache_dir_file,
-                "getAbsolutePath",
-                "()Ljava/lang/String;",
-                &[],
-            )
-            .check_exception(safe_env.env_mut())?;
+    /// Remove staged media left behind, e.g. by a crash during an import
+    ///
+    /// # Returns
+    /// * `AndroidResult<SweepReport>` - What was removed
+    pub fn sweep_staged_media() -> AndroidResult<SweepReport> {
+        let dir = Self::get_media_cache_dir()?;
+        let report = sweep(&dir, &SweepLimits::default()).map_err(|e| {
+            AndroidError::MediaError(format!("Failed to sweep {}: {}", dir.display(), e))
+        })?;
 
-        let path_string = StringHelper::jobject_to_rust(
-            &mut safe_env,
-            &path_result.l().map_err(AndroidError::from)?,
-        )?;
-        let cache_path = PathBuf::from(path_string);
+        info!(
+            "Swept staged media: removed {} files ({} bytes), {} bytes remain",
+            report.removed, report.freed_bytes, report.remaining_bytes
+        );
+        Ok(report)
+    }
+
+    /// Forget which media AnkiDroid already has
+    ///
+    /// Call after unused media was deleted in AnkiDroid, so identical files are
+    /// inserted again instead of being referenced by a name that is gone.
+    ///
+    /// # Returns
+    /// * `AndroidResult<usize>` - Number of forgotten files
+    pub fn clear_media_index() -> AndroidResult<usize> {
+        let mut index = media_index();
+        let count = index.len();
+        index
+            .clear()
+            .map_err(|e| AndroidError::MediaError(format!("Failed to clear media index: {}", e)))?;
+
+        info!("Cleared media index ({} entries)", count);
+        Ok(count)
+    }
 
-        debug!("Cache directory: {}", cache_path.display());
-        Ok(cache_path)
+    /// Get the names of media files added through this plugin
+    pub fn known_media_filenames() -> Vec<String> {
+        media_index().filenames().map(str::to_string).collect()
     }
 
     /// Get FileProvider URI for a file
@@ -207,10 +404,10 @@ impl MediaHandler {
         )?;
 
         // Get FileProvider URI using androidx.core.content.FileProvider
-        let file_provider_class =
-            safe_env.find_class_checked("androidx/core/content/FileProvider")?;
+        let file_provider_class = load_app_class(&mut safe_env, &activity, FILE_PROVIDER_CLASS)?;
+        let package_name = Self::get_package_name(&mut safe_env, &activity)?;
         let authority_jstring =
-            safe_env.new_string_checked("com.tauri.plugin.ankidroid.fileprovider")?;
+            safe_env.new_string_checked(&file_provider_authority(&package_name))?;
 
         let uri_result = safe_env
             .env_mut()
@@ -293,10 +490,80 @@ impl MediaHandler {
 
     // Private helper methods
 
+    /// Apply the configured image preprocessing, renaming the file to its new format
+    ///
+    /// Media without options, or that fails to decode, is returned unchanged.
+    fn preprocess(
+        data: Vec<u8>,
+        filename: &str,
+        options: Option<ImagePreprocessing>,
+    ) -> (Vec<u8>, String) {
+        let Some(options) = options else {
+            return (data, filename.to_string());
+        };
+
+        match preprocess_image(&data, &options) {
+            Ok(processed) => {
+                debug!(
+                    "Preprocessed {}: {} -> {} bytes, {}x{} {}",
+                    filename,
+                    data.len(),
+                    processed.data.len(),
+                    processed.width,
+                    processed.height,
+                    processed.mime_type
+                );
+                (
+                    processed.data,
+                    with_extension(filename, processed.extension),
+                )
+            }
+            Err(e) => {
+                warn!("Adding {} unchanged: {}", filename, e);
+                (data, filename.to_string())
+            }
+        }
+    }
+
+    /// Get a directory of the app through a `Context` getter such as `getCacheDir`
+    fn get_context_dir(getter: &str) -> AndroidResult<PathBuf> {
+        let env = attach_current_thread()?;
+        let mut safe_env = SafeJNIEnv::new(env);
+        let (_, activity) = get_android_context()?;
+
+        let result = safe_env
+            .env_mut()
+            .call_method(&activity, getter, "()Ljava/io/File;", &[])
+            .check_exception(safe_env.env_mut())?;
+
+        let dir_file = result.l().map_err(AndroidError::from)?;
+
+        if dir_file.is_null() {
+            return Err(AndroidError::MediaError(format!(
+                "{} returned null",
+                getter
+            )));
+        }
+
+        // Get the absolute path
+        let path_result = safe_env
+            .env_mut()
+            .call_method(&dir_file, "getAbsolutePath", "()Ljava/lang/String;", &[])
+            .check_exception(safe_env.env_mut())?;
+
+        let path_string = StringHelper::jobject_to_rust(
+            &mut safe_env,
+            &path_result.l().map_err(AndroidError::from)?,
+        )?;
+        let path = PathBuf::from(path_string);
+
+        debug!("{}: {}", getter, path.display());
+        Ok(path)
+    }
+
     /// Save media data to cache directory
     fn save_to_cache(data: Vec<u8>, filename: &str) -> AndroidResult<PathBuf> {
-        let cache_dir = Self::get_cache_dir()?;
-        let media_cache_dir = cache_dir.join("media");
+        let media_cache_dir = Self::get_media_cache_dir()?;
 
         // Create media subdirectory if it doesn't exist
         create_dir_all(&media_cache_dir).map_err(|e| {
@@ -457,6 +724,16 @@ impl MediaHandler {
         StringHelper::jobject_to_rust(env, &segment_obj)
     }
 
+    /// Get the package name of the app
+    fn get_package_name(env: &mut SafeJNIEnv, activity: &JObject) -> AndroidResult<String> {
+        let result = env
+            .env_mut()
+            .call_method(activity, "getPackageName", "()Ljava/lang/String;", &[])
+            .check_exception(env.env_mut())?;
+
+        StringHelper::jobject_to_rust(env, &result.l().map_err(AndroidError::from)?)
+    }
+
     /// Grant permission to a specific package
     fn grant_permission_to_package(
         env: &mut SafeJNIEnv,
@@ -483,20 +760,8 @@ impl MediaHandler {
         Ok(())
     }
 
-    /// Download data from URL - simplified implementation
-    fn download_from_url(url: &str) -> AndroidResult<Vec<u8>> {
-        debug!("Downloading from URL: {}", url);
-
-        // For now, return an error indicating this needs to be implemented
-        // A full HTTP implementation would be quite complex in JNI
-        Err(AndroidError::MediaError(
-            "URL download not yet implemented - use add_media or add_media_from_base64 instead"
-                .to_string(),
-        ))
-    }
-
     /// Decode base64 data
-    fn decode_base64(base64_data: &str) -> AndroidResult<Vec<u8>> {
+    pub fn decode_base64(base64_data: &str) -> AndroidResult<Vec<u8>> {
         use base64::{engine::general_purpose, Engine as _};
 
         general_purpose::STANDARD
@@ -505,7 +770,7 @@ impl MediaHandler {
     }
 
     /// Determine MIME type from filename extension
-    fn determine_mime_type_from_filename(filename: &str) -> Option<&'static str> {
+    pub fn determine_mime_type_from_filename(filename: &str) -> Option<&'static str> {
         let extension = Path::new(filename)
             .extension()
             .and_then(|ext| ext.to_str())
@@ -537,6 +802,26 @@ impl MediaHandler {
         }
     }
 
+    /// Get the usual file extension for a MIME type
+    fn extension_for_mime_type(mime_type: &str) -> Option<&'static str> {
+        match mime_type {
+            "image/jpeg" => Some("jpg"),
+            "image/png" => Some("png"),
+            "image/gif" => Some("gif"),
+            "image/webp" => Some("webp"),
+            "image/svg+xml" => Some("svg"),
+            "audio/mpeg" => Some("mp3"),
+            "audio/wav" | "audio/x-wav" => Some("wav"),
+            "audio/ogg" => Some("ogg"),
+            "audio/mp4" => Some("m4a"),
+            "audio/flac" => Some("flac"),
+            "video/mp4" => Some("mp4"),
+            "video/webm" => Some("webm"),
+            "application/pdf" => Some("pdf"),
+            _ => None,
+        }
+    }
+
     /// Determine MIME type from URL extension
     fn determine_mime_type_from_url(url: &str) -> Option<&'static str> {
         // Extract filename from URL
@@ -550,6 +835,20 @@ impl MediaHandler {
     }
 }
 
+/// Media ready to be inserted into AnkiDroid
+#[derive(Debug)]
+pub enum PreparedMedia {
+    /// Identical content is already in AnkiDroid under this filename
+    Known(String),
+    /// New content, preprocessed if it is an image
+    New {
+        data: Vec<u8>,
+        filename: String,
+        /// Key of the original bytes and preprocessing settings, see `media_key`
+        hash: String,
+    },
+}
+
 /// Result of media addition operation
 #[derive(Debug, Clone)]
 pub struct MediaAddResult {
@@ -557,6 +856,8 @@ pub struct MediaAddResult {
     pub uri: String,
     /// The actual filename used by AnkiDroid (may differ from preferred name)
     pub filename: String,
+    /// Whether identical media was already in AnkiDroid; `uri` is empty then
+    pub reused: bool,
 }
 
 #[cfg(test)]
@@ -599,12 +900,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_file_provider_authority() {
+        assert_eq!(
+            file_provider_authority("com.example.flashcards"),
+            "com.example.flashcards.ankidroid.fileprovider"
+        );
+    }
+
+    fn downloaded(content_type: Option<&str>, bytes: &[u8], final_url: &str) -> Download {
+        Download {
+            bytes: bytes.to_vec(),
+            content_type: content_type.map(str::to_string),
+            final_url: final_url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_name_downloaded_media() {
+        // The filename's extension wins over the response
+        let download = downloaded(Some("audio/ogg"), b"OggS", "https://example.com/a");
+        assert_eq!(
+            MediaHandler::name_downloaded_media(&download, "neko.mp3"),
+            ("neko.mp3".to_string(), "audio/mpeg".to_string())
+        );
+
+        // Content-Type names the file
+        assert_eq!(
+            MediaHandler::name_downloaded_media(&download, "neko"),
+            ("neko.ogg".to_string(), "audio/ogg".to_string())
+        );
+
+        // Generic Content-Type falls back to the sniffed body
+        let download = downloaded(
+            Some("application/octet-stream"),
+            b"\x89PNG\r\n\x1a\n",
+            "https://example.com/flag",
+        );
+        assert_eq!(
+            MediaHandler::name_downloaded_media(&download, "flag"),
+            ("flag.png".to_string(), "image/png".to_string())
+        );
+
+        // Then to the extension in the final URL
+        let download = downloaded(None, b"????", "https://cdn.example.com/clip.webm?sig=1");
+        assert_eq!(
+            MediaHandler::name_downloaded_media(&download, "clip"),
+            ("clip.webm".to_string(), "video/webm".to_string())
+        );
+
+        let download = downloaded(None, b"????", "https://example.com/blob");
+        assert_eq!(
+            MediaHandler::name_downloaded_media(&download, "blob"),
+            ("blob".to_string(), "application/octet-stream".to_string())
+        );
+    }
+
     #[test]
     fn test_media_add_result() {
         let result = MediaAddResult {
             uri: "content://com.tauri.plugin.ankidroid.fileprovider/files/media/image.jpg"
                 .to_string(),
             filename: "image_1.jpg".to_string(),
+            reused: false,
         };
 
         assert!(!result.uri.is_empty());
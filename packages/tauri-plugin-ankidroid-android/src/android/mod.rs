@@ -11,8 +11,10 @@ pub mod error;
 pub mod jni_helpers;
 pub mod media;
 pub mod models;
+pub mod observer;
 pub mod sync;
 pub mod validation;
+pub mod worker;
 
 pub use api_check::*;
 pub use card_management::*;
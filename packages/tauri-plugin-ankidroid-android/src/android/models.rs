@@ -5,6 +5,7 @@ use crate::android::content_provider::query;
 use crate::android::cursor::collect_cursor_results;
 use crate::android::error::{AndroidError, AndroidResult};
 use crate::android::jni_helpers::SafeJNIEnv;
+use ankidroid_api_rust::{model, split_fields, ModelDetails};
 use jni::objects::JObject;
 
 /// Find a model ID by name with optional field count validation
@@ -28,7 +29,7 @@ pub fn find_model_id_by_name(
     let projection = vec![
         "_id".to_string(), // Use "_id" instead of "mid" to avoid "Queue 'mid' is unknown" error
         model_columns::NAME.to_string(),
-        model_columns::FLDS.to_string(),
+        model::FIELD_NAMES.to_string(),
     ];
 
     let env_clone = env.clone();
@@ -39,14 +40,7 @@ pub fn find_model_id_by_name(
     let models = collect_cursor_results(cursor, |cursor| {
         let id = cursor.get_long_by_name("_id")?; // Use "_id" instead of "mid"
         let name = cursor.get_string_by_name(model_columns::NAME)?;
-        let fields_json = cursor.get_string_by_name(model_columns::FLDS)?;
-
-        // Parse field count from fields JSON if validation is needed
-        let field_count = if min_field_count.is_some() {
-            parse_field_count(&fields_json)?
-        } else {
-            0
-        };
+        let field_count = split_fields(&cursor.get_string_by_name(model::FIELD_NAMES)?).len();
 
         Ok((id, name, field_count))
     })?;
@@ -150,7 +144,7 @@ pub fn list_models(
     let projection = vec![
         "_id".to_string(), // Use "_id" instead of "mid" to avoid "Queue 'mid' is unknown" error
         model_columns::NAME.to_string(),
-        model_columns::FLDS.to_string(),
+        model::FIELD_NAMES.to_string(),
         model_columns::TYPE.to_string(),
     ];
 
@@ -162,11 +156,9 @@ pub fn list_models(
     collect_cursor_results(cursor, |cursor| {
         let id = cursor.get_long_by_name("_id")?; // Use "_id" instead of "mid"
         let name = cursor.get_string_by_name(model_columns::NAME)?;
-        let fields_json = cursor.get_string_by_name(model_columns::FLDS)?;
+        let field_count = split_fields(&cursor.get_string_by_name(model::FIELD_NAMES)?).len();
         let model_type = cursor.get_int_by_name(model_columns::TYPE)?;
 
-        let field_count = parse_field_count(&fields_json)?;
-
         log::debug!(
             "Found model: {} (ID: {}, type: {}, fields: {})",
             name,
@@ -183,7 +175,7 @@ pub fn model_exists(env: SafeJNIEnv, activity: &JObject, model_id: i64) -> Andro
     log::info!("Checking if model exists: {}", model_id);
 
     let projection = vec!["_id".to_string()]; // Use "_id" instead of "mid" to avoid "Queue 'mid' is unknown" error
-    // Use "_id" for selection clauses instead of "mid" to avoid "Queue 'mid' is unknown" error
+                                              // Use "_id" for selection clauses instead of "mid" to avoid "Queue 'mid' is unknown" error
     let selection = format!("{} = ?", "_id");
     let selection_args = vec![model_id.to_string()];
 
@@ -197,36 +189,28 @@ pub fn model_exists(env: SafeJNIEnv, activity: &JObject, model_id: i64) -> Andro
     Ok(count > 0)
 }
 
-/// Get model information by ID
-pub fn get_model_info(
+/// Get the details of a model by ID
+pub fn get_model_details(
     env: SafeJNIEnv,
     activity: &JObject,
     model_id: i64,
-) -> AndroidResult<(String, usize, i32)> {
-    log::info!("Getting model info for ID: {}", model_id);
+) -> AndroidResult<ModelDetails> {
+    log::info!("Getting model details for ID: {}", model_id);
 
-    let projection = vec![
-        model_columns::NAME.to_string(),
-        model_columns::FLDS.to_string(),
-        model_columns::TYPE.to_string(),
-    ];
     // Use "_id" for selection clauses instead of "mid" to avoid "Queue 'mid' is unknown" error
     let selection = format!("{} = ?", "_id");
     let selection_args = vec![model_id.to_string()];
 
     let cursor = query(env, MODELS_URI)
-        .projection(projection)
+        .projection(ModelDetails::projection())
         .selection(selection)
         .selection_args(selection_args)
         .execute(activity)?;
 
     let results = collect_cursor_results(cursor, |cursor| {
-        let name = cursor.get_string_by_name(model_columns::NAME)?;
-        let fields_json = cursor.get_string_by_name(model_columns::FLDS)?;
-        let model_type = cursor.get_int_by_name(model_columns::TYPE)?;
-
-        let field_count = parse_field_count(&fields_json)?;
-        Ok((name, field_count, model_type))
+        Ok(ModelDetails::from_columns(|column| {
+            cursor.get_string_by_name(column).ok()
+        })?)
     })?;
 
     results
@@ -235,37 +219,6 @@ pub fn get_model_info(
         .ok_or_else(|| AndroidError::model_not_found(format!("Model ID {} not found", model_id)))
 }
 
-/// Parse field count from fields JSON string
-fn parse_field_count(fields_json: &str) -> AndroidResult<usize> {
-    if fields_json.is_empty() {
-        return Ok(0);
-    }
-
-    // Try to parse as JSON array
-    match serde_json::from_str::<serde_json::Value>(fields_json) {
-        Ok(json) => {
-            if let Some(array) = json.as_array() {
-                Ok(array.len())
-            } else {
-                // If not an array, assume it's a single field
-                Ok(1)
-            }
-        }
-        Err(_) => {
-            // If JSON parsing fails, try to count field separators or commas
-            let comma_count = fields_json.chars().filter(|&c| c == ',').count();
-            let separator_count = fields_json.chars().filter(|&c| c == '\u{001f}').count();
-
-            // Use the higher count plus one, or default to 2 for Basic models
-            if comma_count > 0 || separator_count > 0 {
-                Ok(std::cmp::max(comma_count, separator_count) + 1)
-            } else {
-                Ok(2) // Default for Basic model
-            }
-        }
-    }
-}
-
 /// Validate that a model is suitable for basic card operations
 pub fn validate_model_for_cards(
     env: &mut SafeJNIEnv,
@@ -273,7 +226,10 @@ pub fn validate_model_for_cards(
     model_id: i64,
 ) -> AndroidResult<()> {
     let env_for_info = env.clone();
-    let (name, field_count, model_type) = get_model_info(env_for_info, activity, model_id)?;
+    let details = get_model_details(env_for_info, activity, model_id)?;
+    let name = details.model.name;
+    let field_count = details.model.field_names.len();
+    let model_type = details.model.type_;
 
     if field_count < 2 {
         return Err(AndroidError::validation_error(format!(
@@ -303,41 +259,6 @@ pub fn validate_model_for_cards(
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_parse_field_count_json_array() {
-        let fields_json = r#"[{"name":"Front","ord":0},{"name":"Back","ord":1}]"#;
-        let count = parse_field_count(fields_json).unwrap();
-        assert_eq!(count, 2);
-    }
-
-    #[test]
-    fn test_parse_field_count_empty() {
-        let fields_json = "";
-        let count = parse_field_count(fields_json).unwrap();
-        assert_eq!(count, 0);
-    }
-
-    #[test]
-    fn test_parse_field_count_comma_separated() {
-        let fields_json = "Front,Back,Extra";
-        let count = parse_field_count(fields_json).unwrap();
-        assert_eq!(count, 3);
-    }
-
-    #[test]
-    fn test_parse_field_count_separator() {
-        let fields_json = "Front\u{001f}Back\u{001f}Extra";
-        let count = parse_field_count(fields_json).unwrap();
-        assert_eq!(count, 3);
-    }
-
-    #[test]
-    fn test_parse_field_count_fallback() {
-        let fields_json = "SomeUnstructuredText";
-        let count = parse_field_count(fields_json).unwrap();
-        assert_eq!(count, 2); // Default fallback
-    }
-
     #[test]
     fn test_model_validation() {
         // Test model validation logic
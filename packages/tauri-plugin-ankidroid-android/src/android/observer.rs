@@ -0,0 +1,152 @@
+use crate::android::constants::AUTHORITY;
+use crate::android::error::{AndroidResult, JniResultExt};
+use crate::android::jni_helpers::{get_content_resolver, load_app_class, parse_uri, SafeJNIEnv};
+use crate::cache::{self, CacheKind};
+use crate::observer::{ChangeKind, Debouncer};
+use jni::objects::{GlobalRef, JClass, JObject, JString, JValue};
+use jni::JNIEnv;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// Kotlin `ContentObserver` that forwards `onChange` to `nativeOnChange` below
+const OBSERVER_CLASS: &str = "app.tauri.ankidroid.CollectionObserver";
+
+/// Observers registered with the content resolver and the debouncer they report to
+struct Subscription {
+    observers: Vec<GlobalRef>,
+    debouncer: Debouncer,
+}
+
+fn subscription() -> MutexGuard<'static, Option<Subscription>> {
+    static SUBSCRIPTION: OnceLock<Mutex<Option<Subscription>>> = OnceLock::new();
+    SUBSCRIPTION
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        // Holds no invariants beyond the Option itself, so a poisoned lock is still usable
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Observe the provider URI of each kind, replacing any previous subscription
+pub fn subscribe<'local>(
+    env: &mut SafeJNIEnv<'local>,
+    context: &JObject<'local>,
+    kinds: &[ChangeKind],
+    debouncer: Debouncer,
+) -> AndroidResult<()> {
+    unsubscribe(env, context)?;
+
+    let resolver = get_content_resolver(env, context)?;
+    let class = load_app_class(env, context, OBSERVER_CLASS)?;
+
+    let mut observers = Vec::new();
+    for kind in kinds {
+        let uri = format!("content://{}/{}", AUTHORITY, kind.path());
+        match register(env, &resolver, &class, &uri) {
+            Ok(observer) => observers.push(observer),
+            Err(e) => {
+                unregister(env, &resolver, &observers);
+                return Err(e);
+            }
+        }
+        log::info!("Observing {}", uri);
+    }
+
+    *subscription() = Some(Subscription {
+        observers,
+        debouncer,
+    });
+    Ok(())
+}
+
+/// Stop observing, returning false if there was no subscription
+///
+/// Changes still waiting for their debounce window are emitted.
+pub fn unsubscribe<'local>(
+    env: &mut SafeJNIEnv<'local>,
+    context: &JObject<'local>,
+) -> AndroidResult<bool> {
+    // Take the subscription first so late callbacks find nothing to notify
+    let Some(subscription) = subscription().take() else {
+        return Ok(false);
+    };
+
+    let resolver = get_content_resolver(env, context)?;
+    unregister(env, &resolver, &subscription.observers);
+    log::info!("Stopped observing {} URIs", subscription.observers.len());
+    Ok(true)
+}
+
+fn register(
+    env: &mut SafeJNIEnv,
+    resolver: &JObject,
+    class: &JClass,
+    uri: &str,
+) -> AndroidResult<GlobalRef> {
+    let parsed = parse_uri(env, uri)?;
+    let watched = env.new_string_checked(uri)?;
+
+    let observer = env
+        .env_mut()
+        .new_object(class, "(Ljava/lang/String;)V", &[JValue::Object(&watched)])
+        .check_exception(env.env_mut())?;
+
+    // Also notify for descendants such as notes/<id>
+    env.env_mut()
+        .call_method(
+            resolver,
+            "registerContentObserver",
+            "(Landroid/net/Uri;ZLandroid/database/ContentObserver;)V",
+            &[
+                JValue::Object(&parsed),
+                JValue::Bool(1),
+                JValue::Object(&observer),
+            ],
+        )
+        .check_exception(env.env_mut())?;
+
+    env.env_mut()
+        .new_global_ref(&observer)
+        .check_exception(env.env_mut())
+}
+
+fn unregister(env: &mut SafeJNIEnv, resolver: &JObject, observers: &[GlobalRef]) {
+    for observer in observers {
+        let result = env
+            .env_mut()
+            .call_method(
+                resolver,
+                "unregisterContentObserver",
+                "(Landroid/database/ContentObserver;)V",
+                &[JValue::Object(observer.as_obj())],
+            )
+            .check_exception(env.env_mut());
+
+        if let Err(e) = result {
+            log::warn!("Failed to unregister content observer: {}", e);
+        }
+    }
+}
+
+/// Called by `CollectionObserver.onChange` on the main thread
+#[no_mangle]
+pub extern "system" fn Java_app_tauri_ankidroid_CollectionObserver_nativeOnChange(
+    mut env: JNIEnv,
+    _observer: JObject,
+    uri: JString,
+) {
+    let uri: String = match env.get_string(&uri) {
+        Ok(uri) => uri.into(),
+        Err(e) => {
+            log::warn!("Failed to read changed URI: {}", e);
+            return;
+        }
+    };
+
+    // Drop cached IDs right away rather than after the debounce window
+    if let Some(kind) = ChangeKind::from_uri(&uri).and_then(CacheKind::for_change) {
+        cache::global().invalidate(kind);
+    }
+
+    if let Some(subscription) = subscription().as_ref() {
+        subscription.debouncer.notify(&uri);
+    }
+}
@@ -0,0 +1,320 @@
+use super::api_check;
+use super::jni_helpers::SafeJNIEnv;
+use crate::error::{ErrorCode, PluginError};
+use ankidroid_api_rust::AnkiDroidApi;
+use jni::{objects::JObject, JNIEnv, JavaVM};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// Local reference capacity reserved for each job's frame
+const JOB_FRAME_CAPACITY: i32 = 64;
+
+/// Work sent to the JNI worker thread
+///
+/// The job receives the shared API instance, or the error that prevents using
+/// it: a failed initialization or a missing database permission.
+pub type Job =
+    Box<dyn for<'a, 'local> FnOnce(Result<&'a mut AnkiDroidApi<'local>, PluginError>) + Send>;
+
+/// Handle to the dedicated thread that owns the JNI environment and API instance
+///
+/// The thread is attached to the JVM once and keeps a global reference to the
+/// Android context. The `AnkiDroidApi` (and its `ContentResolver`) is created in
+/// the thread's base local frame, so it stays valid for the thread's lifetime and
+/// is reused by every job. Each job runs in its own local frame so references
+/// created while serving a command are released when it finishes.
+///
+/// Jobs run one at a time in submission order, which serializes concurrent
+/// commands.
+pub struct ApiWorker {
+    sender: Mutex<Sender<Job>>,
+}
+
+impl ApiWorker {
+    fn spawn() -> Result<Self, PluginError> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+
+        thread::Builder::new()
+            .name("ankidroid-jni".to_string())
+            .spawn(move || run(receiver))
+            .map_err(|e| {
+                PluginError::internal(format!("Failed to spawn AnkiDroid worker thread: {}", e))
+            })?;
+
+        Ok(Self {
+            sender: Mutex::new(sender),
+        })
+    }
+
+    /// Queue a job on the worker thread
+    pub fn submit(&self, job: Job) -> Result<(), PluginError> {
+        self.sender
+            .lock()
+            .map_err(|_| PluginError::internal("AnkiDroid worker lock poisoned"))?
+            .send(job)
+            .map_err(|_| PluginError::internal("AnkiDroid worker thread has stopped"))
+    }
+}
+
+/// Get the worker, starting its thread on first use
+pub fn global() -> Result<&'static ApiWorker, PluginError> {
+    static WORKER: OnceLock<Result<ApiWorker, PluginError>> = OnceLock::new();
+    WORKER
+        .get_or_init(ApiWorker::spawn)
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+fn run(receiver: mpsc::Receiver<Job>) {
+    let ctx = ndk_context::android_context();
+
+    let vm = match unsafe { JavaVM::from_raw(ctx.vm() as _) } {
+        Ok(vm) => vm,
+        Err(e) => {
+            return fail_all(
+                receiver,
+                PluginError::new(ErrorCode::Jni, format!("Failed to create JavaVM: {}", e)),
+                None,
+            )
+        }
+    };
+
+    // The worker never returns to Java, so attach it for the lifetime of the thread
+    let mut env = match vm.attach_current_thread_permanently() {
+        Ok(env) => env,
+        Err(e) => {
+            return fail_all(
+                receiver,
+                PluginError::new(ErrorCode::Jni, format!("Failed to attach thread: {}", e)),
+                None,
+            )
+        }
+    };
+
+    let context = unsafe { JObject::from_raw(ctx.context() as jni::sys::jobject) };
+    let context = match env.new_global_ref(&context) {
+        Ok(context) => context,
+        Err(e) => {
+            let error = PluginError::new(
+                ErrorCode::Jni,
+                format!("Failed to reference Android context: {}", e),
+            );
+            return fail_all(receiver, error, Some(&mut env));
+        }
+    };
+
+    log::info!("AnkiDroid worker thread started");
+
+    let mut api: Option<AnkiDroidApi> = None;
+
+    for job in receiver {
+        // Create the API lazily and retry on the next job if it fails, e.g. before
+        // the permission has been granted
+        if api.is_none() {
+            let env_owned = unsafe { env.unsafe_clone() };
+            match AnkiDroidApi::try_new(env_owned, context.as_obj()) {
+                Ok(instance) => api = Some(instance),
+                Err(e) => {
+                    let error = PluginError::from(e).context("Failed to initialize AnkiDroid API");
+                    run_in_frame(&mut env, |_| job(Err(error)));
+                    continue;
+                }
+            }
+        }
+
+        let Some(instance) = api.as_mut() else {
+            continue;
+        };
+
+        let completed = run_in_frame(&mut env, |env| {
+            // Checked for every job since the user can revoke it at any time
+            let api = env.and_then(|env| check_permission(env, context.as_obj()).map(|_| instance));
+            job(api)
+        });
+
+        if !completed {
+            // Rebuild the API in case the panic left it in a bad state
+            api = None;
+        }
+    }
+
+    log::info!("AnkiDroid worker thread stopped");
+}
+
+/// Run a job in its own local frame, catching a panic so the worker keeps serving
+///
+/// If the frame cannot be pushed, the job is given the JNI error instead of an
+/// environment so it can reply with the real cause. Returns false if the job
+/// panicked; its reply is dropped and the caller gets an error.
+fn run_in_frame<F>(env: &mut JNIEnv, job: F) -> bool
+where
+    F: FnOnce(Result<&mut JNIEnv, PluginError>),
+{
+    let mut job = Some(job);
+    let result = env.with_local_frame(JOB_FRAME_CAPACITY, |env| {
+        let job = job.take();
+        Ok::<_, jni::errors::Error>(panic::catch_unwind(AssertUnwindSafe(|| {
+            if let Some(job) = job {
+                job(Ok(env))
+            }
+        })))
+    });
+
+    match result {
+        Ok(Ok(())) => true,
+        Ok(Err(_)) => {
+            log::error!("AnkiDroid worker job panicked");
+            false
+        }
+        Err(e) => {
+            log::error!("Failed to manage local frame for AnkiDroid job: {}", e);
+            // Still set if the frame was never pushed; otherwise popping it failed
+            // after the job had replied
+            match job.take() {
+                Some(job) => {
+                    let error = PluginError::new(
+                        ErrorCode::Jni,
+                        format!("Failed to create a local frame for the job: {}", e),
+                    );
+                    panic::catch_unwind(AssertUnwindSafe(|| job(Err(error)))).is_ok()
+                }
+                None => true,
+            }
+        }
+    }
+}
+
+/// Fail with a permission error unless AnkiDroid's database permission is granted
+fn check_permission(env: &mut JNIEnv, context: &JObject) -> Result<(), PluginError> {
+    let mut env = SafeJNIEnv::new(unsafe { env.unsafe_clone() });
+    match api_check::check_permission(&mut env, context) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(PluginError::new(
+            ErrorCode::Permission,
+            "AnkiDroid database permission has not been granted; call requestPermissions first",
+        )),
+        Err(e) => Err(PluginError::from(e).context("Failed to check AnkiDroid permission")),
+    }
+}
+
+/// Fail every job with `error`, in a local frame when the thread is attached
+fn fail_all(receiver: mpsc::Receiver<Job>, error: PluginError, mut env: Option<&mut JNIEnv>) {
+    log::error!("AnkiDroid worker unavailable: {}", error);
+    for job in receiver {
+        let error = error.clone();
+        match env.as_deref_mut() {
+            Some(env) => {
+                run_in_frame(env, |_| job(Err(error)));
+            }
+            None => {
+                if panic::catch_unwind(AssertUnwindSafe(|| job(Err(error)))).is_err() {
+                    log::error!("AnkiDroid worker job panicked");
+                }
+            }
+        }
+    }
+}
+
+struct Slot<R> {
+    value: Option<Result<R, PluginError>>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+/// Create a single-use channel carrying a job's result back to the async caller
+pub fn reply_channel<R>() -> (ReplySender<R>, ReplyReceiver<R>) {
+    let slot = Arc::new(Mutex::new(Slot {
+        value: None,
+        waker: None,
+        closed: false,
+    }));
+    (ReplySender(slot.clone()), ReplyReceiver(slot))
+}
+
+/// Sending half of a reply channel, held by the job
+pub struct ReplySender<R>(Arc<Mutex<Slot<R>>>);
+
+impl<R> ReplySender<R> {
+    /// Deliver the result and wake the waiting command
+    pub fn send(self, value: Result<R, PluginError>) {
+        if let Ok(mut slot) = self.0.lock() {
+            slot.value = Some(value);
+        }
+        // Drop wakes the receiver
+    }
+}
+
+impl<R> Drop for ReplySender<R> {
+    fn drop(&mut self) {
+        let waker = match self.0.lock() {
+            Ok(mut slot) => {
+                slot.closed = true;
+                slot.waker.take()
+            }
+            Err(_) => None,
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Receiving half of a reply channel, awaited by the command
+pub struct ReplyReceiver<R>(Arc<Mutex<Slot<R>>>);
+
+impl<R> Future for ReplyReceiver<R> {
+    type Output = Result<R, PluginError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = match self.0.lock() {
+            Ok(slot) => slot,
+            Err(_) => {
+                return Poll::Ready(Err(PluginError::internal("AnkiDroid reply lock poisoned")))
+            }
+        };
+
+        if let Some(value) = slot.value.take() {
+            return Poll::Ready(value);
+        }
+
+        if slot.closed {
+            return Poll::Ready(Err(PluginError::internal(
+                "AnkiDroid job ended without a result",
+            )));
+        }
+
+        slot.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reply_delivered_across_threads() {
+        let (sender, receiver) = reply_channel::<i64>();
+        thread::spawn(move || sender.send(Ok(42)));
+        assert_eq!(receiver.await, Ok(42));
+    }
+
+    #[tokio::test]
+    async fn test_reply_error_delivered() {
+        let (sender, receiver) = reply_channel::<i64>();
+        sender.send(Err(PluginError::internal("boom")));
+        assert_eq!(receiver.await, Err(PluginError::internal("boom")));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_sender_resolves_with_error() {
+        let (sender, receiver) = reply_channel::<i64>();
+        thread::spawn(move || drop(sender));
+        assert!(receiver.await.is_err());
+    }
+}
@@ -0,0 +1,231 @@
+use crate::observer::ChangeKind;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Default time an ID stays valid before it is looked up again
+pub const DEFAULT_TTL: Duration = Duration::from_secs(300);
+
+/// Which names an invalidation applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    Models,
+    Decks,
+}
+
+impl CacheKind {
+    /// Parse the kind passed by the frontend ("models" or "decks")
+    pub fn parse(kind: &str) -> Result<Self, String> {
+        match kind {
+            "models" => Ok(Self::Models),
+            "decks" => Ok(Self::Decks),
+            other => Err(format!("Unknown cache kind: {}", other)),
+        }
+    }
+
+    /// Cached IDs a collection change may have made stale
+    ///
+    /// Note changes never touch model or deck IDs.
+    pub fn for_change(kind: ChangeKind) -> Option<Self> {
+        match kind {
+            ChangeKind::Models => Some(Self::Models),
+            ChangeKind::Decks => Some(Self::Decks),
+            ChangeKind::Notes => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    id: i64,
+    inserted: Instant,
+}
+
+/// Name to ID map whose entries expire after a TTL
+#[derive(Debug, Default)]
+struct NameIdMap {
+    entries: HashMap<String, Entry>,
+}
+
+impl NameIdMap {
+    fn get(&mut self, name: &str, ttl: Duration) -> Option<i64> {
+        let entry = *self.entries.get(name)?;
+        if entry.inserted.elapsed() < ttl {
+            Some(entry.id)
+        } else {
+            self.entries.remove(name);
+            None
+        }
+    }
+
+    fn insert(&mut self, name: &str, id: i64) {
+        self.entries.insert(
+            name.to_string(),
+            Entry {
+                id,
+                inserted: Instant::now(),
+            },
+        );
+    }
+}
+
+/// Cache of model and deck IDs keyed by name, shared by all commands
+///
+/// Entries expire after the TTL and can be invalidated explicitly, e.g. when the
+/// user renames or deletes a deck in AnkiDroid.
+#[derive(Debug)]
+pub struct IdCache {
+    ttl: Duration,
+    models: NameIdMap,
+    decks: NameIdMap,
+}
+
+impl Default for IdCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL)
+    }
+}
+
+impl IdCache {
+    /// Create an empty cache with the given TTL
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            models: NameIdMap::default(),
+            decks: NameIdMap::default(),
+        }
+    }
+
+    /// Get the TTL
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Change the TTL; existing entries are checked against the new value
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+
+    /// Look up a model ID by name
+    pub fn model_id(&mut self, name: &str) -> Option<i64> {
+        self.models.get(name, self.ttl)
+    }
+
+    /// Remember a model ID
+    pub fn insert_model(&mut self, name: &str, id: i64) {
+        self.models.insert(name, id);
+    }
+
+    /// Look up a deck ID by name
+    pub fn deck_id(&mut self, name: &str) -> Option<i64> {
+        self.decks.get(name, self.ttl)
+    }
+
+    /// Remember a deck ID
+    pub fn insert_deck(&mut self, name: &str, id: i64) {
+        self.decks.insert(name, id);
+    }
+
+    /// Forget a single model
+    pub fn invalidate_model(&mut self, name: &str) {
+        self.models.entries.remove(name);
+    }
+
+    /// Forget a single deck
+    pub fn invalidate_deck(&mut self, name: &str) {
+        self.decks.entries.remove(name);
+    }
+
+    /// Forget every entry of one kind
+    pub fn invalidate(&mut self, kind: CacheKind) {
+        match kind {
+            CacheKind::Models => self.models.entries.clear(),
+            CacheKind::Decks => self.decks.entries.clear(),
+        }
+    }
+
+    /// Forget everything
+    pub fn invalidate_all(&mut self) {
+        self.invalidate(CacheKind::Models);
+        self.invalidate(CacheKind::Decks);
+    }
+}
+
+/// Lock the cache shared by all plugin commands
+pub fn global() -> MutexGuard<'static, IdCache> {
+    static CACHE: OnceLock<Mutex<IdCache>> = OnceLock::new();
+    CACHE
+        .get_or_init(|| Mutex::new(IdCache::default()))
+        .lock()
+        // The cache holds no invariants across entries, so a poisoned lock is still usable
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_lookup() {
+        let mut cache = IdCache::default();
+        assert_eq!(cache.model_id("Basic"), None);
+
+        cache.insert_model("Basic", 1607392319495);
+        cache.insert_deck("Spanish", 42);
+
+        assert_eq!(cache.model_id("Basic"), Some(1607392319495));
+        assert_eq!(cache.deck_id("Spanish"), Some(42));
+        // Models and decks are separate namespaces
+        assert_eq!(cache.deck_id("Basic"), None);
+    }
+
+    #[test]
+    fn test_entries_expire() {
+        let mut cache = IdCache::new(Duration::ZERO);
+        cache.insert_deck("Spanish", 42);
+        assert_eq!(cache.deck_id("Spanish"), None);
+
+        cache.set_ttl(Duration::from_secs(60));
+        cache.insert_deck("Spanish", 42);
+        assert_eq!(cache.deck_id("Spanish"), Some(42));
+    }
+
+    #[test]
+    fn test_invalidation() {
+        let mut cache = IdCache::default();
+        cache.insert_model("Basic", 1);
+        cache.insert_deck("Spanish", 2);
+        cache.insert_deck("French", 3);
+
+        cache.invalidate_deck("Spanish");
+        assert_eq!(cache.deck_id("Spanish"), None);
+        assert_eq!(cache.deck_id("French"), Some(3));
+
+        cache.invalidate(CacheKind::Decks);
+        assert_eq!(cache.deck_id("French"), None);
+        assert_eq!(cache.model_id("Basic"), Some(1));
+
+        cache.invalidate_all();
+        assert_eq!(cache.model_id("Basic"), None);
+    }
+
+    #[test]
+    fn test_cache_kind_parse() {
+        assert_eq!(CacheKind::parse("models"), Ok(CacheKind::Models));
+        assert_eq!(CacheKind::parse("decks"), Ok(CacheKind::Decks));
+        assert!(CacheKind::parse("cards").is_err());
+    }
+
+    #[test]
+    fn test_cache_kind_for_change() {
+        assert_eq!(
+            CacheKind::for_change(ChangeKind::Models),
+            Some(CacheKind::Models)
+        );
+        assert_eq!(
+            CacheKind::for_change(ChangeKind::Decks),
+            Some(CacheKind::Decks)
+        );
+        assert_eq!(CacheKind::for_change(ChangeKind::Notes), None);
+    }
+}
@@ -0,0 +1,340 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Separator between the segments of a nested deck name
+pub const DECK_SEPARATOR: &str = "::";
+
+/// How a deck name given by the frontend is matched against existing decks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeckMatch {
+    /// The name must match a deck exactly
+    #[default]
+    Exact,
+    /// Names are compared ignoring case
+    CaseInsensitive,
+    /// Falls back to decks whose name contains the query, ignoring case
+    Fuzzy,
+}
+
+/// A deck name matched more than one deck
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousDeck {
+    pub query: String,
+    pub candidates: Vec<String>,
+}
+
+impl fmt::Display for AmbiguousDeck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Ambiguous deck name '{}', candidates: {}",
+            self.query,
+            self.candidates.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for AmbiguousDeck {}
+
+/// Find the ID of the deck matching `name` among `(id, name)` pairs
+///
+/// An exact match always wins. Otherwise the mode decides which decks are
+/// candidates; a single candidate is returned and several are reported as
+/// [`AmbiguousDeck`]. `Ok(None)` means no deck matched.
+pub fn resolve_deck(
+    decks: &[(i64, String)],
+    name: &str,
+    mode: DeckMatch,
+) -> Result<Option<i64>, AmbiguousDeck> {
+    if let Some((id, _)) = decks.iter().find(|(_, deck)| deck == name) {
+        return Ok(Some(*id));
+    }
+
+    if mode == DeckMatch::Exact {
+        return Ok(None);
+    }
+
+    let query = name.to_lowercase();
+    let same_case_folded: Vec<_> = decks
+        .iter()
+        .filter(|(_, deck)| deck.to_lowercase() == query)
+        .collect();
+
+    if !same_case_folded.is_empty() || mode == DeckMatch::CaseInsensitive {
+        return single_candidate(name, same_case_folded);
+    }
+
+    let containing = decks
+        .iter()
+        .filter(|(_, deck)| deck.to_lowercase().contains(&query))
+        .collect();
+    single_candidate(name, containing)
+}
+
+fn single_candidate(
+    query: &str,
+    candidates: Vec<&(i64, String)>,
+) -> Result<Option<i64>, AmbiguousDeck> {
+    match candidates.as_slice() {
+        [] => Ok(None),
+        [(id, _)] => Ok(Some(*id)),
+        _ => {
+            let mut names: Vec<String> = candidates.iter().map(|(_, deck)| deck.clone()).collect();
+            names.sort();
+            Err(AmbiguousDeck {
+                query: query.to_string(),
+                candidates: names,
+            })
+        }
+    }
+}
+
+/// A normalized `::`-separated deck name
+///
+/// Whitespace around each segment is removed, so "Lang :: Japanese:: N5"
+/// becomes "Lang::Japanese::N5".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeckPath {
+    segments: Vec<String>,
+}
+
+impl DeckPath {
+    /// Normalize a deck name, rejecting empty segments
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let segments: Vec<String> = name
+            .split(DECK_SEPARATOR)
+            .map(|segment| segment.trim().to_string())
+            .collect();
+
+        if segments.iter().any(|segment| segment.is_empty()) {
+            return Err(format!(
+                "Deck name '{}' contains an empty segment",
+                name.trim()
+            ));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Get the segments from the top-level deck down
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+}
+
+impl fmt::Display for DeckPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.segments.join(DECK_SEPARATOR))
+    }
+}
+
+/// What has to happen for a deck path to exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeckPlan {
+    /// The deck itself when nothing is missing, otherwise its deepest existing ancestor
+    pub existing: Option<(i64, String)>,
+    /// Full names of the decks to create, parents first
+    pub missing: Vec<String>,
+}
+
+/// Work out which decks along `path` exist and which must be created
+///
+/// The full path is matched with `mode` first. Otherwise each ancestor is
+/// looked up in turn ignoring case, in every mode, since AnkiDroid refuses to
+/// create a deck whose name differs from an existing one only in case. The
+/// names of the missing decks are built on the existing parent's actual name.
+pub fn plan_deck_path(
+    decks: &[(i64, String)],
+    path: &DeckPath,
+    mode: DeckMatch,
+) -> Result<DeckPlan, AmbiguousDeck> {
+    let full_name = path.to_string();
+    if let Some(id) = resolve_deck(decks, &full_name, mode)? {
+        return Ok(DeckPlan {
+            existing: Some((id, deck_name(decks, id, &full_name))),
+            missing: Vec::new(),
+        });
+    }
+
+    let mut existing: Option<(i64, String)> = None;
+    let mut missing = Vec::new();
+    let mut parent: Option<String> = None;
+
+    for segment in path.segments() {
+        let name = match &parent {
+            Some(parent) => format!("{}{}{}", parent, DECK_SEPARATOR, segment),
+            None => segment.clone(),
+        };
+
+        if missing.is_empty() {
+            if let Some(id) = resolve_deck(decks, &name, DeckMatch::CaseInsensitive)? {
+                let actual = deck_name(decks, id, &name);
+                existing = Some((id, actual.clone()));
+                parent = Some(actual);
+                continue;
+            }
+        }
+
+        missing.push(name.clone());
+        parent = Some(name);
+    }
+
+    Ok(DeckPlan { existing, missing })
+}
+
+fn deck_name(decks: &[(i64, String)], id: i64, fallback: &str) -> String {
+    decks
+        .iter()
+        .find(|(deck_id, _)| *deck_id == id)
+        .map(|(_, name)| name.clone())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// Outcome of making sure a deck path exists
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnsuredDeck {
+    /// ID of the requested deck
+    pub deck_id: i64,
+    /// Full name of the deck as stored in AnkiDroid
+    pub name: String,
+    /// IDs of the decks created along the path, parents first
+    pub created: Vec<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decks() -> Vec<(i64, String)> {
+        vec![
+            (1, "Default".to_string()),
+            (2, "Spanish".to_string()),
+            (3, "Spanish::Archive".to_string()),
+            (4, "Japanese::N5".to_string()),
+            (5, "japanese::n5".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_exact_match() {
+        let decks = decks();
+        assert_eq!(
+            resolve_deck(&decks, "Spanish", DeckMatch::Exact),
+            Ok(Some(2))
+        );
+        assert_eq!(resolve_deck(&decks, "spanish", DeckMatch::Exact), Ok(None));
+        // A prefix must not resolve to a subdeck
+        assert_eq!(
+            resolve_deck(&decks, "Spanish::Arch", DeckMatch::Exact),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_match() {
+        let decks = decks();
+        assert_eq!(
+            resolve_deck(&decks, "SPANISH", DeckMatch::CaseInsensitive),
+            Ok(Some(2))
+        );
+        assert_eq!(
+            resolve_deck(&decks, "Span", DeckMatch::CaseInsensitive),
+            Ok(None)
+        );
+        // Exact match wins over case-folded duplicates
+        assert_eq!(
+            resolve_deck(&decks, "japanese::n5", DeckMatch::CaseInsensitive),
+            Ok(Some(5))
+        );
+
+        let err = resolve_deck(&decks, "JAPANESE::N5", DeckMatch::CaseInsensitive).unwrap_err();
+        assert_eq!(err.candidates, vec!["Japanese::N5", "japanese::n5"]);
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        let decks = decks();
+        assert_eq!(
+            resolve_deck(&decks, "archive", DeckMatch::Fuzzy),
+            Ok(Some(3))
+        );
+        assert_eq!(
+            resolve_deck(&decks, "spanish", DeckMatch::Fuzzy),
+            Ok(Some(2))
+        );
+        assert_eq!(resolve_deck(&decks, "French", DeckMatch::Fuzzy), Ok(None));
+
+        let err = resolve_deck(&decks, "span", DeckMatch::Fuzzy).unwrap_err();
+        assert_eq!(err.query, "span");
+        assert_eq!(err.candidates, vec!["Spanish", "Spanish::Archive"]);
+        assert!(err.to_string().contains("Spanish::Archive"));
+    }
+
+    #[test]
+    fn test_deck_path_normalization() {
+        let path = DeckPath::parse("Lang :: Japanese:: N5").unwrap();
+        assert_eq!(path.to_string(), "Lang::Japanese::N5");
+        assert_eq!(path.segments(), ["Lang", "Japanese", "N5"]);
+
+        assert_eq!(
+            DeckPath::parse("  Default ").unwrap().to_string(),
+            "Default"
+        );
+        assert!(DeckPath::parse("Lang::::N5").is_err());
+        assert!(DeckPath::parse("Lang:: ::N5").is_err());
+        assert!(DeckPath::parse("::Lang").is_err());
+        assert!(DeckPath::parse("   ").is_err());
+    }
+
+    #[test]
+    fn test_plan_existing_deck() {
+        let decks = decks();
+        let path = DeckPath::parse("Spanish :: Archive").unwrap();
+        let plan = plan_deck_path(&decks, &path, DeckMatch::Exact).unwrap();
+        assert_eq!(plan.existing, Some((3, "Spanish::Archive".to_string())));
+        assert!(plan.missing.is_empty());
+    }
+
+    #[test]
+    fn test_plan_missing_parents() {
+        let decks = decks();
+        let path = DeckPath::parse("Lang :: Japanese:: N5").unwrap();
+        let plan = plan_deck_path(&decks, &path, DeckMatch::Exact).unwrap();
+        assert_eq!(plan.existing, None);
+        assert_eq!(
+            plan.missing,
+            vec!["Lang", "Lang::Japanese", "Lang::Japanese::N5"]
+        );
+
+        let path = DeckPath::parse("Spanish::Archive::2023").unwrap();
+        let plan = plan_deck_path(&decks, &path, DeckMatch::Exact).unwrap();
+        assert_eq!(plan.existing, Some((3, "Spanish::Archive".to_string())));
+        assert_eq!(plan.missing, vec!["Spanish::Archive::2023"]);
+    }
+
+    #[test]
+    fn test_plan_reuses_parent_spelling() {
+        let decks = decks();
+        let path = DeckPath::parse("spanish::Verbs").unwrap();
+
+        for mode in [DeckMatch::Exact, DeckMatch::CaseInsensitive] {
+            let plan = plan_deck_path(&decks, &path, mode).unwrap();
+            assert_eq!(plan.existing, Some((2, "Spanish".to_string())));
+            assert_eq!(plan.missing, vec!["Spanish::Verbs"]);
+        }
+
+        // A deck differing only in case is reused rather than created again
+        let path = DeckPath::parse("spanish::archive").unwrap();
+        let plan = plan_deck_path(&decks, &path, DeckMatch::Exact).unwrap();
+        assert_eq!(plan.existing, Some((3, "Spanish::Archive".to_string())));
+        assert!(plan.missing.is_empty());
+    }
+
+    #[test]
+    fn test_deck_match_deserialize() {
+        let mode: DeckMatch = serde_json::from_str(r#""case_insensitive""#).unwrap();
+        assert_eq!(mode, DeckMatch::CaseInsensitive);
+        assert_eq!(DeckMatch::default(), DeckMatch::Exact);
+    }
+}
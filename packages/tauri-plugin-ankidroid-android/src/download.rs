@@ -0,0 +1,414 @@
+use std::fmt;
+use std::io::{self, Read};
+use std::time::Duration;
+
+/// Limits applied to a media download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadLimits {
+    /// Largest body accepted, in bytes
+    pub max_bytes: u64,
+    /// Time allowed for the whole request, including reading the body
+    pub timeout: Duration,
+    /// Redirects followed before giving up
+    pub max_redirects: u32,
+}
+
+impl Default for DownloadLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 20 * 1024 * 1024,
+            timeout: Duration::from_secs(30),
+            max_redirects: 5,
+        }
+    }
+}
+
+/// Body and metadata of a completed download
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Download {
+    pub bytes: Vec<u8>,
+    /// MIME type from the Content-Type header, lowercased and without parameters
+    pub content_type: Option<String>,
+    /// URL the body was read from, after redirects
+    pub final_url: String,
+}
+
+impl Download {
+    /// Get the most specific MIME type known for the body
+    ///
+    /// A specific Content-Type header wins; generic ones such as
+    /// `application/octet-stream` fall back to sniffing the body.
+    pub fn mime_type(&self) -> Option<String> {
+        self.content_type
+            .clone()
+            .filter(|content_type| !is_generic_mime_type(content_type))
+            .or_else(|| sniff_mime_type(&self.bytes).map(str::to_string))
+    }
+}
+
+/// Reasons a download failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadError {
+    /// Not an http(s) URL
+    InvalidUrl(String),
+    /// The server answered with an error status
+    Status(u16),
+    /// The body is larger than the limit
+    TooLarge { limit: u64 },
+    /// The request did not finish within the timeout
+    Timeout,
+    /// More redirects than allowed
+    TooManyRedirects { limit: u32 },
+    /// Connection or protocol failure
+    Transport(String),
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DownloadError::InvalidUrl(url) => write!(f, "Invalid download URL: '{}'", url),
+            DownloadError::Status(status) => {
+                write!(f, "Download failed with HTTP status {}", status)
+            }
+            DownloadError::TooLarge { limit } => {
+                write!(f, "Download is larger than the limit of {} bytes", limit)
+            }
+            DownloadError::Timeout => write!(f, "Download timed out"),
+            DownloadError::TooManyRedirects { limit } => {
+                write!(f, "Download was redirected more than {} times", limit)
+            }
+            DownloadError::Transport(message) => write!(f, "Download failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+/// Download a URL into memory within the given limits
+///
+/// Blocks the calling thread, so async callers should run it on a blocking
+/// thread.
+pub fn download(url: &str, limits: &DownloadLimits) -> Result<Download, DownloadError> {
+    let url = url.trim();
+    let scheme = url
+        .split_once("://")
+        .map(|(scheme, _)| scheme.to_ascii_lowercase());
+    if !matches!(scheme.as_deref(), Some("http" | "https")) {
+        return Err(DownloadError::InvalidUrl(url.to_string()));
+    }
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(limits.timeout)
+        .redirects(limits.max_redirects)
+        .build();
+
+    let response = agent.get(url).call().map_err(|e| match e {
+        ureq::Error::Status(status, _) => DownloadError::Status(status),
+        ureq::Error::Transport(transport) => match transport.kind() {
+            ureq::ErrorKind::InvalidUrl | ureq::ErrorKind::UnknownScheme => {
+                DownloadError::InvalidUrl(url.to_string())
+            }
+            ureq::ErrorKind::TooManyRedirects => DownloadError::TooManyRedirects {
+                limit: limits.max_redirects,
+            },
+            _ if is_timeout(&transport) => DownloadError::Timeout,
+            _ => DownloadError::Transport(transport.to_string()),
+        },
+    })?;
+
+    // Ureq only reports redirects it could not follow as plain responses
+    if (300..400).contains(&response.status()) {
+        return Err(DownloadError::TooManyRedirects {
+            limit: limits.max_redirects,
+        });
+    }
+
+    let declared_length = response
+        .header("Content-Length")
+        .and_then(|length| length.trim().parse::<u64>().ok());
+    if declared_length.is_some_and(|length| length > limits.max_bytes) {
+        return Err(DownloadError::TooLarge {
+            limit: limits.max_bytes,
+        });
+    }
+
+    let content_type = response.header("Content-Type").and_then(parse_content_type);
+    let final_url = response.get_url().to_string();
+
+    // Read one byte past the limit to tell a full body from a truncated one
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(limits.max_bytes + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => DownloadError::Timeout,
+            _ => DownloadError::Transport(e.to_string()),
+        })?;
+
+    if bytes.len() as u64 > limits.max_bytes {
+        return Err(DownloadError::TooLarge {
+            limit: limits.max_bytes,
+        });
+    }
+
+    Ok(Download {
+        bytes,
+        content_type,
+        final_url,
+    })
+}
+
+fn is_timeout(error: &ureq::Transport) -> bool {
+    let mut source = std::error::Error::source(error);
+    while let Some(error) = source {
+        if let Some(io_error) = error.downcast_ref::<io::Error>() {
+            return matches!(
+                io_error.kind(),
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+            );
+        }
+        source = error.source();
+    }
+    false
+}
+
+/// Reduce a Content-Type header to its lowercase MIME type
+pub fn parse_content_type(header: &str) -> Option<String> {
+    let mime_type = header.split(';').next()?.trim().to_ascii_lowercase();
+    mime_type.contains('/').then_some(mime_type)
+}
+
+/// Check whether a MIME type says nothing about the content
+pub fn is_generic_mime_type(mime_type: &str) -> bool {
+    matches!(
+        mime_type,
+        "application/octet-stream" | "binary/octet-stream" | "application/download"
+    )
+}
+
+/// Guess the MIME type of media from its leading bytes
+pub fn sniff_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    let riff_type = bytes.get(8..12).filter(|_| bytes.starts_with(b"RIFF"));
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if riff_type == Some(b"WEBP") {
+        Some("image/webp")
+    } else if riff_type == Some(b"WAVE") {
+        Some("audio/wav")
+    } else if bytes.starts_with(b"OggS") {
+        Some("audio/ogg")
+    } else if bytes.starts_with(b"fLaC") {
+        Some("audio/flac")
+    } else if bytes.starts_with(b"ID3") || is_mpeg_audio_frame(bytes) {
+        Some("audio/mpeg")
+    } else if bytes.get(4..8) == Some(b"ftyp") {
+        // ISO media; the major brand tells audio-only files apart
+        match bytes.get(8..11) {
+            Some(b"M4A") | Some(b"M4B") => Some("audio/mp4"),
+            _ => Some("video/mp4"),
+        }
+    } else if bytes.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        Some("video/webm")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else if looks_like_svg(bytes) {
+        Some("image/svg+xml")
+    } else {
+        None
+    }
+}
+
+fn is_mpeg_audio_frame(bytes: &[u8]) -> bool {
+    // Frame sync: eleven set bits, with a valid layer
+    matches!(bytes, [0xFF, second, ..] if second & 0xE0 == 0xE0 && second & 0x06 != 0)
+}
+
+fn looks_like_svg(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(256)];
+    let head = String::from_utf8_lossy(head);
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+    (head.starts_with("<?xml") || head.starts_with("<svg")) && head.contains("<svg")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use tiny_http::{Header, Response, Server};
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    /// Serve requests on a local port, answering each with `respond`
+    fn serve<F>(respond: F) -> String
+    where
+        F: Fn(&tiny_http::Request, &str) -> Response<io::Cursor<Vec<u8>>> + Send + 'static,
+    {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", server.server_addr().to_ip().unwrap());
+
+        let base_for_thread = base.clone();
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let response = respond(&request, &base_for_thread);
+                let _ = request.respond(response);
+            }
+        });
+
+        base
+    }
+
+    fn header(name: &str, value: &str) -> Header {
+        Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap()
+    }
+
+    fn limits() -> DownloadLimits {
+        DownloadLimits {
+            max_bytes: 1024,
+            timeout: Duration::from_secs(5),
+            max_redirects: 2,
+        }
+    }
+
+    #[test]
+    fn test_download() {
+        let base = serve(|_, _| {
+            Response::from_data(PNG.to_vec())
+                .with_header(header("Content-Type", "Image/PNG; charset=binary"))
+        });
+
+        let download = download(&format!("{}/flag.png", base), &limits()).unwrap();
+        assert_eq!(download.bytes, PNG);
+        assert_eq!(download.content_type.as_deref(), Some("image/png"));
+        assert_eq!(download.final_url, format!("{}/flag.png", base));
+    }
+
+    #[test]
+    fn test_generic_content_type_is_sniffed() {
+        let base = serve(|_, _| {
+            Response::from_data(b"ID3\x04\0\0\0\0\0\0".to_vec())
+                .with_header(header("Content-Type", "application/octet-stream"))
+        });
+
+        let download = download(&format!("{}/audio?id=7", base), &limits()).unwrap();
+        assert_eq!(download.mime_type().as_deref(), Some("audio/mpeg"));
+    }
+
+    #[test]
+    fn test_redirects() {
+        let base = serve(|request, base| match request.url() {
+            "/final" => Response::from_data(PNG.to_vec()),
+            "/loop" => Response::from_data(Vec::new())
+                .with_status_code(302)
+                .with_header(header("Location", &format!("{}/loop", base))),
+            _ => Response::from_data(Vec::new())
+                .with_status_code(301)
+                .with_header(header("Location", &format!("{}/final", base))),
+        });
+
+        let download = download(&format!("{}/start", base), &limits()).unwrap();
+        assert_eq!(download.final_url, format!("{}/final", base));
+
+        let err = super::download(&format!("{}/loop", base), &limits()).unwrap_err();
+        assert_eq!(err, DownloadError::TooManyRedirects { limit: 2 });
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let base = serve(|request, _| {
+            let body = vec![0u8; 2048];
+            if request.url() == "/chunked" {
+                // No Content-Length, so the limit is enforced while reading
+                Response::new(200.into(), Vec::new(), io::Cursor::new(body), None, None)
+            } else {
+                Response::from_data(body)
+            }
+        });
+
+        let err = download(&format!("{}/sized", base), &limits()).unwrap_err();
+        assert_eq!(err, DownloadError::TooLarge { limit: 1024 });
+
+        let err = download(&format!("{}/chunked", base), &limits()).unwrap_err();
+        assert_eq!(err, DownloadError::TooLarge { limit: 1024 });
+    }
+
+    #[test]
+    fn test_error_status() {
+        let base = serve(|_, _| Response::from_data(Vec::new()).with_status_code(404));
+
+        let err = download(&format!("{}/missing.mp3", base), &limits()).unwrap_err();
+        assert_eq!(err, DownloadError::Status(404));
+        assert_eq!(err.to_string(), "Download failed with HTTP status 404");
+    }
+
+    #[test]
+    fn test_timeout() {
+        let base = serve(|_, _| {
+            thread::sleep(Duration::from_millis(500));
+            Response::from_data(PNG.to_vec())
+        });
+
+        let limits = DownloadLimits {
+            timeout: Duration::from_millis(100),
+            ..limits()
+        };
+        let err = download(&format!("{}/slow", base), &limits).unwrap_err();
+        assert_eq!(err, DownloadError::Timeout);
+    }
+
+    #[test]
+    fn test_invalid_url() {
+        for url in [
+            "",
+            "ftp://example.com/a.mp3",
+            "file:///etc/passwd",
+            "example.com",
+        ] {
+            assert!(matches!(
+                download(url, &limits()),
+                Err(DownloadError::InvalidUrl(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_sniff_mime_type() {
+        assert_eq!(sniff_mime_type(PNG), Some("image/png"));
+        assert_eq!(
+            sniff_mime_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some("image/jpeg")
+        );
+        assert_eq!(sniff_mime_type(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff_mime_type(b"RIFF\0\0\0\0WAVEfmt "), Some("audio/wav"));
+        assert_eq!(
+            sniff_mime_type(&[0xFF, 0xFB, 0x90, 0x64]),
+            Some("audio/mpeg")
+        );
+        assert_eq!(sniff_mime_type(b"\0\0\0\x20ftypM4A "), Some("audio/mp4"));
+        assert_eq!(sniff_mime_type(b"\0\0\0\x20ftypisom"), Some("video/mp4"));
+        assert_eq!(
+            sniff_mime_type(b"<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\">"),
+            Some("image/svg+xml")
+        );
+        assert_eq!(sniff_mime_type(b"<html></html>"), None);
+        assert_eq!(sniff_mime_type(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_content_type() {
+        assert_eq!(
+            parse_content_type("audio/MPEG").as_deref(),
+            Some("audio/mpeg")
+        );
+        assert_eq!(
+            parse_content_type("text/html; charset=utf-8").as_deref(),
+            Some("text/html")
+        );
+        assert_eq!(parse_content_type("garbage"), None);
+    }
+}
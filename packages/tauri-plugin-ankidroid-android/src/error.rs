@@ -0,0 +1,408 @@
+use crate::decks::AmbiguousDeck;
+use crate::download::DownloadError;
+use crate::notes::FieldError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Stable identifier for the kind of failure, for the frontend to match on
+///
+/// Codes shared with the core crate are named after `AnkiDroidError::category`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// AnkiDroid is installed but its API cannot be reached
+    Availability,
+    /// AnkiDroid is not installed
+    NotInstalled,
+    /// The AnkiDroid database permission has not been granted
+    Permission,
+    /// The request was rejected before reaching AnkiDroid
+    Validation,
+    /// The note already exists
+    Duplicate,
+    /// A note, deck, model or template does not exist
+    NotFound,
+    /// A name matched more than one deck or note type
+    Ambiguous,
+    Jni,
+    /// AnkiDroid threw an exception
+    JavaException,
+    ContentProvider,
+    Conversion,
+    Database,
+    Io,
+    Serialization,
+    /// AnkiDroid's database is locked or its provider is not ready yet
+    Busy,
+    Media,
+    Network,
+    Sync,
+    Internal,
+}
+
+impl ErrorCode {
+    /// Get the name the code serializes to
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::Availability => "availability",
+            ErrorCode::NotInstalled => "not_installed",
+            ErrorCode::Permission => "permission",
+            ErrorCode::Validation => "validation",
+            ErrorCode::Duplicate => "duplicate",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::Ambiguous => "ambiguous",
+            ErrorCode::Jni => "jni",
+            ErrorCode::JavaException => "java_exception",
+            ErrorCode::ContentProvider => "content_provider",
+            ErrorCode::Conversion => "conversion",
+            ErrorCode::Database => "database",
+            ErrorCode::Io => "io",
+            ErrorCode::Serialization => "serialization",
+            ErrorCode::Busy => "busy",
+            ErrorCode::Media => "media",
+            ErrorCode::Network => "network",
+            ErrorCode::Sync => "sync",
+            ErrorCode::Internal => "internal",
+        }
+    }
+
+    /// Map a category reported by `AnkiDroidError::category`
+    pub fn from_category(category: &str) -> Self {
+        match category {
+            "availability" => ErrorCode::Availability,
+            "permission" => ErrorCode::Permission,
+            "validation" => ErrorCode::Validation,
+            "duplicate" => ErrorCode::Duplicate,
+            "jni" => ErrorCode::Jni,
+            "conversion" => ErrorCode::Conversion,
+            "database" => ErrorCode::Database,
+            "io" => ErrorCode::Io,
+            "serialization" => ErrorCode::Serialization,
+            "busy" => ErrorCode::Busy,
+            _ => ErrorCode::Internal,
+        }
+    }
+
+    /// Check whether failures of this kind are usually worth retrying
+    ///
+    /// Mirrors `AnkiDroidError::is_recoverable`, plus network failures. Database
+    /// and JNI failures are not: a constraint violation or an unexpected Java
+    /// exception fails the same way on every try.
+    pub fn is_recoverable(self) -> bool {
+        matches!(self, ErrorCode::Busy | ErrorCode::Network)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Machine-readable context for an error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ErrorDetails {
+    /// The number of field values does not match the note type
+    FieldCount { expected: usize, actual: usize },
+    /// Values were given for fields the note type does not have
+    UnknownFields {
+        model: String,
+        fields: Vec<String>,
+        expected: Vec<String>,
+    },
+    /// Required fields were absent or empty
+    MissingFields { model: String, fields: Vec<String> },
+    /// An ID does not refer to an existing model or deck
+    InvalidId { resource: String, id: i64 },
+    /// A name matched several decks or note types
+    Ambiguous {
+        query: String,
+        candidates: Vec<String>,
+    },
+    /// A download was answered with an HTTP error status
+    HttpStatus { status: u16 },
+    /// A download exceeded a size or redirect limit
+    LimitExceeded { limit: u64 },
+    /// The failure was reported by a Java exception
+    JavaException {
+        class_name: String,
+        stack_trace: Vec<String>,
+    },
+}
+
+/// One failed try of a retried operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryAttempt {
+    /// Attempt number, starting at 1
+    pub attempt: u32,
+    pub code: ErrorCode,
+    pub message: String,
+    /// Wait before the next attempt, absent for the last one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+}
+
+impl RetryAttempt {
+    /// Record an attempt that failed with `err`
+    pub fn failed(attempt: u32, err: &PluginError, delay: Option<Duration>) -> Self {
+        Self {
+            attempt,
+            code: err.code,
+            message: err.message.clone(),
+            delay_ms: delay.map(|delay| delay.as_millis() as u64),
+        }
+    }
+}
+
+/// Error returned by every plugin command
+///
+/// Serialized as `{ code, message, recoverable, details?, attempts? }` so the
+/// frontend can branch on `code` instead of parsing `message`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginError {
+    pub code: ErrorCode,
+    /// Human-readable description, not meant to be matched on
+    pub message: String,
+    /// Whether the same call may succeed if retried
+    pub recoverable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<ErrorDetails>,
+    /// Every try, when the operation was retried before failing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<RetryAttempt>,
+}
+
+impl PluginError {
+    /// Create an error, recoverable if its code usually is
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            recoverable: code.is_recoverable(),
+            details: None,
+            attempts: Vec::new(),
+        }
+    }
+
+    /// Create a not found error
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    /// Create a validation error
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Validation, message)
+    }
+
+    /// Create an internal error
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    /// Attach structured details
+    pub fn with_details(mut self, details: ErrorDetails) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Override whether the error is recoverable
+    pub fn with_recoverable(mut self, recoverable: bool) -> Self {
+        self.recoverable = recoverable;
+        self
+    }
+
+    /// Prefix the message with what was being done
+    pub fn context(mut self, context: impl fmt::Display) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+/// Plain messages carry no code, so they are reported as internal errors
+impl From<String> for PluginError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for PluginError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<serde_json::Error> for PluginError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::new(ErrorCode::Serialization, format!("JSON error: {}", err))
+    }
+}
+
+impl From<AmbiguousDeck> for PluginError {
+    fn from(err: AmbiguousDeck) -> Self {
+        Self::new(ErrorCode::Ambiguous, err.to_string()).with_details(ErrorDetails::Ambiguous {
+            query: err.query,
+            candidates: err.candidates,
+        })
+    }
+}
+
+impl From<FieldError> for PluginError {
+    fn from(err: FieldError) -> Self {
+        let message = err.to_string();
+        let details = match err {
+            FieldError::Unknown {
+                model,
+                fields,
+                expected,
+            } => ErrorDetails::UnknownFields {
+                model,
+                fields,
+                expected,
+            },
+            FieldError::Missing { model, fields } => ErrorDetails::MissingFields { model, fields },
+        };
+        Self::validation(message).with_details(details)
+    }
+}
+
+impl From<DownloadError> for PluginError {
+    fn from(err: DownloadError) -> Self {
+        let message = err.to_string();
+        match err {
+            DownloadError::InvalidUrl(_) => Self::validation(message),
+            DownloadError::Status(status) => Self::new(ErrorCode::Network, message)
+                // Server errors may be temporary, client errors will not change
+                .with_recoverable(status >= 500)
+                .with_details(ErrorDetails::HttpStatus { status }),
+            DownloadError::TooLarge { limit } => Self::new(ErrorCode::Network, message)
+                .with_recoverable(false)
+                .with_details(ErrorDetails::LimitExceeded { limit }),
+            DownloadError::TooManyRedirects { limit } => Self::new(ErrorCode::Network, message)
+                .with_recoverable(false)
+                .with_details(ErrorDetails::LimitExceeded {
+                    limit: u64::from(limit),
+                }),
+            DownloadError::Timeout | DownloadError::Transport(_) => {
+                Self::new(ErrorCode::Network, message)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialized_shape() {
+        let err = PluginError::validation("Field count mismatch: expected 3, got 2").with_details(
+            ErrorDetails::FieldCount {
+                expected: 3,
+                actual: 2,
+            },
+        );
+
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({
+                "code": "validation",
+                "message": "Field count mismatch: expected 3, got 2",
+                "recoverable": false,
+                "details": { "type": "field_count", "expected": 3, "actual": 2 }
+            })
+        );
+
+        // Details are left out when there are none
+        let value = serde_json::to_value(PluginError::not_found("Note 1 not found")).unwrap();
+        assert_eq!(value["code"], "not_found");
+        assert!(value.get("details").is_none());
+        assert!(value.get("attempts").is_none());
+    }
+
+    #[test]
+    fn test_codes() {
+        for category in [
+            "availability",
+            "permission",
+            "validation",
+            "duplicate",
+            "jni",
+            "conversion",
+            "database",
+            "io",
+            "serialization",
+            "busy",
+        ] {
+            let code = ErrorCode::from_category(category);
+            assert_eq!(code.as_str(), category);
+            assert_eq!(serde_json::to_value(code).unwrap(), category);
+        }
+        assert_eq!(ErrorCode::from_category("unheard of"), ErrorCode::Internal);
+
+        assert!(ErrorCode::Busy.is_recoverable());
+        assert!(!ErrorCode::Database.is_recoverable());
+        assert!(!ErrorCode::Jni.is_recoverable());
+        assert!(!ErrorCode::Permission.is_recoverable());
+        assert!(PluginError::new(ErrorCode::Busy, "database is locked").recoverable);
+        assert!(!PluginError::new(ErrorCode::Io, "disk full").recoverable);
+
+        // Context added on the way out keeps the code the frontend matches on
+        let err = PluginError::new(ErrorCode::Permission, "Permission denied")
+            .context("Failed to get decks");
+        assert_eq!((err.code, err.recoverable), (ErrorCode::Permission, false));
+    }
+
+    #[test]
+    fn test_plain_messages_are_internal() {
+        let err = PluginError::from("Something broke".to_string());
+        assert_eq!(err.code, ErrorCode::Internal);
+        assert_eq!(err.to_string(), "Something broke");
+        assert_eq!(
+            err.context("Failed to add note").message,
+            "Failed to add note: Something broke"
+        );
+    }
+
+    #[test]
+    fn test_from_domain_errors() {
+        let err = PluginError::from(AmbiguousDeck {
+            query: "span".to_string(),
+            candidates: vec!["Spanish".to_string(), "Spanish::Verbs".to_string()],
+        });
+        assert_eq!(err.code, ErrorCode::Ambiguous);
+        assert!(
+            matches!(err.details, Some(ErrorDetails::Ambiguous { ref candidates, .. }) if candidates.len() == 2)
+        );
+
+        let err = PluginError::from(FieldError::Missing {
+            model: "Basic".to_string(),
+            fields: vec!["Front".to_string()],
+        });
+        assert_eq!(err.code, ErrorCode::Validation);
+        assert!(matches!(
+            err.details,
+            Some(ErrorDetails::MissingFields { .. })
+        ));
+
+        let err = PluginError::from(DownloadError::Status(503));
+        assert_eq!((err.code, err.recoverable), (ErrorCode::Network, true));
+        assert_eq!(err.details, Some(ErrorDetails::HttpStatus { status: 503 }));
+        assert!(!PluginError::from(DownloadError::Status(404)).recoverable);
+        assert!(PluginError::from(DownloadError::Timeout).recoverable);
+        assert_eq!(
+            PluginError::from(DownloadError::InvalidUrl("ftp://x".to_string())).code,
+            ErrorCode::Validation
+        );
+    }
+}
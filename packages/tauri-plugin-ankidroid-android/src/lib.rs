@@ -4,10 +4,20 @@ use tauri::{
     Runtime,
 };
 
+pub mod cache;
 #[cfg(feature = "tauri-plugin")]
 mod commands;
+pub mod decks;
+pub mod download;
+pub mod error;
+pub mod media;
 #[cfg(feature = "tauri-plugin")]
 mod mobile;
+pub mod notes;
+pub mod observer;
+pub mod preprocess;
+pub mod retry;
+pub mod staging;
 pub mod types;
 
 #[cfg(target_os = "android")]
@@ -18,9 +28,15 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("ankidroid")
         .invoke_handler(tauri::generate_handler![
             commands::hello,
+            // AnkiDroid database permission
+            commands::check_permissions,
+            commands::request_permissions,
             // New commands with correct terminology
             commands::list_notes,
+            commands::get_note,
+            commands::get_note_changes,
             commands::create_note,
+            commands::create_note_with_fields,
             commands::update_note,
             commands::delete_note,
             // Legacy commands for backward compatibility
@@ -30,11 +46,46 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             commands::delete_card,
             // Deck operations
             commands::get_decks,
+            commands::get_deck_tree,
+            commands::create_deck,
+            commands::get_deck_details,
+            // Selection and launching
+            commands::get_selected_deck,
+            commands::set_selected_deck,
+            commands::get_current_model,
+            commands::launch_ankidroid,
+            // Collection change events
+            commands::subscribe_changes,
+            commands::unsubscribe_changes,
+            // Card rendering
+            commands::get_cards_for_note,
+            commands::get_card,
+            // Card templates
+            commands::list_templates,
+            commands::get_template,
+            commands::update_template,
+            commands::add_template,
+            // Note types
+            commands::get_model,
+            commands::ensure_model,
+            // Media
+            commands::add_media,
+            commands::add_media_from_base64,
+            commands::add_media_from_url,
+            commands::set_image_preprocessing,
+            commands::clear_media_index,
+            commands::get_note_media,
+            // Model and deck ID cache
+            commands::invalidate_cache,
+            commands::set_cache_ttl,
+            // Retrying transient failures
+            commands::set_retry_policy,
         ])
         .setup(|app, api| {
             mobile::init(app, api)?;
             Ok(())
         })
+        .on_drop(|_app| mobile::teardown())
         .build()
 }
 
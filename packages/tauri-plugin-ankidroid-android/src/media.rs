@@ -0,0 +1,360 @@
+use crate::preprocess::ImagePreprocessing;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How a media file is referenced from a note field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+    Image,
+    Audio,
+    Video,
+    Other,
+}
+
+impl MediaKind {
+    /// Classify a MIME type such as "image/png"
+    pub fn from_mime_type(mime_type: &str) -> Self {
+        let top_level = mime_type.split('/').next().unwrap_or_default();
+        match top_level.trim().to_ascii_lowercase().as_str() {
+            "image" => MediaKind::Image,
+            "audio" => MediaKind::Audio,
+            "video" => MediaKind::Video,
+            _ => MediaKind::Other,
+        }
+    }
+
+    /// Get the markup that references `filename` in a note field
+    ///
+    /// Anki plays both audio and video through `[sound:...]`. Other files have
+    /// no field syntax, so None is returned.
+    pub fn field_markup(self, filename: &str) -> Option<String> {
+        match self {
+            MediaKind::Image => Some(format!(r#"<img src="{}">"#, escape_attribute(filename))),
+            MediaKind::Audio | MediaKind::Video => Some(format!("[sound:{}]", filename)),
+            MediaKind::Other => None,
+        }
+    }
+}
+
+/// Reduce a filename given by the frontend to a bare file name
+///
+/// Directory components are dropped so the name cannot escape the media cache.
+/// Returns None if nothing usable is left.
+pub fn sanitize_filename(filename: &str) -> Option<String> {
+    let name = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>();
+    let name = name.trim();
+
+    match name {
+        "" | "." | ".." => None,
+        name => Some(name.to_string()),
+    }
+}
+
+/// Take a filename from the last path segment of a URL
+///
+/// Query and fragment are ignored. Returns None if the path has no usable name.
+pub fn filename_from_url(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let (_, path) = rest.split_once('/')?;
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    sanitize_filename(path)
+}
+
+/// Hash media content the way Anki does, as lowercase hex SHA-1
+pub fn content_hash(data: &[u8]) -> String {
+    sha1_smol::Sha1::from(data).digest().to_string()
+}
+
+/// Key media is deduplicated under in the media index
+///
+/// Media added unchanged is keyed by its content hash. Media that is
+/// preprocessed also folds in the settings it is processed with, so changing
+/// `max_dimension` or `quality` adds the image again instead of returning the
+/// file produced under the old settings.
+pub fn media_key(data: &[u8], preprocessing: Option<&ImagePreprocessing>) -> String {
+    let Some(options) = preprocessing else {
+        return content_hash(data);
+    };
+
+    let mut hasher = sha1_smol::Sha1::from(data);
+    hasher.update(
+        format!(
+            "\0{:?}:{}:{}",
+            options.format, options.max_dimension, options.quality
+        )
+        .as_bytes(),
+    );
+    hasher.digest().to_string()
+}
+
+/// Map from media key to the name AnkiDroid stored that content under
+///
+/// Used to skip inserting media the collection already has. The map is saved
+/// to its file after every change so it survives restarts. It cannot see media
+/// deleted inside AnkiDroid, so it should be cleared after the user removes
+/// unused media there.
+#[derive(Debug, Default)]
+pub struct MediaIndex {
+    path: Option<PathBuf>,
+    entries: HashMap<String, String>,
+}
+
+impl MediaIndex {
+    /// Create an index that is not saved anywhere
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load the index saved at `path`, starting empty if there is none
+    ///
+    /// An unreadable file is treated as empty; at worst some media is inserted
+    /// again.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable media index {}: {}", path.display(), e);
+                HashMap::new()
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                log::warn!("Failed to read media index {}: {}", path.display(), e);
+                HashMap::new()
+            }
+        };
+
+        Self {
+            path: Some(path),
+            entries,
+        }
+    }
+
+    /// Get the stored filename for a content hash
+    pub fn get(&self, hash: &str) -> Option<&str> {
+        self.entries.get(hash).map(String::as_str)
+    }
+
+    /// Remember the filename content was stored under and save the index
+    pub fn insert(&mut self, hash: &str, filename: &str) -> io::Result<()> {
+        self.entries.insert(hash.to_string(), filename.to_string());
+        self.save()
+    }
+
+    /// Forget one content hash and save the index
+    pub fn remove(&mut self, hash: &str) -> io::Result<()> {
+        if self.entries.remove(hash).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Forget everything and save the index
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.entries.clear();
+        self.save()
+    }
+
+    /// Get the number of known hashes
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether no hashes are known
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the names of all files known to be in the collection
+    pub fn filenames(&self) -> impl Iterator<Item = &str> {
+        self.entries.values().map(String::as_str)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Write a temporary file and rename it so a crash never leaves half an index
+        let json = serde_json::to_vec(&self.entries)?;
+        let temp = temp_path(path);
+        fs::write(&temp, json)?;
+        fs::rename(&temp, path)
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_from_mime_type() {
+        assert_eq!(MediaKind::from_mime_type("image/webp"), MediaKind::Image);
+        assert_eq!(MediaKind::from_mime_type("Audio/MPEG"), MediaKind::Audio);
+        assert_eq!(MediaKind::from_mime_type("video/mp4"), MediaKind::Video);
+        assert_eq!(
+            MediaKind::from_mime_type("application/pdf"),
+            MediaKind::Other
+        );
+        assert_eq!(MediaKind::from_mime_type(""), MediaKind::Other);
+    }
+
+    #[test]
+    fn test_field_markup() {
+        assert_eq!(
+            MediaKind::Image.field_markup("cat_1.png").as_deref(),
+            Some(r#"<img src="cat_1.png">"#)
+        );
+        assert_eq!(
+            MediaKind::Image.field_markup(r#"a "b" & c.png"#).as_deref(),
+            Some(r#"<img src="a &quot;b&quot; &amp; c.png">"#)
+        );
+        assert_eq!(
+            MediaKind::Audio.field_markup("neko.mp3").as_deref(),
+            Some("[sound:neko.mp3]")
+        );
+        assert_eq!(
+            MediaKind::Video.field_markup("clip.mp4").as_deref(),
+            Some("[sound:clip.mp4]")
+        );
+        assert_eq!(MediaKind::Other.field_markup("notes.pdf"), None);
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("cat.png").as_deref(), Some("cat.png"));
+        assert_eq!(
+            sanitize_filename("../../etc/passwd").as_deref(),
+            Some("passwd")
+        );
+        assert_eq!(
+            sanitize_filename("C:\\photos\\ cat.jpg ").as_deref(),
+            Some("cat.jpg")
+        );
+        assert_eq!(
+            sanitize_filename("new\nline.png").as_deref(),
+            Some("newline.png")
+        );
+        assert_eq!(sanitize_filename("dir/"), None);
+        assert_eq!(sanitize_filename(".."), None);
+        assert_eq!(sanitize_filename(""), None);
+    }
+
+    #[test]
+    fn test_filename_from_url() {
+        assert_eq!(
+            filename_from_url("https://example.com/audio/neko.mp3?token=x#t=1").as_deref(),
+            Some("neko.mp3")
+        );
+        assert_eq!(
+            filename_from_url("https://example.com/pronounce?word=neko").as_deref(),
+            Some("pronounce")
+        );
+        assert_eq!(filename_from_url("https://example.com/"), None);
+        assert_eq!(filename_from_url("https://example.com"), None);
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash(b""),
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+        assert_eq!(
+            content_hash(b"abc"),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+    }
+
+    #[test]
+    fn test_media_key() {
+        let options = ImagePreprocessing::default();
+        assert_eq!(media_key(b"abc", None), content_hash(b"abc"));
+        assert_ne!(media_key(b"abc", Some(&options)), content_hash(b"abc"));
+        assert_eq!(
+            media_key(b"abc", Some(&options)),
+            media_key(b"abc", Some(&ImagePreprocessing::default()))
+        );
+
+        let smaller = ImagePreprocessing {
+            max_dimension: 800,
+            ..options
+        };
+        let lower_quality = ImagePreprocessing {
+            quality: 60,
+            ..options
+        };
+        assert_ne!(
+            media_key(b"abc", Some(&options)),
+            media_key(b"abc", Some(&smaller))
+        );
+        assert_ne!(
+            media_key(b"abc", Some(&options)),
+            media_key(b"abc", Some(&lower_quality))
+        );
+    }
+
+    #[test]
+    fn test_media_index_persists() {
+        let dir =
+            std::env::temp_dir().join(format!("ankidroid-media-index-{}", std::process::id()));
+        let path = dir.join("media").join("index.json");
+        let _ = fs::remove_dir_all(&dir);
+
+        let flag = content_hash(b"flag");
+        let mut index = MediaIndex::open(&path);
+        assert!(index.is_empty());
+        index.insert(&flag, "flag_jp.png").unwrap();
+        index.insert(&content_hash(b"audio"), "neko.mp3").unwrap();
+
+        let mut reopened = MediaIndex::open(&path);
+        assert_eq!(reopened.get(&flag), Some("flag_jp.png"));
+        assert_eq!(reopened.len(), 2);
+
+        reopened.remove(&flag).unwrap();
+        assert_eq!(MediaIndex::open(&path).get(&flag), None);
+
+        reopened.clear().unwrap();
+        assert!(MediaIndex::open(&path).is_empty());
+
+        // A damaged file starts an empty index instead of failing
+        fs::write(&path, b"{not json").unwrap();
+        assert!(MediaIndex::open(&path).is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_in_memory_index() {
+        let mut index = MediaIndex::in_memory();
+        index.insert("hash", "a.png").unwrap();
+        assert_eq!(index.get("hash"), Some("a.png"));
+        assert_eq!(index.filenames().collect::<Vec<_>>(), vec!["a.png"]);
+    }
+}
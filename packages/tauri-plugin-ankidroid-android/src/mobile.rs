@@ -1,17 +1,92 @@
-use crate::android::api_wrapper;
-use crate::types::{Card, CreateCardResponse, Deck};
+use crate::android::jni_helpers::SafeJNIEnv;
+use crate::android::media::{MediaHandler, PreparedMedia};
+use crate::android::{api_check, api_wrapper, decks, observer};
+use crate::cache::{self, CacheKind};
+use crate::decks::{DeckMatch, DeckPath, EnsuredDeck};
+use crate::error::{ErrorCode, ErrorDetails, PluginError};
+use crate::media::{filename_from_url, sanitize_filename, MediaKind};
+use crate::notes::{order_fields, ModelRef};
+use crate::observer::{ChangeEvent, ChangeKind, Debouncer, DEFAULT_DEBOUNCE};
+use crate::preprocess::{self, ImagePreprocessing};
+use crate::retry::{self, RetryPolicy};
+use crate::types::{
+    AddMediaResponse, Card, CardTemplate, CreateCardResponse, Deck, DeckDetails, DeckTreeNode,
+    EnsureModelResponse, Model, ModelDrift, ModelSpec, NoteChangesResponse, NoteMediaRef,
+    NoteMediaResponse, NoteView, OperationResponse, RenderedCard,
+};
+use ankidroid_api_rust::media::{missing_media, note_media_refs, MediaRef};
 use ankidroid_api_rust::AnkiDroidApiExtended;
-use tauri::{AppHandle, Runtime};
+use ankidroid_api_rust::{AnkiDroidApi, DriftPolicy, ModelDetails, DEFAULT_DECK_ID};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::plugin::{PermissionState, PluginApi, PluginHandle};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+type ChangeEmitter = Box<dyn Fn(ChangeEvent) + Send + Sync>;
+
+/// Emits collection change events through the app handle given to `init`
+static CHANGE_EMITTER: OnceLock<ChangeEmitter> = OnceLock::new();
+
+/// Alias the database permission is declared under in `AnkiDroidPlugin.kt`
+const PERMISSION_ALIAS: &str = "ankidroid";
+
+/// Handle to the plugin's Kotlin class, kept in app state
+struct AnkiDroidPlugin<R: Runtime>(PluginHandle<R>);
+
+/// State of the permissions the plugin declares, keyed by alias
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PermissionStatus {
+    pub ankidroid: PermissionState,
+}
 
 pub fn init<R: Runtime>(
-    _app: &AppHandle<R>,
-    _api: tauri::plugin::PluginApi<R, ()>,
+    app: &AppHandle<R>,
+    api: PluginApi<R, ()>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     log::info!("Initializing AnkiDroid mobile plugin");
+
+    let handle = api.register_android_plugin("app.tauri.ankidroid", "AnkiDroidPlugin")?;
+    app.manage(AnkiDroidPlugin(handle));
+
+    let app = app.clone();
+    let _ = CHANGE_EMITTER.set(Box::new(move |event: ChangeEvent| {
+        if let Err(e) = app.emit(event.kind.event_name(), &event) {
+            log::warn!("Failed to emit {}: {}", event.kind.event_name(), e);
+        }
+    }));
+
+    // Clear out media staged by earlier runs without holding up startup, and open
+    // the media index on the worker since finding its file takes JNI calls
+    let sweep = api_wrapper::submit_to_worker(|| {
+        MediaHandler::open_media_index();
+        if let Err(e) = MediaHandler::sweep_staged_media() {
+            log::warn!("Failed to sweep staged media: {}", e);
+        }
+    });
+    if let Err(e) = sweep {
+        log::warn!("Failed to schedule staged media sweep: {}", e);
+    }
     Ok(())
 }
 
-pub async fn hello(name: String) -> Result<String, String> {
+/// Stop observing the collection when the plugin is dropped
+pub fn teardown() {
+    let result = api_wrapper::submit_to_api_instance(|api| {
+        let env = unsafe { api.env_mut().env_mut().unsafe_clone() };
+        let mut env = SafeJNIEnv::new(env);
+        observer::unsubscribe(&mut env, api.context())
+            .map(|_| ())
+            .map_err(PluginError::from)
+    });
+
+    if let Err(e) = result {
+        log::warn!("Failed to unregister content observers: {}", e);
+    }
+}
+
+pub async fn hello(name: String) -> Result<String, PluginError> {
     log::info!("Hello called with name: {}", name);
 
     match check_ankidroid_status().await {
@@ -29,7 +104,8 @@ pub async fn create_note(
     back: String,
     deck: Option<String>,
     tags: Option<String>,
-) -> Result<String, String> {
+    deck_match: Option<DeckMatch>,
+) -> Result<String, PluginError> {
     log::info!(
         "Creating note - Front: {}, Back: {}, Deck: {:?}",
         front,
@@ -37,19 +113,44 @@ pub async fn create_note(
         deck
     );
 
-    match create_note_impl(&front, &back, deck.as_deref(), tags.as_deref()).await {
-        Ok(note_id) => {
-            let response = CreateCardResponse::simple_success(note_id);
-            serde_json::to_string(&response)
-                .map_err(|e| format!("Failed to serialize response: {}", e))
-        }
-        Err(e) => {
+    let deck_match = deck_match.unwrap_or_default();
+    let note_id = create_note_impl(front, back, deck, tags, deck_match)
+        .await
+        .map_err(|e| {
             log::error!("Failed to create note: {}", e);
-            let response = CreateCardResponse::error(e);
-            serde_json::to_string(&response)
-                .map_err(|e| format!("Failed to serialize error response: {}", e))
-        }
-    }
+            e
+        })?;
+
+    let response = CreateCardResponse::simple_success(note_id);
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn create_note_with_fields(
+    model: ModelRef,
+    fields: HashMap<String, String>,
+    deck: Option<String>,
+    tags: Option<Vec<String>>,
+    deck_match: Option<DeckMatch>,
+) -> Result<String, PluginError> {
+    log::info!(
+        "Creating note - Model: {}, Fields: {:?}, Deck: {:?}",
+        model,
+        fields.keys().collect::<Vec<_>>(),
+        deck
+    );
+
+    let deck_match = deck_match.unwrap_or_default();
+    let note_id = create_note_with_fields_impl(model, fields, deck, tags, deck_match)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to create note: {}", e);
+            e
+        })?;
+
+    let response = CreateCardResponse::simple_success(note_id);
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
 }
 
 // Legacy wrapper for backward compatibility - redirects to create_note
@@ -58,54 +159,311 @@ pub async fn create_card(
     back: String,
     deck: Option<String>,
     tags: Option<String>,
-) -> Result<String, String> {
-    create_note(front, back, deck, tags).await
+) -> Result<String, PluginError> {
+    create_note(front, back, deck, tags, None).await
 }
 
 // Renamed from list_cards to list_notes - we list Notes, not Cards
-pub async fn list_notes() -> Result<String, String> {
-    log::info!("Listing notes");
+pub async fn list_notes(
+    offset: Option<usize>,
+    limit: Option<usize>,
+    include_decks: bool,
+) -> Result<String, PluginError> {
+    log::info!(
+        "Listing notes (offset: {:?}, limit: {:?}, decks: {})",
+        offset,
+        limit,
+        include_decks
+    );
 
-    match list_notes_impl().await {
-        Ok(notes) => {
-            serde_json::to_string(&notes).map_err(|e| format!("Failed to serialize notes: {}", e))
-        }
-        Err(e) => {
+    let notes = list_notes_impl(offset.unwrap_or(0), limit, include_decks)
+        .await
+        .map_err(|e| {
             log::error!("Failed to list notes: {}", e);
-            // Return error cards to maintain API compatibility
-            let error_cards = vec![Card::new(
-                1,
-                "AnkiDroid Error".to_string(),
-                format!("Error occurred: {}", e),
-                "Error".to_string(),
-                "".to_string(),
-            )];
-            serde_json::to_string(&error_cards)
-                .map_err(|e| format!("Failed to serialize error cards: {}", e))
+            e
+        })?;
+
+    serde_json::to_string(&notes)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize notes"))
+}
+
+pub async fn get_note(note_id: i64) -> Result<String, PluginError> {
+    log::info!("Getting note {}", note_id);
+
+    let note = get_note_impl(note_id).await.map_err(|e| {
+        log::error!("Failed to get note {}: {}", note_id, e);
+        e
+    })?;
+
+    serde_json::to_string(&note)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize note"))
+}
+
+pub async fn get_note_changes(
+    since: i64,
+    known_ids: Vec<i64>,
+    include_decks: bool,
+) -> Result<String, PluginError> {
+    log::info!(
+        "Getting note changes since {} ({} known notes)",
+        since,
+        known_ids.len()
+    );
+
+    let changes = get_note_changes_impl(since, known_ids, include_decks)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to get note changes: {}", e);
+            e
+        })?;
+
+    serde_json::to_string(&changes)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize note changes"))
+}
+
+// Legacy wrapper for backward compatibility - keeps the front/back card format
+pub async fn list_cards() -> Result<String, PluginError> {
+    log::info!("Listing notes as cards");
+
+    let cards = list_cards_impl().await.map_err(|e| {
+        log::error!("Failed to list notes: {}", e);
+        e
+    })?;
+
+    serde_json::to_string(&cards)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize notes"))
+}
+
+pub async fn create_deck(
+    name: String,
+    deck_match: Option<DeckMatch>,
+) -> Result<String, PluginError> {
+    log::info!("Creating deck: {}", name);
+
+    let deck_match = deck_match.unwrap_or_default();
+    let ensured =
+        api_wrapper::with_api_instance(move |api| ensure_deck_path(api, &name, deck_match))
+            .await
+            .map_err(|e| {
+                log::error!("Failed to create deck: {}", e);
+                e
+            })?;
+
+    serde_json::to_string(&ensured)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn get_deck_details(deck_id: i64) -> Result<String, PluginError> {
+    log::info!("Getting details of deck {}", deck_id);
+
+    let details = get_deck_details_impl(deck_id).await.map_err(|e| {
+        log::error!("Failed to get details of deck {}: {}", deck_id, e);
+        e
+    })?;
+
+    serde_json::to_string(&details)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize deck details"))
+}
+
+pub async fn get_selected_deck() -> Result<String, PluginError> {
+    log::info!("Getting selected deck");
+
+    let deck = api_wrapper::with_api_retry(move |api| {
+        let deck = api.get_selected_deck()?;
+        Ok(deck.map(deck_with_stats))
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Failed to get selected deck: {}", e);
+        e
+    })?;
+
+    serde_json::to_string(&deck)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize deck"))
+}
+
+pub async fn set_selected_deck(deck_id: i64) -> Result<String, PluginError> {
+    log::info!("Selecting deck {}", deck_id);
+
+    let result = api_wrapper::with_api_retry(move |api| {
+        api.set_selected_deck(deck_id).map_err(PluginError::from)
+    })
+    .await;
+
+    let response = match result {
+        Ok(true) => OperationResponse::success(Some("Deck selected successfully".to_string())),
+        Ok(false) => OperationResponse::error(format!("Deck {} was not selected", deck_id)),
+        Err(e) => {
+            log::error!("Failed to select deck {}: {}", deck_id, e);
+            return Err(e);
         }
-    }
+    };
+
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
 }
 
-// Legacy wrapper for backward compatibility - redirects to list_notes
-pub async fn list_cards() -> Result<String, String> {
-    list_notes().await
+pub async fn get_current_model() -> Result<String, PluginError> {
+    log::info!("Getting current model");
+
+    let current = api_wrapper::with_api_retry(move |api| {
+        let current = api.get_current_model()?;
+        Ok(current.map(model))
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Failed to get current model: {}", e);
+        e
+    })?;
+
+    serde_json::to_string(&current)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize model"))
 }
 
-pub async fn get_decks() -> Result<String, String> {
-    log::info!("Getting decks");
+pub async fn get_model(model_id: i64) -> Result<String, PluginError> {
+    log::info!("Getting model {}", model_id);
+
+    let details = api_wrapper::with_api_retry(move |api| {
+        let details = api
+            .get_model_details(model_id)?
+            .ok_or_else(|| PluginError::not_found(format!("Model {} not found", model_id)))?;
+        Ok(model(details))
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Failed to get model {}: {}", model_id, e);
+        e
+    })?;
+
+    serde_json::to_string(&details)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize model"))
+}
+
+pub async fn launch_ankidroid(deck_id: Option<i64>) -> Result<String, PluginError> {
+    log::info!("Launching AnkiDroid (deck: {:?})", deck_id);
 
-    match get_decks_impl().await {
-        Ok(decks) => {
-            serde_json::to_string(&decks).map_err(|e| format!("Failed to serialize decks: {}", e))
+    let result = api_wrapper::with_api_instance(move |api| {
+        // Fail with not found here rather than have AnkiDroid review a missing deck
+        if let Some(deck_id) = deck_id {
+            if api.get_deck_details(deck_id)?.is_none() {
+                return Err(PluginError::not_found(format!(
+                    "Deck {} not found",
+                    deck_id
+                )));
+            }
         }
+
+        // Same thread and local frame as the API instance, so the clone stays valid
+        let env = unsafe { api.env_mut().env_mut().unsafe_clone() };
+        let mut env = SafeJNIEnv::new(env);
+        api_check::launch_ankidroid(&mut env, api.context(), deck_id).map_err(PluginError::from)
+    })
+    .await;
+
+    let response = match result {
+        Ok(()) => OperationResponse::success(Some("AnkiDroid launched".to_string())),
         Err(e) => {
-            log::error!("Failed to get decks: {}", e);
-            // Return default deck to maintain API compatibility
-            let default_decks = vec![Deck::new(1, "Default".to_string())];
-            serde_json::to_string(&default_decks)
-                .map_err(|e| format!("Failed to serialize default decks: {}", e))
+            log::error!("Failed to launch AnkiDroid: {}", e);
+            return Err(e);
         }
-    }
+    };
+
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn subscribe_changes(
+    kinds: Option<Vec<ChangeKind>>,
+    debounce_ms: Option<u64>,
+) -> Result<String, PluginError> {
+    let mut kinds = kinds
+        .filter(|kinds| !kinds.is_empty())
+        .unwrap_or_else(|| ChangeKind::ALL.to_vec());
+    kinds.sort();
+    kinds.dedup();
+    let delay = debounce_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_DEBOUNCE);
+    log::info!(
+        "Subscribing to collection changes: {:?} ({:?})",
+        kinds,
+        delay
+    );
+
+    let debouncer = Debouncer::spawn(delay, |event| {
+        if let Some(emit) = CHANGE_EMITTER.get() {
+            emit(event);
+        }
+    })?;
+
+    let observed: Vec<&str> = kinds.iter().map(|kind| kind.path()).collect();
+    let message = format!("Observing {}", observed.join(", "));
+
+    let result = api_wrapper::with_api_instance(move |api| {
+        // Same thread and local frame as the API instance, so the clone stays valid
+        let env = unsafe { api.env_mut().env_mut().unsafe_clone() };
+        let mut env = SafeJNIEnv::new(env);
+        observer::subscribe(&mut env, api.context(), &kinds, debouncer).map_err(PluginError::from)
+    })
+    .await;
+
+    let response = match result {
+        Ok(()) => OperationResponse::success(Some(message)),
+        Err(e) => {
+            log::error!("Failed to subscribe to collection changes: {}", e);
+            return Err(e);
+        }
+    };
+
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn unsubscribe_changes() -> Result<String, PluginError> {
+    log::info!("Unsubscribing from collection changes");
+
+    let result = api_wrapper::with_api_instance(|api| {
+        let env = unsafe { api.env_mut().env_mut().unsafe_clone() };
+        let mut env = SafeJNIEnv::new(env);
+        observer::unsubscribe(&mut env, api.context()).map_err(PluginError::from)
+    })
+    .await;
+
+    let response = match result {
+        Ok(true) => OperationResponse::success(Some("Stopped observing".to_string())),
+        Ok(false) => OperationResponse::success(Some("Not subscribed".to_string())),
+        Err(e) => {
+            log::error!("Failed to unsubscribe from collection changes: {}", e);
+            return Err(e);
+        }
+    };
+
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn get_deck_tree() -> Result<String, PluginError> {
+    log::info!("Getting deck tree");
+
+    let tree = get_deck_tree_impl().await.map_err(|e| {
+        log::error!("Failed to get deck tree: {}", e);
+        e
+    })?;
+
+    serde_json::to_string(&tree)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize deck tree"))
+}
+
+pub async fn get_decks() -> Result<String, PluginError> {
+    log::info!("Getting decks");
+
+    let decks = get_decks_impl().await.map_err(|e| {
+        log::error!("Failed to get decks: {}", e);
+        e
+    })?;
+
+    serde_json::to_string(&decks)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize decks"))
 }
 
 // Renamed from update_card to update_note - we update Notes, not Cards
@@ -115,7 +473,7 @@ pub async fn update_note(
     back: String,
     deck: Option<String>,
     tags: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, PluginError> {
     log::info!(
         "Updating note {} - Front: {}, Back: {}",
         note_id,
@@ -123,23 +481,17 @@ pub async fn update_note(
         back
     );
 
-    match update_note_impl(note_id, &front, &back, deck.as_deref(), tags.as_deref()).await {
-        Ok(success) => {
-            let response = if success {
-                CreateCardResponse::success(note_id, Some("Note updated successfully".to_string()))
-            } else {
-                CreateCardResponse::error("Failed to update note".to_string())
-            };
-            serde_json::to_string(&response)
-                .map_err(|e| format!("Failed to serialize response: {}", e))
-        }
-        Err(e) => {
-            log::error!("Failed to update note: {}", e);
-            let response = CreateCardResponse::error(e);
-            serde_json::to_string(&response)
-                .map_err(|e| format!("Failed to serialize error response: {}", e))
-        }
-    }
+    update_note_impl(note_id, front, back, deck, tags)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to update note {}: {}", note_id, e);
+            e
+        })?;
+
+    let response =
+        CreateCardResponse::success(note_id, Some("Note updated successfully".to_string()));
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
 }
 
 // Legacy wrapper for backward compatibility - redirects to update_note
@@ -149,82 +501,788 @@ pub async fn update_card(
     back: String,
     deck: Option<String>,
     tags: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, PluginError> {
     update_note(note_id, front, back, deck, tags).await
 }
 
 // Renamed from delete_card to delete_note - we delete Notes, not Cards
-pub async fn delete_note(note_id: i64) -> Result<String, String> {
+pub async fn delete_note(note_id: i64) -> Result<String, PluginError> {
     log::info!("Deleting note with ID: {}", note_id);
 
-    match delete_note_impl(note_id).await {
-        Ok(success) => {
-            let response = if success {
-                CreateCardResponse::success(note_id, Some("Note deleted successfully".to_string()))
-            } else {
-                CreateCardResponse::error("Failed to delete note".to_string())
-            };
-            serde_json::to_string(&response)
-                .map_err(|e| format!("Failed to serialize response: {}", e))
+    delete_note_impl(note_id).await.map_err(|e| {
+        log::error!("Failed to delete note {}: {}", note_id, e);
+        e
+    })?;
+
+    let response =
+        CreateCardResponse::success(note_id, Some("Note deleted successfully".to_string()));
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+// Legacy wrapper for backward compatibility - redirects to delete_note
+pub async fn delete_card(note_id: i64) -> Result<String, PluginError> {
+    delete_note(note_id).await
+}
+
+pub async fn get_cards_for_note(note_id: i64) -> Result<String, PluginError> {
+    log::info!("Getting cards for note {}", note_id);
+
+    let cards = get_cards_for_note_impl(note_id).await.map_err(|e| {
+        log::error!("Failed to get cards for note {}: {}", note_id, e);
+        e
+    })?;
+
+    serde_json::to_string(&cards)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize cards"))
+}
+
+pub async fn get_card(note_id: i64, ord: i32) -> Result<String, PluginError> {
+    log::info!("Getting card {} of note {}", ord, note_id);
+
+    let card = get_card_impl(note_id, ord).await.map_err(|e| {
+        log::error!("Failed to get card {} of note {}: {}", ord, note_id, e);
+        e
+    })?;
+
+    serde_json::to_string(&card)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize card"))
+}
+
+pub async fn list_templates(model_id: i64) -> Result<String, PluginError> {
+    log::info!("Listing templates of model {}", model_id);
+
+    let templates = list_templates_impl(model_id).await.map_err(|e| {
+        log::error!("Failed to list templates of model {}: {}", model_id, e);
+        e
+    })?;
+
+    serde_json::to_string(&templates)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize templates"))
+}
+
+pub async fn get_template(model_id: i64, ord: i32) -> Result<String, PluginError> {
+    log::info!("Getting template {} of model {}", ord, model_id);
+
+    let template = get_template_impl(model_id, ord).await.map_err(|e| {
+        log::error!(
+            "Failed to get template {} of model {}: {}",
+            ord,
+            model_id,
+            e
+        );
+        e
+    })?;
+
+    serde_json::to_string(&template)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize template"))
+}
+
+pub async fn update_template(
+    model_id: i64,
+    ord: i32,
+    question_format: Option<String>,
+    answer_format: Option<String>,
+    browser_question_format: Option<String>,
+    browser_answer_format: Option<String>,
+) -> Result<String, PluginError> {
+    log::info!("Updating template {} of model {}", ord, model_id);
+
+    let response = match update_template_impl(
+        model_id,
+        ord,
+        question_format,
+        answer_format,
+        browser_question_format,
+        browser_answer_format,
+    )
+    .await
+    {
+        Ok(true) => OperationResponse::success(Some("Template updated successfully".to_string())),
+        Ok(false) => OperationResponse::error(format!(
+            "Template {} of model {} was not updated",
+            ord, model_id
+        )),
+        Err(e) => {
+            log::error!(
+                "Failed to update template {} of model {}: {}",
+                ord,
+                model_id,
+                e
+            );
+            return Err(e);
         }
+    };
+
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn add_template(
+    model_id: i64,
+    name: String,
+    question_format: String,
+    answer_format: String,
+) -> Result<String, PluginError> {
+    log::info!("Adding template '{}' to model {}", name, model_id);
+
+    let template = add_template_impl(model_id, name.clone(), question_format, answer_format)
+        .await
+        .map_err(|e| {
+            log::error!(
+                "Failed to add template '{}' to model {}: {}",
+                name,
+                model_id,
+                e
+            );
+            e
+        })?;
+
+    serde_json::to_string(&template)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize template"))
+}
+
+pub async fn ensure_model(spec: ModelSpec, apply: bool) -> Result<String, PluginError> {
+    log::info!("Ensuring model '{}' (apply drift: {})", spec.name, apply);
+
+    let policy = if apply {
+        DriftPolicy::Apply
+    } else {
+        DriftPolicy::Report
+    };
+
+    let response = ensure_model_impl(spec, policy).await.map_err(|e| {
+        log::error!("Failed to ensure model: {}", e);
+        e
+    })?;
+
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn add_media(
+    data: Vec<u8>,
+    filename: String,
+    mime_type: Option<String>,
+) -> Result<String, PluginError> {
+    log::info!("Adding media: {} ({} bytes)", filename, data.len());
+
+    let response = add_media_impl(MediaSource::Bytes(data), filename, mime_type).await?;
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn add_media_from_base64(
+    data: String,
+    filename: String,
+    mime_type: Option<String>,
+) -> Result<String, PluginError> {
+    log::info!("Adding media from base64: {}", filename);
+
+    let response = add_media_impl(MediaSource::Base64(data), filename, mime_type).await?;
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn add_media_from_url(
+    url: String,
+    filename: Option<String>,
+) -> Result<String, PluginError> {
+    log::info!("Adding media from URL: {} ({:?})", url, filename);
+
+    // Download off the worker so other commands are not held up by the network
+    let download =
+        tauri::async_runtime::spawn_blocking(move || MediaHandler::download_from_url(&url))
+            .await
+            .map_err(|e| PluginError::internal(format!("Download task failed: {}", e)))?
+            .map_err(|e| {
+                log::error!("Failed to download media: {}", e);
+                PluginError::from(e)
+            })?;
+
+    let filename = filename
+        .or_else(|| filename_from_url(&download.final_url))
+        .unwrap_or_else(|| "media".to_string());
+    let (filename, mime_type) = MediaHandler::name_downloaded_media(&download, &filename);
+
+    let response = add_media_impl(
+        MediaSource::Bytes(download.bytes),
+        filename,
+        Some(mime_type),
+    )
+    .await?;
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn set_image_preprocessing(
+    options: Option<ImagePreprocessing>,
+) -> Result<String, PluginError> {
+    log::info!("Setting image preprocessing: {:?}", options);
+
+    *preprocess::settings() = options;
+    let message = match options {
+        Some(options) => format!(
+            "Images will be scaled to {}px and re-encoded as {:?}",
+            options.max_dimension, options.format
+        ),
+        None => "Images will be added unchanged".to_string(),
+    };
+
+    let response = OperationResponse::success(Some(message));
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn get_note_media(note_id: i64, known_files: Vec<String>) -> Result<String, PluginError> {
+    log::info!(
+        "Getting media of note {} ({} known files)",
+        note_id,
+        known_files.len()
+    );
+
+    let response = get_note_media_impl(note_id, known_files)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to get media of note {}: {}", note_id, e);
+            e
+        })?;
+
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize note media"))
+}
+
+pub async fn clear_media_index() -> Result<String, PluginError> {
+    log::info!("Clearing media index");
+
+    let result = api_wrapper::with_api_instance(|_api| {
+        MediaHandler::clear_media_index().map_err(PluginError::from)
+    })
+    .await;
+
+    let response = match result {
+        Ok(count) => OperationResponse::success(Some(format!("Forgot {} media files", count))),
         Err(e) => {
-            log::error!("Failed to delete note: {}", e);
-            let response = CreateCardResponse::error(e);
-            serde_json::to_string(&response)
-                .map_err(|e| format!("Failed to serialize error response: {}", e))
+            log::error!("Failed to clear media index: {}", e);
+            return Err(e);
         }
-    }
+    };
+
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
 }
 
-// Legacy wrapper for backward compatibility - redirects to delete_note
-pub async fn delete_card(note_id: i64) -> Result<String, String> {
-    delete_note(note_id).await
+pub async fn check_permissions<R: Runtime>(app: &AppHandle<R>) -> Result<String, PluginError> {
+    log::info!("Checking AnkiDroid permission");
+
+    let status: PermissionStatus = plugin_handle(app)?
+        .run_mobile_plugin_async("checkPermissions", ())
+        .await
+        .map_err(|e| PluginError::internal(format!("Failed to check permissions: {}", e)))?;
+
+    serde_json::to_string(&status)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize permission status"))
+}
+
+pub async fn request_permissions<R: Runtime>(app: &AppHandle<R>) -> Result<String, PluginError> {
+    log::info!("Requesting AnkiDroid permission");
+
+    let status: PermissionStatus = plugin_handle(app)?
+        .run_mobile_plugin_async(
+            "requestPermissions",
+            serde_json::json!({ "permissions": [PERMISSION_ALIAS] }),
+        )
+        .await
+        .map_err(|e| PluginError::internal(format!("Failed to request permissions: {}", e)))?;
+    log::info!("AnkiDroid permission is {}", status.ankidroid);
+
+    serde_json::to_string(&status)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize permission status"))
+}
+
+fn plugin_handle<R: Runtime>(app: &AppHandle<R>) -> Result<PluginHandle<R>, PluginError> {
+    app.try_state::<AnkiDroidPlugin<R>>()
+        .map(|plugin| plugin.0.clone())
+        .ok_or_else(|| PluginError::internal("AnkiDroid plugin has not been set up"))
+}
+
+pub async fn set_retry_policy(policy: Option<RetryPolicy>) -> Result<String, PluginError> {
+    log::info!("Setting retry policy: {:?}", policy);
+
+    let policy = policy.unwrap_or_default();
+    policy.validate().map_err(PluginError::validation)?;
+    *retry::settings() = policy;
+
+    let message = format!(
+        "Recoverable failures will be tried up to {} times",
+        policy.max_attempts
+    );
+    let response = OperationResponse::success(Some(message));
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn invalidate_cache(kind: Option<String>) -> Result<String, PluginError> {
+    log::info!("Invalidating ID cache: {:?}", kind);
+
+    let response = match kind.as_deref().map(CacheKind::parse).transpose() {
+        Ok(Some(kind)) => {
+            cache::global().invalidate(kind);
+            OperationResponse::success(Some("Cache invalidated".to_string()))
+        }
+        Ok(None) => {
+            cache::global().invalidate_all();
+            OperationResponse::success(Some("Cache invalidated".to_string()))
+        }
+        Err(e) => return Err(PluginError::validation(e)),
+    };
+
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
+}
+
+pub async fn set_cache_ttl(ttl_secs: Option<u64>) -> Result<String, PluginError> {
+    log::info!("Setting ID cache TTL: {:?}", ttl_secs);
+
+    let ttl = ttl_secs
+        .map(Duration::from_secs)
+        .unwrap_or(cache::DEFAULT_TTL);
+    cache::global().set_ttl(ttl);
+
+    let message = format!("Cached IDs are kept for {}s", ttl.as_secs());
+    let response = OperationResponse::success(Some(message));
+    serde_json::to_string(&response)
+        .map_err(|e| PluginError::from(e).context("Failed to serialize response"))
 }
 
 // Internal implementation functions using ankidroid-api-rust
 
+/// Media content as sent by the frontend
+enum MediaSource {
+    Bytes(Vec<u8>),
+    Base64(String),
+}
+
+async fn add_media_impl(
+    source: MediaSource,
+    filename: String,
+    mime_type: Option<String>,
+) -> Result<AddMediaResponse, PluginError> {
+    let filename = sanitize_filename(&filename).ok_or_else(|| {
+        PluginError::validation(format!("Invalid media filename: '{}'", filename))
+    })?;
+    let mime_type = mime_type
+        .filter(|mime_type| !mime_type.trim().is_empty())
+        .or_else(|| MediaHandler::determine_mime_type_from_filename(&filename).map(str::to_string))
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let kind = MediaKind::from_mime_type(&mime_type);
+
+    // Opening the index takes JNI calls, which belong on the worker. The init job
+    // has usually opened it already.
+    if !MediaHandler::is_media_index_open() {
+        api_wrapper::with_api_instance(|_api| {
+            MediaHandler::open_media_index();
+            Ok(())
+        })
+        .await?;
+    }
+
+    // Decode, hash and preprocess off the worker so other commands are not held up
+    let prepared = tauri::async_runtime::spawn_blocking(move || {
+        let data = match source {
+            MediaSource::Bytes(data) => data,
+            MediaSource::Base64(data) => MediaHandler::decode_base64(&data)?,
+        };
+        MediaHandler::prepare_media(data, &filename, &mime_type)
+    })
+    .await
+    .map_err(|e| PluginError::internal(format!("Media task failed: {}", e)))?
+    .map_err(|e| {
+        log::error!("Failed to prepare media: {}", e);
+        PluginError::from(e)
+    })?;
+
+    let added = match prepared {
+        // Nothing to insert, so there is no need to wait for the worker
+        known @ PreparedMedia::Known(_) => MediaHandler::insert_media(known)?,
+        // Inserts on the worker so its JNI references are released with the job's frame
+        new => api_wrapper::with_api_instance(move |_api| {
+            MediaHandler::insert_media(new).map_err(PluginError::from)
+        })
+        .await
+        .map_err(|e| {
+            log::error!("Failed to add media: {}", e);
+            e
+        })?,
+    };
+
+    Ok(AddMediaResponse {
+        reused: added.reused,
+        ..AddMediaResponse::new(added.filename, kind)
+    })
+}
+
 async fn create_note_impl(
-    front: &str,
-    back: &str,
-    deck: Option<&str>,
-    tags: Option<&str>,
-) -> Result<i64, String> {
-    api_wrapper::with_api_instance(|api| {
-        // Get or create model (use default Basic model)
-        let model_id = api.add_new_basic_model("Basic")
-            .map_err(|e| api_wrapper::format_error(e))?
-            .unwrap_or(1); // Default model ID if already exists
+    front: String,
+    back: String,
+    deck: Option<String>,
+    tags: Option<String>,
+    deck_match: DeckMatch,
+) -> Result<i64, PluginError> {
+    // Normalize up front so the cache is keyed by the name AnkiDroid stores
+    let deck = deck
+        .map(|name| DeckPath::parse(&name).map(|path| path.to_string()))
+        .transpose()
+        .map_err(PluginError::validation)?;
+
+    api_wrapper::with_api_instance(move |api| {
+        let deck = deck.as_deref();
+        let model_id = cached_model_id(api, "Basic")?;
 
         // Get or create deck
         let deck_id = if let Some(deck_name) = deck {
-            api.add_new_deck(deck_name)
-                .map_err(|e| api_wrapper::format_error(e))?
-                .unwrap_or(1) // Default deck if already exists
+            cached_deck_id(api, deck_name, deck_match)?
         } else {
             1 // Default deck ID
         };
 
         // Prepare tags
-        let tag_vec: Option<Vec<&str>> = tags.map(|t| vec![t]);
+        let tag_vec: Option<Vec<&str>> = tags.as_deref().map(|t| vec![t]);
 
         // Add note using ankidroid-api-rust (this creates a Note, which generates Cards)
-        let note_id = api.add_note(
-            model_id,
-            deck_id,
-            &[front, back],
-            tag_vec.as_deref()
-        ).map_err(|e| api_wrapper::format_error(e))?;
+        let note_id = api
+            .add_note(model_id, deck_id, &[&front, &back], tag_vec.as_deref())
+            .map_err(|e| {
+                // A cached ID may point at a model or deck deleted in AnkiDroid
+                let mut cache = cache::global();
+                cache.invalidate_model("Basic");
+                if let Some(deck_name) = deck {
+                    cache.invalidate_deck(deck_name);
+                }
+                PluginError::from(e)
+            })?;
+
+        note_id.ok_or_else(|| PluginError::internal("Failed to create note - no ID returned"))
+    })
+    .await
+}
+
+async fn create_note_with_fields_impl(
+    model: ModelRef,
+    fields: HashMap<String, String>,
+    deck: Option<String>,
+    tags: Option<Vec<String>>,
+    deck_match: DeckMatch,
+) -> Result<i64, PluginError> {
+    let deck = deck
+        .map(|name| DeckPath::parse(&name).map(|path| path.to_string()))
+        .transpose()
+        .map_err(PluginError::validation)?;
+
+    api_wrapper::with_api_instance(move |api| {
+        let details = resolve_model(api, &model)?;
+        let values = order_fields(&details.model.name, &details.model.field_names, &fields)?;
+
+        let deck = deck.as_deref();
+        let deck_id = match deck {
+            Some(deck_name) => cached_deck_id(api, deck_name, deck_match)?,
+            None => DEFAULT_DECK_ID,
+        };
+
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+        let tags: Option<Vec<&str>> = tags
+            .as_ref()
+            .map(|tags| tags.iter().map(String::as_str).collect());
+
+        let note_id = api
+            .add_note(details.model.id, deck_id, &values, tags.as_deref())
+            .map_err(|e| {
+                // A cached ID may point at a model or deck deleted in AnkiDroid
+                let mut cache = cache::global();
+                if let ModelRef::Name(name) = &model {
+                    cache.invalidate_model(name);
+                }
+                if let Some(deck_name) = deck {
+                    cache.invalidate_deck(deck_name);
+                }
+                PluginError::from(e)
+            })?;
+
+        note_id.ok_or_else(|| PluginError::internal("Failed to create note - no ID returned"))
+    })
+    .await
+}
+
+/// Look up a note type by ID or name, using the shared cache for names
+///
+/// Unlike [`cached_model_id`], a missing note type is reported rather than created.
+fn resolve_model(
+    api: &mut AnkiDroidApi<'_>,
+    model: &ModelRef,
+) -> Result<ModelDetails, PluginError> {
+    let name = match model {
+        ModelRef::Id(model_id) => {
+            return api.get_model_details(*model_id)?.ok_or_else(|| {
+                PluginError::not_found(format!("Note type {} not found", model_id))
+            });
+        }
+        ModelRef::Name(name) => name,
+    };
+
+    if let Some(model_id) = cache::global().model_id(name) {
+        let details = api.get_model_details(model_id)?;
+        match details {
+            Some(details) if details.model.name == *name => return Ok(details),
+            // Deleted or renamed in AnkiDroid since it was cached
+            _ => cache::global().invalidate_model(name),
+        }
+    }
+
+    let mut matches: Vec<ModelDetails> = api
+        .list_models()?
+        .into_iter()
+        .filter(|details| details.model.name == *name)
+        .collect();
+
+    match matches.len() {
+        0 => Err(PluginError::not_found(format!(
+            "Note type '{}' not found",
+            name
+        ))),
+        1 => {
+            let details = matches.remove(0);
+            cache::global().insert_model(name, details.model.id);
+            Ok(details)
+        }
+        _ => {
+            let ids: Vec<String> = matches
+                .iter()
+                .map(|details| details.model.id.to_string())
+                .collect();
+            let message = format!(
+                "Ambiguous note type name '{}', pass one of the IDs instead: {}",
+                name,
+                ids.join(", ")
+            );
+            Err(
+                PluginError::new(ErrorCode::Ambiguous, message).with_details(
+                    ErrorDetails::Ambiguous {
+                        query: name.clone(),
+                        candidates: ids,
+                    },
+                ),
+            )
+        }
+    }
+}
+
+/// Resolve a model ID through the shared cache, provisioning the Basic model on a miss
+fn cached_model_id(api: &mut AnkiDroidApi<'_>, name: &str) -> Result<i64, PluginError> {
+    if let Some(model_id) = cache::global().model_id(name) {
+        return Ok(model_id);
+    }
+
+    // Reuse the Basic model if it exists, only create it when missing
+    let model_id = api
+        .ensure_model(
+            &ankidroid_api_rust::ModelSpec::basic(name),
+            DriftPolicy::Report,
+        )?
+        .model_id;
+
+    cache::global().insert_model(name, model_id);
+    Ok(model_id)
+}
+
+/// Resolve a deck ID through the shared cache, creating the deck when nothing matches
+///
+/// Only exact names are served from the cache; other modes always match against
+/// the current deck list so ambiguity is detected.
+fn cached_deck_id(
+    api: &mut AnkiDroidApi<'_>,
+    name: &str,
+    mode: DeckMatch,
+) -> Result<i64, PluginError> {
+    if mode == DeckMatch::Exact {
+        if let Some(deck_id) = cache::global().deck_id(name) {
+            return Ok(deck_id);
+        }
+    }
+
+    Ok(ensure_deck_path(api, name, mode)?.deck_id)
+}
+
+/// Make sure every deck along a `::`-separated path exists and cache the result
+fn ensure_deck_path(
+    api: &mut AnkiDroidApi<'_>,
+    name: &str,
+    mode: DeckMatch,
+) -> Result<EnsuredDeck, PluginError> {
+    // Same thread and local frame as the API instance, so the clone stays valid
+    let env = unsafe { api.env_mut().env_mut().unsafe_clone() };
+    let ensured = decks::ensure_deck_path(SafeJNIEnv::new(env), api.context(), name, mode)?;
+
+    let mut cache = cache::global();
+    // A new deck may take the name of one deleted since it was cached
+    if !ensured.created.is_empty() {
+        cache.invalidate(CacheKind::Decks);
+    }
+    cache.insert_deck(&ensured.name, ensured.deck_id);
+    Ok(ensured)
+}
+
+async fn list_notes_impl(
+    offset: usize,
+    limit: Option<usize>,
+    include_decks: bool,
+) -> Result<Vec<NoteView>, PluginError> {
+    api_wrapper::with_api_retry(move |api| {
+        let notes = api.list_notes()?;
+
+        let models: HashMap<i64, ModelDetails> = api
+            .list_models()?
+            .into_iter()
+            .map(|details| (details.model.id, details))
+            .collect();
+
+        // Paged before the per-note deck queries so each call stays bounded
+        notes
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|note| {
+                let details = models.get(&note.mid);
+                note_view(api, note, details, include_decks)
+            })
+            .collect()
+    })
+    .await
+}
+
+async fn get_note_impl(note_id: i64) -> Result<NoteView, PluginError> {
+    api_wrapper::with_api_retry(move |api| {
+        let note = api
+            .get_note(note_id)?
+            .ok_or_else(|| PluginError::not_found(format!("Note {} not found", note_id)))?;
+
+        let details = api.get_model_details(note.mid)?;
+        note_view(api, note, details.as_ref(), true)
+    })
+    .await
+}
+
+async fn get_note_media_impl(
+    note_id: i64,
+    known_files: Vec<String>,
+) -> Result<NoteMediaResponse, PluginError> {
+    api_wrapper::with_api_retry(move |api| {
+        let note = api
+            .get_note(note_id)?
+            .ok_or_else(|| PluginError::not_found(format!("Note {} not found", note_id)))?;
+
+        let refs = note_media_refs(&note.fields);
 
-        note_id.ok_or_else(|| "Failed to create note - no ID returned".to_string())
+        // The provider cannot list the media folder, so only files the caller
+        // knows about or that were added through this plugin count as present
+        let mut known: HashSet<String> = known_files.iter().cloned().collect();
+        known.extend(MediaHandler::known_media_filenames());
+        let missing = missing_media(&refs, &known);
+
+        let to_view = |media: &MediaRef| NoteMediaRef {
+            kind: media.kind.as_str().to_string(),
+            filename: media.filename.clone(),
+        };
+        Ok(NoteMediaResponse {
+            note_id,
+            missing: missing.into_iter().map(to_view).collect(),
+            refs: refs.iter().map(to_view).collect(),
+        })
     })
+    .await
 }
 
-async fn list_notes_impl() -> Result<Vec<Card>, String> {
-    api_wrapper::with_api_instance(|api| {
+async fn get_note_changes_impl(
+    since: i64,
+    known_ids: Vec<i64>,
+    include_decks: bool,
+) -> Result<NoteChangesResponse, PluginError> {
+    // Taken before reading so edits made during the read show up next time
+    let synced_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| format!("Failed to get current time: {}", e))?
+        .as_secs() as i64;
+
+    api_wrapper::with_api_retry(move |api| {
+        let changes = api.note_changes(since, &known_ids)?;
+
+        let models: HashMap<i64, ModelDetails> = api
+            .list_models()?
+            .into_iter()
+            .map(|details| (details.model.id, details))
+            .collect();
+
+        let mut views =
+            |notes: Vec<ankidroid_api_rust::Note>| -> Result<Vec<NoteView>, PluginError> {
+                notes
+                    .into_iter()
+                    .map(|note| {
+                        let details = models.get(&note.mid);
+                        note_view(api, note, details, include_decks)
+                    })
+                    .collect()
+            };
+
+        Ok(NoteChangesResponse {
+            created: views(changes.created)?,
+            updated: views(changes.updated)?,
+            deleted: changes.deleted,
+            synced_at,
+        })
+    })
+    .await
+}
+
+/// Name a note's fields after its note type and optionally collect the decks of its cards
+///
+/// A note whose note type could not be read keeps generic field names. The
+/// provider has no bulk card query, so each note's decks cost one query.
+fn note_view(
+    api: &mut AnkiDroidApi<'_>,
+    note: ankidroid_api_rust::Note,
+    details: Option<&ModelDetails>,
+    include_decks: bool,
+) -> Result<NoteView, PluginError> {
+    let deck_ids = if include_decks {
+        Some(api.get_note_deck_ids(note.id)?)
+    } else {
+        None
+    };
+
+    let (model_name, field_names) = match details {
+        Some(details) => (
+            details.model.name.clone(),
+            details.model.field_names.as_slice(),
+        ),
+        None => (String::new(), &[][..]),
+    };
+
+    Ok(NoteView {
+        id: note.id,
+        guid: note.guid,
+        model_id: note.mid,
+        model_name,
+        fields: NoteView::name_fields(field_names, note.fields),
+        tags: note.tags,
+        modified: note.mod_,
+        deck_ids,
+    })
+}
+
+async fn list_cards_impl() -> Result<Vec<Card>, PluginError> {
+    api_wrapper::with_api_retry(move |api| {
         // Use the extended API method to list notes
-        let notes = api.list_notes()
-            .map_err(|e| api_wrapper::format_error(e))?;
-        
+        let notes = api.list_notes()?;
+
         // Convert Notes to Card format for frontend compatibility
         let cards: Vec<Card> = notes.into_iter()
             .take(20) // Limit to 20 for performance
@@ -232,12 +1290,12 @@ async fn list_notes_impl() -> Result<Vec<Card>, String> {
                 // Use default deck since we don't have deck info directly from notes
                 let deck_name = "Default".to_string();
                 let deck_id = 1i64; // Default deck ID
-                
+
                 // Join fields with a separator for display
                 let front = note.fields.get(0).cloned().unwrap_or_default();
                 let back = note.fields.get(1).cloned().unwrap_or_default();
                 let tags = note.tags.join(" ");
-                
+
                 Card::with_metadata(
                     note.id,
                     front,
@@ -250,71 +1308,297 @@ async fn list_notes_impl() -> Result<Vec<Card>, String> {
                 )
             })
             .collect();
-        
+
         Ok(cards)
     })
+    .await
 }
 
-async fn get_decks_impl() -> Result<Vec<Deck>, String> {
-    api_wrapper::with_api_instance(|api| {
-        // Get deck list returns HashMap<deck_id, deck_name>
-        let deck_map = api.get_deck_list()
-            .map_err(|e| api_wrapper::format_error(e))?;
-        
+async fn get_decks_impl() -> Result<Vec<Deck>, PluginError> {
+    api_wrapper::with_api_retry(move |api| {
+        let deck_list = api.list_decks()?;
+
+        // Refresh the deck cache while we have the full list
+        let mut cache = cache::global();
+        cache.invalidate(CacheKind::Decks);
+        for deck in &deck_list {
+            cache.insert_deck(&deck.name, deck.id);
+        }
+        drop(cache);
+
         // Convert to expected format
-        let decks: Vec<Deck> = deck_map.into_iter()
-            .map(|(id, name)| Deck::new(id, name))
-            .collect();
-        
+        let decks: Vec<Deck> = deck_list.into_iter().map(deck_with_stats).collect();
+
         Ok(decks)
     })
+    .await
+}
+
+fn deck_with_stats(deck: ankidroid_api_rust::Deck) -> Deck {
+    let mut converted = Deck::with_stats(
+        deck.id,
+        deck.name.clone(),
+        deck.total_cards(),
+        deck.new_cards(),
+        deck.learning_cards(),
+        deck.review_cards(),
+    );
+    if !deck.desc.is_empty() {
+        converted.description = Some(deck.desc);
+    }
+    converted
+}
+
+fn model(details: ankidroid_api_rust::ModelDetails) -> Model {
+    let non_empty = |value: String| Some(value).filter(|value| !value.is_empty());
+    let model = details.model;
+    let field_count = model.field_names.len() as i32;
+
+    Model {
+        css: non_empty(model.css),
+        num_cards: Some(model.num_cards),
+        sort_field_index: Some(model.sort_field_index),
+        deck_id: model.deck_id,
+        latex_pre: non_empty(model.latex_pre),
+        latex_post: non_empty(model.latex_post),
+        note_count: details.note_count,
+        field_names: model.field_names,
+        ..Model::with_type(model.id, model.name, field_count, model.type_)
+    }
+}
+
+async fn get_deck_tree_impl() -> Result<Vec<DeckTreeNode>, PluginError> {
+    api_wrapper::with_api_retry(move |api| {
+        let tree = api.get_deck_tree()?;
+
+        Ok(tree.roots.into_iter().map(deck_tree_node).collect())
+    })
+    .await
+}
+
+async fn get_deck_details_impl(deck_id: i64) -> Result<DeckDetails, PluginError> {
+    api_wrapper::with_api_retry(move |api| {
+        api.get_deck_details(deck_id)?
+            .ok_or_else(|| PluginError::not_found(format!("Deck {} not found", deck_id)))
+    })
+    .await
+}
+
+fn deck_tree_node(node: ankidroid_api_rust::DeckNode) -> DeckTreeNode {
+    DeckTreeNode {
+        id: node.id,
+        name: node.name,
+        full_name: node.full_name,
+        new_count: node.totals.new,
+        learning_count: node.totals.learning,
+        review_count: node.totals.review,
+        children: node.children.into_iter().map(deck_tree_node).collect(),
+    }
 }
 
 async fn update_note_impl(
     note_id: i64,
-    front: &str,
-    back: &str,
-    _deck: Option<&str>,
-    _tags: Option<&str>,
-) -> Result<bool, String> {
-    api_wrapper::with_api_instance(|api| {
+    front: String,
+    back: String,
+    _deck: Option<String>,
+    _tags: Option<String>,
+) -> Result<(), PluginError> {
+    api_wrapper::with_api_retry(move |api| {
         // Use the extended API method to update note
-        api.update_note(note_id, &[front, back])
-            .map_err(|e| api_wrapper::format_error(e))?;
-        
-        Ok(true)
+        api.update_note(note_id, &[&front, &back])?;
+
+        Ok(())
     })
+    .await
 }
 
-async fn delete_note_impl(note_id: i64) -> Result<bool, String> {
-    api_wrapper::with_api_instance(|api| {
+async fn delete_note_impl(note_id: i64) -> Result<(), PluginError> {
+    api_wrapper::with_api_instance(move |api| {
         // Use the extended API method to delete note
-        let deleted = api.delete_note(note_id)
-            .map_err(|e| api_wrapper::format_error(e))?;
-        
-        Ok(deleted)
+        if !api.delete_note(note_id)? {
+            return Err(PluginError::not_found(format!(
+                "Note {} not found",
+                note_id
+            )));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+async fn get_cards_for_note_impl(note_id: i64) -> Result<Vec<RenderedCard>, PluginError> {
+    api_wrapper::with_api_retry(move |api| Ok(api.get_cards_for_note(note_id)?)).await
+}
+
+async fn get_card_impl(note_id: i64, ord: i32) -> Result<Option<RenderedCard>, PluginError> {
+    api_wrapper::with_api_retry(move |api| Ok(api.get_card(note_id, ord)?)).await
+}
+
+async fn list_templates_impl(model_id: i64) -> Result<Vec<CardTemplate>, PluginError> {
+    api_wrapper::with_api_retry(move |api| {
+        let templates = api.list_templates(model_id)?;
+
+        Ok(templates.into_iter().map(card_template).collect())
     })
+    .await
+}
+
+async fn get_template_impl(model_id: i64, ord: i32) -> Result<Option<CardTemplate>, PluginError> {
+    api_wrapper::with_api_retry(move |api| {
+        let template = api.get_template(model_id, ord)?;
+
+        Ok(template.map(card_template))
+    })
+    .await
+}
+
+async fn update_template_impl(
+    model_id: i64,
+    ord: i32,
+    qfmt: Option<String>,
+    afmt: Option<String>,
+    bqfmt: Option<String>,
+    bafmt: Option<String>,
+) -> Result<bool, PluginError> {
+    api_wrapper::with_api_retry(move |api| {
+        api.update_template(
+            model_id,
+            ord,
+            qfmt.as_deref(),
+            afmt.as_deref(),
+            bqfmt.as_deref(),
+            bafmt.as_deref(),
+        )
+        .map_err(PluginError::from)
+    })
+    .await
+}
+
+async fn add_template_impl(
+    model_id: i64,
+    name: String,
+    qfmt: String,
+    afmt: String,
+) -> Result<CardTemplate, PluginError> {
+    api_wrapper::with_api_instance(move |api| {
+        let ord = api.add_template(model_id, &name, &qfmt, &afmt)?;
+
+        let template = api.get_template(model_id, ord)?.ok_or_else(|| {
+            PluginError::not_found(format!(
+                "Template {} of model {} not found after insert",
+                ord, model_id
+            ))
+        })?;
+
+        Ok(card_template(template))
+    })
+    .await
+}
+
+fn card_template(template: ankidroid_api_rust::CardTemplate) -> CardTemplate {
+    CardTemplate {
+        id: template.id,
+        model_id: template.model_id,
+        ord: template.ord,
+        name: template.name,
+        question_format: template.question_format,
+        answer_format: template.answer_format,
+        browser_question_format: template.browser_question_format,
+        browser_answer_format: template.browser_answer_format,
+        card_count: template.card_count,
+    }
+}
+
+async fn ensure_model_impl(
+    spec: ModelSpec,
+    policy: DriftPolicy,
+) -> Result<EnsureModelResponse, PluginError> {
+    let spec = ankidroid_api_rust::ModelSpec {
+        name: spec.name,
+        fields: spec.fields,
+        templates: spec
+            .templates
+            .into_iter()
+            .map(|t| ankidroid_api_rust::TemplateSpec::new(t.name, t.qfmt, t.afmt))
+            .collect(),
+        css: spec.css,
+        sort_field: spec.sort_field,
+        cloze: spec.cloze,
+    };
+
+    api_wrapper::with_api_instance(move |api| {
+        let outcome = api.ensure_model(&spec, policy)?;
+
+        // Replaces an ID cached for a note type deleted and created again
+        if outcome.created {
+            cache::global().insert_model(&spec.name, outcome.model_id);
+        }
+
+        Ok(EnsureModelResponse {
+            model_id: outcome.model_id,
+            created: outcome.created,
+            drift: outcome.drift.into_iter().map(model_drift).collect(),
+            applied: outcome.applied,
+        })
+    })
+    .await
+}
+
+fn model_drift(drift: ankidroid_api_rust::ModelDrift) -> ModelDrift {
+    match drift {
+        ankidroid_api_rust::ModelDrift::Fields { expected, actual } => {
+            ModelDrift::Fields { expected, actual }
+        }
+        ankidroid_api_rust::ModelDrift::Css { expected, actual } => {
+            ModelDrift::Css { expected, actual }
+        }
+        ankidroid_api_rust::ModelDrift::TemplateChanged { ord, name } => {
+            ModelDrift::TemplateChanged { ord, name }
+        }
+        ankidroid_api_rust::ModelDrift::TemplateMissing { ord, name } => {
+            ModelDrift::TemplateMissing { ord, name }
+        }
+        ankidroid_api_rust::ModelDrift::ExtraTemplate { ord, name } => {
+            ModelDrift::ExtraTemplate { ord, name }
+        }
+    }
 }
 
 async fn check_ankidroid_status() -> Result<String, String> {
+    // Checked before awaiting, since the thread's JNI attachment cannot be held across it
+    if !is_ankidroid_installed()? {
+        return Err("AnkiDroid is not installed".to_string());
+    }
+
+    // Try to initialize API to check permissions
+    match api_wrapper::with_api_instance(|_api| Ok(())).await {
+        Ok(_) => Ok("✅ Connected! AnkiDroid is installed and API is accessible.".to_string()),
+        Err(e) => Ok(format!(
+            "⚠️ AnkiDroid is installed but API access failed: {}",
+            e
+        )),
+    }
+}
+
+fn is_ankidroid_installed() -> Result<bool, String> {
     use jni::objects::JValue;
-    
+
     // Get Android context
     let ctx = ndk_context::android_context();
     if ctx.context().is_null() {
         return Err("Android context not initialized".to_string());
     }
-    
+
     // Attach thread and get JNI environment
     let vm = unsafe { jni::JavaVM::from_raw(ctx.vm() as _) }
         .map_err(|e| format!("Failed to create JavaVM: {}", e))?;
-    let mut env = vm.attach_current_thread()
+    let mut env = vm
+        .attach_current_thread()
         .map_err(|e| format!("Failed to attach thread: {}", e))?;
-    
-    let activity = unsafe {
-        jni::objects::JObject::from_raw(ctx.context() as jni::sys::jobject)
-    };
-    
+
+    let activity = unsafe { jni::objects::JObject::from_raw(ctx.context() as jni::sys::jobject) };
+
     // Get PackageManager
     let package_manager = env
         .call_method(
@@ -326,28 +1610,25 @@ async fn check_ankidroid_status() -> Result<String, String> {
         .map_err(|e| format!("Failed to get PackageManager: {}", e))?
         .l()
         .map_err(|e| format!("Failed to convert PackageManager: {}", e))?;
-    
+
     // Check if AnkiDroid is installed
-    let ankidroid_package = env.new_string("com.ichi2.anki")
+    let ankidroid_package = env
+        .new_string("com.ichi2.anki")
         .map_err(|e| format!("Failed to create string: {}", e))?;
-    
+
     let package_info_result = env.call_method(
         &package_manager,
         "getPackageInfo",
         "(Ljava/lang/String;I)Landroid/content/pm/PackageInfo;",
         &[JValue::Object(&ankidroid_package.into()), JValue::Int(0)],
     );
-    
-    match package_info_result {
-        Ok(_) => {
-            // Try to initialize API to check permissions
-            match api_wrapper::with_api_instance(|_api| Ok(())) {
-                Ok(_) => Ok("✅ Connected! AnkiDroid is installed and API is accessible.".to_string()),
-                Err(e) => Ok(format!("⚠️ AnkiDroid is installed but API access failed: {}", e))
-            }
-        }
-        Err(_) => Err("AnkiDroid is not installed".to_string()),
+
+    if package_info_result.is_err() {
+        // NameNotFoundException is left pending by a missing package
+        let _ = env.exception_clear();
+        return Ok(false);
     }
+    Ok(true)
 }
 
 #[cfg(test)]
@@ -373,9 +1654,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_notes_returns_valid_json() {
-        let result = list_notes().await;
-        assert!(result.is_ok());
-        let response = result.unwrap();
+        let response = match list_notes(None, Some(20), true).await {
+            Ok(response) => response,
+            // Without AnkiDroid or its permission the command rejects with a code
+            Err(e) => {
+                assert!(!e.message.is_empty(), "Error should describe the failure");
+                return;
+            }
+        };
 
         // Validate it's valid JSON
         let parsed: Result<serde_json::Value, _> = serde_json::from_str(&response);
@@ -392,9 +1678,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_decks_returns_valid_json() {
-        let result = get_decks().await;
-        assert!(result.is_ok());
-        let response = result.unwrap();
+        let response = match get_decks().await {
+            Ok(response) => response,
+            // Without AnkiDroid or its permission the command rejects with a code
+            Err(e) => {
+                assert!(!e.message.is_empty(), "Error should describe the failure");
+                return;
+            }
+        };
 
         // Should be valid JSON
         let parsed: Result<serde_json::Value, _> = serde_json::from_str(&response);
@@ -424,35 +1715,67 @@ mod tests {
         let result = hello(long_string.clone()).await;
         assert!(result.is_ok(), "hello command should handle long strings");
 
-        // Test create_note with edge cases
-        let result = create_note(long_string.clone(), long_string.clone(), None, None).await;
-        assert!(
-            result.is_ok(),
-            "create_note command should handle long strings"
-        );
+        // Test create_note with edge cases; it rejects when AnkiDroid is unavailable
+        let result = create_note(long_string.clone(), long_string.clone(), None, None, None).await;
+        if let Err(e) = result {
+            assert!(
+                !e.message.is_empty(),
+                "create_note should describe its failure"
+            );
+        }
 
-        // Test list_notes doesn't panic
-        let result = list_notes().await;
-        assert!(result.is_ok(), "list_notes command should not panic");
+        // Test list_notes doesn't panic; it rejects when AnkiDroid is unavailable
+        let result = list_notes(None, None, false).await;
+        if let Err(e) = result {
+            assert!(
+                !e.message.is_empty(),
+                "list_notes should describe its failure"
+            );
+        }
 
         // Test get_decks doesn't panic
         let result = get_decks().await;
-        assert!(result.is_ok(), "get_decks command should not panic");
+        if let Err(e) = result {
+            assert!(
+                !e.message.is_empty(),
+                "get_decks should describe its failure"
+            );
+        }
     }
 
     #[tokio::test]
     async fn test_backward_compatibility() {
-        // Test that legacy functions still work
+        // Test that legacy functions still answer like the commands they wrap
         let result = create_card("Front".to_string(), "Back".to_string(), None, None).await;
-        assert!(result.is_ok(), "create_card wrapper should work");
+        let expected = create_note("Front".to_string(), "Back".to_string(), None, None, None).await;
+        assert_eq!(
+            result.is_ok(),
+            expected.is_ok(),
+            "create_card wrapper should work"
+        );
 
         let result = list_cards().await;
-        assert!(result.is_ok(), "list_cards wrapper should work");
+        if let Err(e) = result {
+            assert!(
+                !e.message.is_empty(),
+                "list_cards wrapper should describe its failure"
+            );
+        }
 
         let result = update_card(1, "Front".to_string(), "Back".to_string(), None, None).await;
-        assert!(result.is_ok(), "update_card wrapper should work");
+        let expected = update_note(1, "Front".to_string(), "Back".to_string(), None, None).await;
+        assert_eq!(
+            result.is_ok(),
+            expected.is_ok(),
+            "update_card wrapper should work"
+        );
 
         let result = delete_card(1).await;
-        assert!(result.is_ok(), "delete_card wrapper should work");
+        if let Err(e) = result {
+            assert!(
+                !e.message.is_empty(),
+                "delete_card wrapper should describe its failure"
+            );
+        }
     }
-}
\ No newline at end of file
+}
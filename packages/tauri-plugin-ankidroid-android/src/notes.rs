@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Note type given by the frontend, either by ID or by name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ModelRef {
+    Id(i64),
+    Name(String),
+}
+
+impl fmt::Display for ModelRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelRef::Id(id) => write!(f, "{}", id),
+            ModelRef::Name(name) => write!(f, "'{}'", name),
+        }
+    }
+}
+
+/// Field values that do not fit a note type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldError {
+    /// Values were given for fields the note type does not have
+    Unknown {
+        model: String,
+        fields: Vec<String>,
+        expected: Vec<String>,
+    },
+    /// Required fields were absent or empty
+    Missing { model: String, fields: Vec<String> },
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::Unknown {
+                model,
+                fields,
+                expected,
+            } => write!(
+                f,
+                "Unknown field(s) for note type '{}': {} (expected: {})",
+                model,
+                fields.join(", "),
+                expected.join(", ")
+            ),
+            FieldError::Missing { model, fields } => write!(
+                f,
+                "Missing required field(s) for note type '{}': {}",
+                model,
+                fields.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Arrange named field values in the order of the note type's fields
+///
+/// Fields without a value are left empty, except the first field: Anki
+/// identifies notes by it, so it must be present and non-empty.
+pub fn order_fields(
+    model: &str,
+    field_names: &[String],
+    values: &HashMap<String, String>,
+) -> Result<Vec<String>, FieldError> {
+    let mut unknown: Vec<String> = values
+        .keys()
+        .filter(|name| !field_names.contains(name))
+        .cloned()
+        .collect();
+
+    if !unknown.is_empty() {
+        unknown.sort();
+        return Err(FieldError::Unknown {
+            model: model.to_string(),
+            fields: unknown,
+            expected: field_names.to_vec(),
+        });
+    }
+
+    if let Some(first) = field_names.first() {
+        let present = values
+            .get(first)
+            .is_some_and(|value| !value.trim().is_empty());
+        if !present {
+            return Err(FieldError::Missing {
+                model: model.to_string(),
+                fields: vec![first.clone()],
+            });
+        }
+    }
+
+    Ok(field_names
+        .iter()
+        .map(|name| values.get(name).cloned().unwrap_or_default())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_names() -> Vec<String> {
+        vec![
+            "Word".to_string(),
+            "Reading".to_string(),
+            "Meaning".to_string(),
+        ]
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_order_fields() {
+        let ordered = order_fields(
+            "Vocabulary",
+            &field_names(),
+            &values(&[("Meaning", "cat"), ("Word", "猫")]),
+        )
+        .unwrap();
+        assert_eq!(ordered, vec!["猫", "", "cat"]);
+    }
+
+    #[test]
+    fn test_unknown_fields_rejected() {
+        let err = order_fields(
+            "Vocabulary",
+            &field_names(),
+            &values(&[("Word", "猫"), ("Front", "x"), ("Back", "y")]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            FieldError::Unknown {
+                model: "Vocabulary".to_string(),
+                fields: vec!["Back".to_string(), "Front".to_string()],
+                expected: field_names(),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Unknown field(s) for note type 'Vocabulary': Back, Front (expected: Word, Reading, Meaning)"
+        );
+    }
+
+    #[test]
+    fn test_missing_first_field_rejected() {
+        let err =
+            order_fields("Vocabulary", &field_names(), &values(&[("Meaning", "cat")])).unwrap_err();
+        assert!(matches!(err, FieldError::Missing { ref fields, .. } if fields == &["Word"]));
+
+        let err =
+            order_fields("Vocabulary", &field_names(), &values(&[("Word", "  ")])).unwrap_err();
+        assert!(err.to_string().contains("Missing required field(s)"));
+    }
+
+    #[test]
+    fn test_model_ref_deserialize() {
+        let by_id: ModelRef = serde_json::from_str("1607392319495").unwrap();
+        assert_eq!(by_id, ModelRef::Id(1607392319495));
+
+        let by_name: ModelRef = serde_json::from_str(r#""Cloze""#).unwrap();
+        assert_eq!(by_name, ModelRef::Name("Cloze".to_string()));
+        assert_eq!(by_name.to_string(), "'Cloze'");
+    }
+}
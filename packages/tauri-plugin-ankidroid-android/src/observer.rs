@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default time changes are collected before an event is emitted
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Part of the collection a change notification refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Notes,
+    Decks,
+    Models,
+}
+
+impl ChangeKind {
+    /// Every kind, in event order
+    pub const ALL: [ChangeKind; 3] = [ChangeKind::Notes, ChangeKind::Decks, ChangeKind::Models];
+
+    /// Path of the provider URI observed for this kind
+    pub fn path(self) -> &'static str {
+        match self {
+            ChangeKind::Notes => "notes",
+            ChangeKind::Decks => "decks",
+            ChangeKind::Models => "models",
+        }
+    }
+
+    /// Name of the Tauri event emitted for this kind
+    pub fn event_name(self) -> &'static str {
+        match self {
+            ChangeKind::Notes => "ankidroid://notes-changed",
+            ChangeKind::Decks => "ankidroid://decks-changed",
+            ChangeKind::Models => "ankidroid://models-changed",
+        }
+    }
+
+    /// Classify a changed provider URI by its first path segment
+    ///
+    /// Returns None for URIs outside the observed parts of the collection.
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        let path = uri.split_once("://").map_or(uri, |(_, rest)| rest);
+        let segment = path.split('/').nth(1)?;
+        let segment = segment.split(['?', '#']).next().unwrap_or_default();
+
+        match segment {
+            "notes" | "notes_v2" => Some(ChangeKind::Notes),
+            "decks" | "selected_deck" => Some(ChangeKind::Decks),
+            "models" => Some(ChangeKind::Models),
+            _ => None,
+        }
+    }
+}
+
+/// Payload of a change event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    pub kind: ChangeKind,
+    /// Changed URIs in the order they were first reported, without duplicates
+    pub uris: Vec<String>,
+}
+
+/// Changed URIs collected during one debounce window
+#[derive(Debug, Default)]
+pub struct ChangeBatch {
+    events: Vec<ChangeEvent>,
+}
+
+impl ChangeBatch {
+    /// Add a changed URI, returning false if it is not part of an observed kind
+    pub fn record(&mut self, uri: &str) -> bool {
+        let Some(kind) = ChangeKind::from_uri(uri) else {
+            return false;
+        };
+
+        match self.events.iter_mut().find(|event| event.kind == kind) {
+            Some(event) => {
+                if !event.uris.iter().any(|known| known == uri) {
+                    event.uris.push(uri.to_string());
+                }
+            }
+            None => self.events.push(ChangeEvent {
+                kind,
+                uris: vec![uri.to_string()],
+            }),
+        }
+        true
+    }
+
+    /// Check whether nothing was recorded
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Take one event per kind, ordered by kind
+    pub fn drain(&mut self) -> Vec<ChangeEvent> {
+        let mut events = std::mem::take(&mut self.events);
+        events.sort_by_key(|event| event.kind);
+        events
+    }
+}
+
+/// Background thread turning bursts of change notifications into single events
+///
+/// The first change starts a window of `delay`; everything reported until it
+/// ends is emitted together, one event per kind. Dropping the debouncer emits
+/// what is pending and stops the thread.
+pub struct Debouncer {
+    sender: Mutex<Sender<String>>,
+}
+
+impl Debouncer {
+    /// Start the thread, calling `emit` for each event
+    pub fn spawn<F>(delay: Duration, emit: F) -> Result<Self, String>
+    where
+        F: Fn(ChangeEvent) + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<String>();
+
+        thread::Builder::new()
+            .name("ankidroid-observer".to_string())
+            .spawn(move || {
+                let mut batch = ChangeBatch::default();
+                let mut deadline: Option<Instant> = None;
+
+                loop {
+                    let received = match deadline {
+                        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                        Some(deadline) => receiver
+                            .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                    };
+
+                    match received {
+                        Ok(uri) => {
+                            if batch.record(&uri) && deadline.is_none() {
+                                deadline = Some(Instant::now() + delay);
+                            }
+                        }
+                        Err(RecvTimeoutError::Timeout) => {
+                            batch.drain().into_iter().for_each(&emit);
+                            deadline = None;
+                        }
+                        Err(RecvTimeoutError::Disconnected) => {
+                            batch.drain().into_iter().for_each(&emit);
+                            break;
+                        }
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to spawn change observer thread: {}", e))?;
+
+        Ok(Self {
+            sender: Mutex::new(sender),
+        })
+    }
+
+    /// Report a changed URI
+    pub fn notify(&self, uri: &str) {
+        if let Ok(sender) = self.sender.lock() {
+            // The thread only stops once the debouncer is dropped
+            let _ = sender.send(uri.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTES: &str = "content://com.ichi2.anki.flashcards/notes";
+
+    #[test]
+    fn test_kind_from_uri() {
+        assert_eq!(ChangeKind::from_uri(NOTES), Some(ChangeKind::Notes));
+        assert_eq!(
+            ChangeKind::from_uri("content://com.ichi2.anki.flashcards/notes/42/cards/0"),
+            Some(ChangeKind::Notes)
+        );
+        assert_eq!(
+            ChangeKind::from_uri("content://com.ichi2.anki.flashcards/selected_deck"),
+            Some(ChangeKind::Decks)
+        );
+        assert_eq!(
+            ChangeKind::from_uri("content://com.ichi2.anki.flashcards/models?x=1"),
+            Some(ChangeKind::Models)
+        );
+        assert_eq!(
+            ChangeKind::from_uri("content://com.ichi2.anki.flashcards/schedule"),
+            None
+        );
+        assert_eq!(
+            ChangeKind::from_uri("content://com.ichi2.anki.flashcards"),
+            None
+        );
+        assert_eq!(ChangeKind::Decks.event_name(), "ankidroid://decks-changed");
+    }
+
+    #[test]
+    fn test_batch_groups_by_kind() {
+        let mut batch = ChangeBatch::default();
+        assert!(batch.record("content://com.ichi2.anki.flashcards/models/7"));
+        assert!(batch.record("content://com.ichi2.anki.flashcards/notes/1"));
+        assert!(batch.record("content://com.ichi2.anki.flashcards/notes/2"));
+        assert!(batch.record("content://com.ichi2.anki.flashcards/notes/1"));
+        assert!(!batch.record("content://com.ichi2.anki.flashcards/media"));
+
+        let events = batch.drain();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, ChangeKind::Notes);
+        assert_eq!(
+            events[0].uris,
+            vec![
+                "content://com.ichi2.anki.flashcards/notes/1",
+                "content://com.ichi2.anki.flashcards/notes/2"
+            ]
+        );
+        assert_eq!(events[1].kind, ChangeKind::Models);
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn test_debouncer_emits_one_event_per_burst() {
+        let (sender, receiver) = mpsc::channel();
+        let debouncer = Debouncer::spawn(Duration::from_millis(50), move |event| {
+            let _ = sender.send(event);
+        })
+        .unwrap();
+
+        debouncer.notify(NOTES);
+        debouncer.notify("content://com.ichi2.anki.flashcards/notes/1");
+        debouncer.notify(NOTES);
+
+        let event = receiver.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(event.kind, ChangeKind::Notes);
+        assert_eq!(event.uris.len(), 2);
+        assert!(receiver.recv_timeout(Duration::from_millis(150)).is_err());
+    }
+
+    #[test]
+    fn test_debouncer_flushes_on_drop() {
+        let (sender, receiver) = mpsc::channel();
+        let debouncer = Debouncer::spawn(Duration::from_secs(60), move |event| {
+            let _ = sender.send(event);
+        })
+        .unwrap();
+
+        debouncer.notify("content://com.ichi2.anki.flashcards/decks");
+        drop(debouncer);
+
+        let event = receiver.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(event.kind, ChangeKind::Decks);
+    }
+}
@@ -0,0 +1,336 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType as PngFilter, PngEncoder};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// Format images are re-encoded to
+///
+/// Only JPEG is offered. WebP images are still accepted as input, but WebP is
+/// not an output format: the pure Rust encoder is lossless only, which makes
+/// camera photos larger rather than smaller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Lossy, honours the quality setting
+    Jpeg,
+}
+
+/// How images are shrunk before they are added to AnkiDroid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImagePreprocessing {
+    /// Longest side in pixels; larger images are scaled down to fit
+    pub max_dimension: u32,
+    pub format: OutputFormat,
+    /// JPEG quality from 1 to 100
+    pub quality: u8,
+}
+
+impl Default for ImagePreprocessing {
+    fn default() -> Self {
+        Self {
+            max_dimension: 1920,
+            format: OutputFormat::Jpeg,
+            quality: 85,
+        }
+    }
+}
+
+/// Image after preprocessing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessedImage {
+    pub data: Vec<u8>,
+    pub mime_type: &'static str,
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Reasons an image could not be preprocessed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreprocessError {
+    Decode(String),
+    Encode(String),
+}
+
+impl fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreprocessError::Decode(message) => write!(f, "Failed to decode image: {}", message),
+            PreprocessError::Encode(message) => write!(f, "Failed to encode image: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PreprocessError {}
+
+/// Check whether media of this MIME type is preprocessed
+///
+/// Only still raster images are; GIFs would lose their animation and SVGs are
+/// already small. Audio and other media are added unchanged.
+pub fn is_preprocessed(mime_type: &str) -> bool {
+    matches!(mime_type, "image/jpeg" | "image/png" | "image/webp")
+}
+
+/// Downscale and re-encode an image, dropping its metadata
+///
+/// The EXIF orientation is applied to the pixels first, since the re-encoded
+/// image carries no EXIF to rotate it. Images with transparent pixels stay PNG
+/// when the target is JPEG, which has no alpha channel.
+pub fn preprocess_image(
+    data: &[u8],
+    options: &ImagePreprocessing,
+) -> Result<ProcessedImage, PreprocessError> {
+    let decode_error = |e: &dyn fmt::Display| PreprocessError::Decode(e.to_string());
+
+    let mut decoder = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| decode_error(&e))?
+        .into_decoder()
+        .map_err(|e| decode_error(&e))?;
+    let orientation = decoder.orientation().map_err(|e| decode_error(&e))?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| decode_error(&e))?;
+    image.apply_orientation(orientation);
+
+    let max_dimension = options.max_dimension.max(1);
+    if image.width() > max_dimension || image.height() > max_dimension {
+        image = image.resize(max_dimension, max_dimension, FilterType::Lanczos3);
+    }
+
+    let encode_error = |e: image::ImageError| PreprocessError::Encode(e.to_string());
+    let mut out = Vec::new();
+
+    let (mime_type, extension) = match options.format {
+        OutputFormat::Jpeg if has_transparency(&image) => {
+            let encoder =
+                PngEncoder::new_with_quality(&mut out, CompressionType::Best, PngFilter::Adaptive);
+            image
+                .to_rgba8()
+                .write_with_encoder(encoder)
+                .map_err(encode_error)?;
+            ("image/png", "png")
+        }
+        OutputFormat::Jpeg => {
+            let encoder = JpegEncoder::new_with_quality(&mut out, options.quality.clamp(1, 100));
+            image
+                .to_rgb8()
+                .write_with_encoder(encoder)
+                .map_err(encode_error)?;
+            ("image/jpeg", "jpg")
+        }
+    };
+
+    Ok(ProcessedImage {
+        data: out,
+        mime_type,
+        extension,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+fn has_transparency(image: &DynamicImage) -> bool {
+    image.color().has_alpha() && image.to_rgba8().pixels().any(|pixel| pixel[3] < u8::MAX)
+}
+
+/// Give a filename the extension of its re-encoded format
+pub fn with_extension(filename: &str, extension: &str) -> String {
+    let stem = Path::new(filename)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or("image");
+    format!("{}.{}", stem, extension)
+}
+
+/// Lock the preprocessing applied to images added by any command
+///
+/// None, the default, adds images unchanged.
+pub fn settings() -> MutexGuard<'static, Option<ImagePreprocessing>> {
+    static SETTINGS: OnceLock<Mutex<Option<ImagePreprocessing>>> = OnceLock::new();
+    SETTINGS
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        // A plain value, so a poisoned lock is still usable
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgb, RgbImage, Rgba, RgbaImage};
+
+    /// Camera-like photo: a gradient, encoded as JPEG
+    fn photo(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, height, |x, y| {
+            Rgb([(x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8])
+        });
+        let mut out = Vec::new();
+        image
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut out, 95))
+            .unwrap();
+        out
+    }
+
+    /// Gradient with sensor-like noise, as saved by a phone camera
+    fn noisy_photo(width: u32, height: u32) -> Vec<u8> {
+        let mut seed = 0x2545_f491_u32;
+        let image = RgbImage::from_fn(width, height, |x, y| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let noise = (seed >> 27) as u8;
+            Rgb([
+                ((x / 4) as u8).wrapping_add(noise),
+                ((y / 4) as u8).wrapping_add(noise),
+                (((x + y) / 8) as u8).wrapping_add(noise),
+            ])
+        });
+        let mut out = Vec::new();
+        image
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut out, 95))
+            .unwrap();
+        out
+    }
+
+    /// Insert an EXIF segment with the given orientation after the JPEG SOI marker
+    fn with_exif_orientation(jpeg: &[u8], orientation: u8) -> Vec<u8> {
+        let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01".to_vec();
+        exif.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1, 0, orientation, 0, 0]);
+        exif.extend_from_slice(&[0, 0, 0, 0]);
+
+        let length = (exif.len() + 2) as u16;
+        let mut out = jpeg[..2].to_vec();
+        out.extend_from_slice(&[0xFF, 0xE1]);
+        out.extend_from_slice(&length.to_be_bytes());
+        out.extend_from_slice(&exif);
+        out.extend_from_slice(&jpeg[2..]);
+        out
+    }
+
+    fn decode(data: &[u8]) -> (ImageFormat, DynamicImage) {
+        let format = image::guess_format(data).unwrap();
+        (format, image::load_from_memory(data).unwrap())
+    }
+
+    #[test]
+    fn test_downscales_to_max_dimension() {
+        let options = ImagePreprocessing {
+            max_dimension: 400,
+            ..Default::default()
+        };
+        let original = photo(1600, 1200);
+        let processed = preprocess_image(&original, &options).unwrap();
+
+        assert_eq!((processed.width, processed.height), (400, 300));
+        assert_eq!(processed.mime_type, "image/jpeg");
+        assert!(processed.data.len() < original.len());
+
+        let (format, image) = decode(&processed.data);
+        assert_eq!(format, ImageFormat::Jpeg);
+        assert_eq!((image.width(), image.height()), (400, 300));
+    }
+
+    #[test]
+    fn test_small_images_keep_their_size() {
+        let processed = preprocess_image(&photo(120, 80), &ImagePreprocessing::default()).unwrap();
+        assert_eq!((processed.width, processed.height), (120, 80));
+    }
+
+    #[test]
+    fn test_quality_changes_size() {
+        let original = photo(300, 300);
+        let size = |quality| {
+            let options = ImagePreprocessing {
+                quality,
+                ..Default::default()
+            };
+            preprocess_image(&original, &options).unwrap().data.len()
+        };
+        assert!(size(30) < size(95));
+    }
+
+    #[test]
+    fn test_exif_is_applied_and_stripped() {
+        // Orientation 6: the camera was turned, pixels must be rotated 90° clockwise
+        let original = with_exif_orientation(&photo(64, 32), 6);
+        assert!(original.windows(4).any(|window| window == b"Exif"));
+
+        let processed = preprocess_image(&original, &ImagePreprocessing::default()).unwrap();
+        assert_eq!((processed.width, processed.height), (32, 64));
+        assert!(!processed.data.windows(4).any(|window| window == b"Exif"));
+    }
+
+    #[test]
+    fn test_reencoding_shrinks_photos() {
+        // Already within the size limit, so only re-encoding can save space
+        let original = noisy_photo(800, 600);
+        let processed = preprocess_image(&original, &ImagePreprocessing::default()).unwrap();
+
+        assert_eq!((processed.width, processed.height), (800, 600));
+        assert!(
+            processed.data.len() < original.len(),
+            "{} bytes re-encoded to {}",
+            original.len(),
+            processed.data.len()
+        );
+
+        // WebP is not a format the frontend can ask for
+        assert!(serde_json::from_str::<ImagePreprocessing>(r#"{"format": "webp"}"#).is_err());
+    }
+
+    #[test]
+    fn test_transparency_is_kept() {
+        let image = RgbaImage::from_fn(50, 50, |x, _| {
+            Rgba([255, 0, 0, if x < 25 { 0 } else { 255 }])
+        });
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let processed = preprocess_image(&png, &ImagePreprocessing::default()).unwrap();
+        assert_eq!(processed.mime_type, "image/png");
+        let (_, decoded) = decode(&processed.data);
+        assert_eq!(decoded.to_rgba8().get_pixel(0, 0)[3], 0);
+
+        // Opaque images with an alpha channel can still become JPEG
+        let opaque = RgbaImage::from_pixel(50, 50, Rgba([0, 0, 255, 255]));
+        let mut png = Vec::new();
+        opaque
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let processed = preprocess_image(&png, &ImagePreprocessing::default()).unwrap();
+        assert_eq!(processed.mime_type, "image/jpeg");
+    }
+
+    #[test]
+    fn test_invalid_image() {
+        let err = preprocess_image(b"not an image", &ImagePreprocessing::default()).unwrap_err();
+        assert!(matches!(err, PreprocessError::Decode(_)));
+    }
+
+    #[test]
+    fn test_options_deserialize_with_defaults() {
+        let options: ImagePreprocessing =
+            serde_json::from_str(r#"{"max_dimension": 800}"#).unwrap();
+        assert_eq!(options.max_dimension, 800);
+        assert_eq!(options.format, OutputFormat::Jpeg);
+        assert_eq!(options.quality, 85);
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert!(is_preprocessed("image/png"));
+        assert!(!is_preprocessed("image/gif"));
+        assert!(!is_preprocessed("audio/mpeg"));
+        assert_eq!(
+            with_extension("IMG_0001.HEIC.png", "jpg"),
+            "IMG_0001.HEIC.jpg"
+        );
+        assert_eq!(with_extension("photo", "webp"), "photo.webp");
+    }
+}
@@ -0,0 +1,288 @@
+use crate::error::{PluginError, RetryAttempt};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, SystemTime};
+
+/// How provider calls that fail transiently are retried
+///
+/// AnkiDroid's provider can fail for a moment while the app is starting or
+/// checking its database. Failures marked `recoverable`, which are those with
+/// code `busy` or `network`, are retried with exponential backoff; anything
+/// else is returned at once.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total attempts including the first; 1 disables retrying
+    pub max_attempts: u32,
+    /// Delay before the first retry, in milliseconds
+    pub initial_delay_ms: u64,
+    /// Upper bound on any delay, in milliseconds
+    pub max_delay_ms: u64,
+    /// Factor the delay grows by after each retry
+    pub multiplier: f64,
+    /// Fraction of each delay that is randomized, from 0 to 1
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay_ms: 250,
+            max_delay_ms: 2000,
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Check that the policy can be applied
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_attempts == 0 {
+            return Err("max_attempts must be at least 1".to_string());
+        }
+        if self.multiplier.is_nan() || self.multiplier < 1.0 {
+            return Err(format!(
+                "multiplier must be at least 1, got {}",
+                self.multiplier
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err(format!(
+                "jitter must be between 0 and 1, got {}",
+                self.jitter
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the delay before a retry
+    ///
+    /// `retry` counts from 0 for the first retry. `sample` is a number in
+    /// `[0, 1)` that moves the delay up or down by at most `jitter` of it.
+    pub fn delay(&self, retry: u32, sample: f64) -> Duration {
+        let max = self.max_delay_ms as f64;
+        let base = (self.initial_delay_ms as f64 * self.multiplier.powi(retry as i32)).min(max);
+        let spread = 1.0 + self.jitter * (2.0 * sample - 1.0);
+        Duration::from_millis((base * spread).clamp(0.0, max) as u64)
+    }
+}
+
+/// Run an operation until it succeeds, fails for good or runs out of attempts
+///
+/// `sleep` waits out the delay between attempts. When the operation was tried
+/// more than once, the returned error lists every attempt in `attempts`.
+/// Only use this for operations that are safe to repeat, such as reads and
+/// updates keyed by ID; a retried insert could add the same note twice.
+pub async fn retry<T, F, Fut, S, SleepFut>(
+    policy: &RetryPolicy,
+    mut operation: F,
+    mut sleep: S,
+) -> Result<T, PluginError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, PluginError>>,
+    S: FnMut(Duration) -> SleepFut,
+    SleepFut: Future<Output = ()>,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut history = Vec::new();
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let mut err = match operation().await {
+            Ok(value) => {
+                if attempt > 1 {
+                    log::info!("Succeeded on attempt {} of {}", attempt, max_attempts);
+                }
+                return Ok(value);
+            }
+            Err(err) => err,
+        };
+
+        if !err.recoverable || attempt >= max_attempts {
+            if !history.is_empty() {
+                history.push(RetryAttempt::failed(attempt, &err, None));
+                err.attempts = history;
+            }
+            return Err(err);
+        }
+
+        let delay = policy.delay(attempt - 1, jitter_sample());
+        log::warn!(
+            "Attempt {} of {} failed, retrying in {}ms: {}",
+            attempt,
+            max_attempts,
+            delay.as_millis(),
+            err
+        );
+        history.push(RetryAttempt::failed(attempt, &err, Some(delay)));
+        sleep(delay).await;
+    }
+}
+
+/// Get a number in `[0, 1)` that differs between calls
+fn jitter_sample() -> f64 {
+    let hash = RandomState::new().hash_one(SystemTime::now());
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Lock the policy applied to retried provider calls
+pub fn settings() -> MutexGuard<'static, RetryPolicy> {
+    static SETTINGS: OnceLock<Mutex<RetryPolicy>> = OnceLock::new();
+    SETTINGS
+        .get_or_init(|| Mutex::new(RetryPolicy::default()))
+        .lock()
+        // A plain value, so a poisoned lock is still usable
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use std::cell::{Cell, RefCell};
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_delay_backoff_and_jitter() {
+        let policy = policy(5);
+        assert_eq!(policy.delay(0, 0.5), Duration::from_millis(250));
+        assert_eq!(policy.delay(1, 0.5), Duration::from_millis(500));
+        assert_eq!(policy.delay(3, 0.5), Duration::from_millis(2000));
+        assert_eq!(policy.delay(30, 0.5), Duration::from_millis(2000));
+
+        let jittered = RetryPolicy {
+            jitter: 0.2,
+            ..policy
+        };
+        assert_eq!(jittered.delay(0, 0.0), Duration::from_millis(200));
+        assert_eq!(jittered.delay(0, 0.5), Duration::from_millis(250));
+        // Jitter never pushes a delay past the cap
+        assert_eq!(jittered.delay(10, 0.99), Duration::from_millis(2000));
+
+        for _ in 0..100 {
+            let sample = jitter_sample();
+            assert!((0.0..1.0).contains(&sample));
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(RetryPolicy::default().validate().is_ok());
+        assert!(policy(0).validate().is_err());
+        assert!(RetryPolicy {
+            multiplier: 0.5,
+            ..policy(3)
+        }
+        .validate()
+        .is_err());
+        assert!(RetryPolicy {
+            jitter: 1.5,
+            ..policy(3)
+        }
+        .validate()
+        .is_err());
+        assert!(RetryPolicy {
+            jitter: f64::NAN,
+            ..policy(3)
+        }
+        .validate()
+        .is_err());
+
+        let parsed: RetryPolicy = serde_json::from_str(r#"{"max_attempts": 5}"#).unwrap();
+        assert_eq!(
+            parsed,
+            RetryPolicy {
+                max_attempts: 5,
+                ..RetryPolicy::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recovers_after_transient_failures() {
+        let calls = Cell::new(0);
+        let slept = RefCell::new(Vec::new());
+
+        let result = retry(
+            &policy(3),
+            || {
+                calls.set(calls.get() + 1);
+                let call = calls.get();
+                async move {
+                    match call {
+                        1 => Err(PluginError::new(ErrorCode::Busy, "database is locked")),
+                        _ => Ok(call),
+                    }
+                }
+            },
+            |delay| {
+                slept.borrow_mut().push(delay);
+                async {}
+            },
+        )
+        .await;
+
+        assert_eq!(result, Ok(2));
+        assert_eq!(*slept.borrow(), vec![Duration::from_millis(250)]);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_with_history() {
+        let calls = Cell::new(0);
+
+        let err = retry(
+            &policy(3),
+            || {
+                calls.set(calls.get() + 1);
+                async { Err::<(), _>(PluginError::new(ErrorCode::Busy, "database is locked")) }
+            },
+            |_| async {},
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(calls.get(), 3);
+        assert_eq!(err.code, ErrorCode::Busy);
+        let delays: Vec<_> = err
+            .attempts
+            .iter()
+            .map(|attempt| attempt.delay_ms)
+            .collect();
+        assert_eq!(delays, vec![Some(250), Some(500), None]);
+        assert_eq!(err.attempts[2].attempt, 3);
+        assert_eq!(err.attempts[0].message, "database is locked");
+    }
+
+    #[tokio::test]
+    async fn test_permanent_errors_not_retried() {
+        let calls = Cell::new(0);
+
+        let err = retry(
+            &policy(3),
+            || {
+                calls.set(calls.get() + 1);
+                async { Err::<(), _>(PluginError::not_found("Note 1 not found")) }
+            },
+            |_| async {},
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(calls.get(), 1);
+        assert!(err.attempts.is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, SystemTime};
+
+/// Limits a sweep of the staging directory enforces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SweepLimits {
+    /// Files older than this are removed
+    pub max_age: Duration,
+    /// Oldest files are removed until the directory is at most this large
+    pub max_bytes: u64,
+}
+
+impl Default for SweepLimits {
+    fn default() -> Self {
+        Self {
+            max_age: Duration::from_secs(60 * 60),
+            max_bytes: 50 * 1024 * 1024,
+        }
+    }
+}
+
+/// Outcome of a sweep
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepReport {
+    pub removed: usize,
+    pub freed_bytes: u64,
+    pub remaining_bytes: u64,
+}
+
+fn in_use() -> MutexGuard<'static, HashSet<PathBuf>> {
+    static IN_USE: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+    IN_USE
+        .get_or_init(|| Mutex::new(HashSet::new()))
+        .lock()
+        // A plain set of paths, so a poisoned lock is still usable
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// File written for another app to copy, deleted when dropped
+///
+/// While it exists the file is skipped by [`sweep`].
+#[derive(Debug)]
+pub struct StagedFile {
+    path: PathBuf,
+}
+
+impl StagedFile {
+    /// Track a file that has just been written
+    pub fn new(path: PathBuf) -> Self {
+        in_use().insert(path.clone());
+        Self { path }
+    }
+
+    /// Get the path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for StagedFile {
+    fn drop(&mut self) {
+        match fs::remove_file(&self.path) {
+            Ok(()) => log::debug!("Removed staged file {}", self.path.display()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!(
+                "Failed to remove staged file {}: {}",
+                self.path.display(),
+                e
+            ),
+        }
+        in_use().remove(&self.path);
+    }
+}
+
+/// Remove stale files from a staging directory
+///
+/// Files older than `max_age` go first; if the rest is still larger than
+/// `max_bytes`, the oldest are removed until it fits. Subdirectories and files
+/// tracked by a live [`StagedFile`] are left alone. A missing directory is
+/// treated as empty.
+pub fn sweep(dir: &Path, limits: &SweepLimits) -> io::Result<SweepReport> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(SweepReport::default()),
+        Err(e) => return Err(e),
+    };
+
+    let now = SystemTime::now();
+    let mut files = Vec::new();
+    {
+        let in_use = in_use();
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() || in_use.contains(&entry.path()) {
+                continue;
+            }
+            let modified = metadata.modified().unwrap_or(now);
+            files.push((entry.path(), modified, metadata.len()));
+        }
+    }
+
+    // Oldest first, so the size bound removes the least recent files
+    files.sort_by_key(|(_, modified, _)| *modified);
+
+    let mut report = SweepReport {
+        remaining_bytes: files.iter().map(|(_, _, size)| size).sum(),
+        ..Default::default()
+    };
+
+    for (path, modified, size) in files {
+        let age = now.duration_since(modified).unwrap_or_default();
+        if age <= limits.max_age && report.remaining_bytes <= limits.max_bytes {
+            continue;
+        }
+
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                report.removed += 1;
+                report.freed_bytes += size;
+                report.remaining_bytes -= size;
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => report.remaining_bytes -= size,
+            Err(e) => log::warn!("Failed to remove stale file {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("ankidroid-staging-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(dir: &Path, name: &str, size: usize, age: Duration) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, vec![0u8; size]).unwrap();
+        let file = File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+        path
+    }
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_staged_file_removed_on_drop() {
+        let dir = temp_dir("drop");
+        let path = write(&dir, "flag.png", 10, Duration::ZERO);
+
+        let staged = StagedFile::new(path.clone());
+        assert_eq!(staged.path(), path);
+        drop(staged);
+        assert!(!path.exists());
+
+        // Already gone is fine
+        drop(StagedFile::new(path));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sweep_removes_stale_files() {
+        let dir = temp_dir("age");
+        let old = write(&dir, "old.jpg", 100, 120 * MINUTE);
+        let fresh = write(&dir, "fresh.jpg", 100, MINUTE);
+        fs::create_dir(dir.join("nested")).unwrap();
+
+        let limits = SweepLimits {
+            max_age: 60 * MINUTE,
+            max_bytes: 1024,
+        };
+        let report = sweep(&dir, &limits).unwrap();
+
+        assert_eq!(
+            report,
+            SweepReport {
+                removed: 1,
+                freed_bytes: 100,
+                remaining_bytes: 100
+            }
+        );
+        assert!(!old.exists());
+        assert!(fresh.exists());
+        assert!(dir.join("nested").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sweep_bounds_size_oldest_first() {
+        let dir = temp_dir("size");
+        let oldest = write(&dir, "a.mp3", 400, 30 * MINUTE);
+        let middle = write(&dir, "b.mp3", 400, 20 * MINUTE);
+        let newest = write(&dir, "c.mp3", 400, 10 * MINUTE);
+
+        let limits = SweepLimits {
+            max_age: 60 * MINUTE,
+            max_bytes: 900,
+        };
+        let report = sweep(&dir, &limits).unwrap();
+
+        assert_eq!(report.removed, 1);
+        assert_eq!(report.remaining_bytes, 800);
+        assert!(!oldest.exists());
+        assert!(middle.exists() && newest.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sweep_skips_files_in_use() {
+        let dir = temp_dir("in-use");
+        let path = write(&dir, "staged.png", 100, 120 * MINUTE);
+        let staged = StagedFile::new(path.clone());
+
+        let report = sweep(&dir, &SweepLimits::default()).unwrap();
+        assert_eq!(report.removed, 0);
+        assert!(path.exists());
+
+        drop(staged);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sweep_missing_dir() {
+        let dir = std::env::temp_dir().join("ankidroid-staging-does-not-exist");
+        assert_eq!(
+            sweep(&dir, &SweepLimits::default()).unwrap(),
+            SweepReport::default()
+        );
+    }
+}
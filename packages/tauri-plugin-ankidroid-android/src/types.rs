@@ -1,3 +1,4 @@
+use crate::media::MediaKind;
 use serde::{Deserialize, Serialize};
 
 /// Represents a flashcard in AnkiDroid
@@ -32,6 +33,7 @@ impl Card {
     }
 
     /// Create a card with additional metadata
+    #[allow(clippy::too_many_arguments)]
     pub fn with_metadata(
         id: i64,
         front: String,
@@ -89,6 +91,72 @@ impl Card {
     }
 }
 
+/// A named field value of a note
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteField {
+    pub name: String,
+    pub value: String,
+}
+
+/// A note with its fields named after its note type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteView {
+    pub id: i64,
+    pub guid: String,
+    pub model_id: i64,
+    pub model_name: String,
+    /// Fields in the note type's order
+    pub fields: Vec<NoteField>,
+    pub tags: Vec<String>,
+    /// Modification time in seconds since the epoch
+    pub modified: i64,
+    /// Decks holding the note's cards, without duplicates; absent unless requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deck_ids: Option<Vec<i64>>,
+}
+
+impl NoteView {
+    /// Pair field values with the note type's field names
+    ///
+    /// Fields missing at the end of `values` are empty. Values beyond the known
+    /// names, e.g. when the note type could not be read, are named "Field N".
+    pub fn name_fields(field_names: &[String], values: Vec<String>) -> Vec<NoteField> {
+        let count = field_names.len().max(values.len());
+        let mut values = values.into_iter();
+
+        (0..count)
+            .map(|index| NoteField {
+                name: field_names
+                    .get(index)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Field {}", index + 1)),
+                value: values.next().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Get the value of a field by name
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.value.as_str())
+    }
+}
+
+/// Notes created, updated and deleted since a previous sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteChangesResponse {
+    pub created: Vec<NoteView>,
+    pub updated: Vec<NoteView>,
+    pub deleted: Vec<i64>,
+    /// Time the changes were read, in seconds; pass it as `since` next time
+    pub synced_at: i64,
+}
+
+/// A card as rendered by AnkiDroid from its note and card template
+pub use ankidroid_api_rust::Card as RenderedCard;
+
 /// Represents a deck in AnkiDroid
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Deck {
@@ -151,6 +219,27 @@ impl Deck {
     }
 }
 
+/// A deck in the nested deck tree
+///
+/// Counts are rolled up, so a parent's counts include all of its subdecks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckTreeNode {
+    /// None for a parent deck missing from AnkiDroid's deck list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    /// Last segment of the deck name
+    pub name: String,
+    /// Full `::`-separated deck name
+    pub full_name: String,
+    pub new_count: i32,
+    pub learning_count: i32,
+    pub review_count: i32,
+    pub children: Vec<DeckTreeNode>,
+}
+
+// Deck details are read through the core crate's types
+pub use ankidroid_api_rust::{DeckDetails, DeckOptions};
+
 /// Represents a note model in AnkiDroid
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Model {
@@ -161,6 +250,20 @@ pub struct Model {
     pub model_type: Option<i32>, // 0 = standard, 1 = cloze
     #[serde(skip_serializing_if = "Option::is_none")]
     pub css: Option<String>,
+    #[serde(default)]
+    pub field_names: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_cards: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_field_index: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deck_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latex_pre: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latex_post: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note_count: Option<i64>,
 }
 
 impl Model {
@@ -172,17 +275,21 @@ impl Model {
             field_count,
             model_type: None,
             css: None,
+            field_names: Vec::new(),
+            num_cards: None,
+            sort_field_index: None,
+            deck_id: None,
+            latex_pre: None,
+            latex_post: None,
+            note_count: None,
         }
     }
 
     /// Create a model with type information
     pub fn with_type(id: i64, name: String, field_count: i32, model_type: i32) -> Self {
         Self {
-            id,
-            name,
-            field_count,
             model_type: Some(model_type),
-            css: None,
+            ..Self::new(id, name, field_count)
         }
     }
 
@@ -202,6 +309,77 @@ impl Model {
     }
 }
 
+/// A card template of a note type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CardTemplate {
+    pub id: i64,
+    pub model_id: i64,
+    pub ord: i32,
+    pub name: String,
+    pub question_format: String,
+    pub answer_format: String,
+    pub browser_question_format: String,
+    pub browser_answer_format: String,
+    pub card_count: i32,
+}
+
+/// Declarative description of a note type owned by the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSpec {
+    pub name: String,
+    pub fields: Vec<String>,
+    pub templates: Vec<TemplateSpec>,
+    #[serde(default)]
+    pub css: Option<String>,
+    #[serde(default)]
+    pub sort_field: i32,
+    #[serde(default)]
+    pub cloze: bool,
+}
+
+/// Description of a card template within a ModelSpec
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateSpec {
+    pub name: String,
+    pub qfmt: String,
+    pub afmt: String,
+}
+
+/// A difference between a ModelSpec and the model stored in AnkiDroid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ModelDrift {
+    Fields {
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+    Css {
+        expected: String,
+        actual: String,
+    },
+    TemplateChanged {
+        ord: i32,
+        name: String,
+    },
+    TemplateMissing {
+        ord: i32,
+        name: String,
+    },
+    ExtraTemplate {
+        ord: i32,
+        name: String,
+    },
+}
+
+/// Response structure for model provisioning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsureModelResponse {
+    pub model_id: i64,
+    pub created: bool,
+    pub drift: Vec<ModelDrift>,
+    pub applied: bool,
+}
+
 /// Response structure for card creation operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateCardResponse {
@@ -360,6 +538,49 @@ impl OperationResponse {
     }
 }
 
+/// Media file added to AnkiDroid's collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddMediaResponse {
+    /// Name AnkiDroid stored the file under, which may differ from the one given
+    pub filename: String,
+    pub kind: MediaKind,
+    /// `<img>` tag or `[sound:...]` reference to put in a note field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub markup: Option<String>,
+    /// Whether identical media was already in the collection and nothing was added
+    #[serde(default)]
+    pub reused: bool,
+}
+
+impl AddMediaResponse {
+    /// Describe a stored file, deriving its field markup from the kind
+    pub fn new(filename: String, kind: MediaKind) -> Self {
+        Self {
+            markup: kind.field_markup(&filename),
+            filename,
+            kind,
+            reused: false,
+        }
+    }
+}
+
+/// A media file a note refers to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteMediaRef {
+    /// How the file is referenced: image, sound, audio, video or css_url
+    pub kind: String,
+    pub filename: String,
+}
+
+/// Media files a note refers to, and those not known to be in the collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteMediaResponse {
+    pub note_id: i64,
+    pub refs: Vec<NoteMediaRef>,
+    /// Referenced files neither passed as known nor added through this plugin
+    pub missing: Vec<NoteMediaRef>,
+}
+
 /// Configuration for the plugin
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginConfig {
@@ -398,6 +619,18 @@ fn default_max_field_length() -> usize {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_add_media_response() {
+        let response = AddMediaResponse::new("flag_1.png".to_string(), MediaKind::Image);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["kind"], "image");
+        assert_eq!(json["markup"], r#"<img src="flag_1.png">"#);
+
+        let response = AddMediaResponse::new("deck.pdf".to_string(), MediaKind::Other);
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json.get("markup").is_none());
+    }
+
     #[test]
     fn test_card_creation() {
         let card = Card::new(
@@ -466,6 +699,54 @@ mod tests {
         assert!(!deck_with_stats.is_empty());
     }
 
+    #[test]
+    fn test_note_view_name_fields() {
+        let names = vec![
+            "Word".to_string(),
+            "Reading".to_string(),
+            "Meaning".to_string(),
+        ];
+
+        // Trailing empty fields are not stored
+        let fields = NoteView::name_fields(&names, vec!["猫".to_string(), "ねこ".to_string()]);
+        assert_eq!(fields.len(), 3);
+        assert_eq!(
+            fields[2],
+            NoteField {
+                name: "Meaning".to_string(),
+                value: String::new()
+            }
+        );
+
+        let fields = NoteView::name_fields(&names[..1], vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(fields[1].name, "Field 2");
+        assert_eq!(fields[1].value, "b");
+    }
+
+    #[test]
+    fn test_note_view_serialization() {
+        let names = vec!["Front".to_string(), "Back".to_string(), "Extra".to_string()];
+        let note = NoteView {
+            id: 1,
+            guid: "abc".to_string(),
+            model_id: 2,
+            model_name: "Basic (with extra)".to_string(),
+            fields: NoteView::name_fields(
+                &names,
+                vec!["Q".to_string(), "A".to_string(), "More".to_string()],
+            ),
+            tags: vec!["geo".to_string()],
+            modified: 1700000000,
+            deck_ids: Some(vec![1]),
+        };
+        assert_eq!(note.field("Extra"), Some("More"));
+        assert_eq!(note.field("Missing"), None);
+
+        let json = serde_json::to_value(&note).unwrap();
+        assert_eq!(json["fields"][2]["name"], "Extra");
+        assert_eq!(json["tags"][0], "geo");
+    }
+
     #[test]
     fn test_model_types() {
         let basic_model = Model::new(1, "Basic".to_string(), 2);
@@ -507,6 +788,58 @@ mod tests {
         assert_eq!(card.back, deserialized.back);
     }
 
+    #[test]
+    fn test_rendered_card_serialization() {
+        let card = RenderedCard {
+            note_id: 42,
+            ord: 1,
+            name: "Card 2".to_string(),
+            deck_id: 1,
+            question: "<div>Back</div>".to_string(),
+            answer: "<div>Back</div><hr id=answer>Front".to_string(),
+            question_simple: "Back".to_string(),
+            answer_simple: "Front".to_string(),
+            answer_pure: "Front".to_string(),
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&card).unwrap();
+        assert_eq!(json["note_id"], 42);
+        assert_eq!(json["ord"], 1);
+        assert_eq!(json["name"], "Card 2");
+        assert_eq!(json["answer_pure"], "Front");
+    }
+
+    #[test]
+    fn test_model_spec_deserialize() {
+        let json = r#"{
+            "name": "Vocab",
+            "fields": ["Word", "Meaning"],
+            "templates": [{"name": "Card 1", "qfmt": "{{Word}}", "afmt": "{{Meaning}}"}],
+            "css": ".card { color: black; }"
+        }"#;
+        let spec: ModelSpec = serde_json::from_str(json).unwrap();
+        assert_eq!(spec.fields, vec!["Word", "Meaning"]);
+        assert_eq!(spec.templates[0].qfmt, "{{Word}}");
+        assert_eq!(spec.sort_field, 0);
+        assert!(!spec.cloze);
+    }
+
+    #[test]
+    fn test_ensure_model_response_serialization() {
+        let response = EnsureModelResponse {
+            model_id: 7,
+            created: false,
+            drift: vec![ModelDrift::TemplateChanged {
+                ord: 0,
+                name: "Card 1".to_string(),
+            }],
+            applied: true,
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["drift"][0]["kind"], "template_changed");
+        assert_eq!(json["applied"], true);
+    }
+
     #[test]
     fn test_plugin_config() {
         let config = PluginConfig::default();
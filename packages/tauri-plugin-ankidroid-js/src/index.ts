@@ -1,4 +1,13 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type ChangeKind = 'notes' | 'decks' | 'models';
+
+export interface ChangeEvent {
+  kind: ChangeKind;
+  /** Changed content provider URIs, without duplicates */
+  uris: string[];
+}
 
 export interface HelloResponse {
   value: string;
@@ -21,6 +30,95 @@ export interface CreateCardResponse {
 export interface Deck {
   id: number;
   name: string;
+  description?: string;
+  card_count?: number;
+  new_count?: number;
+  learning_count?: number;
+  review_count?: number;
+}
+
+export interface NoteField {
+  name: string;
+  value: string;
+}
+
+/**
+ * A note with its fields named after its note type, in the note type's order
+ */
+export interface NoteView {
+  id: number;
+  guid: string;
+  model_id: number;
+  model_name: string;
+  fields: NoteField[];
+  tags: string[];
+  modified: number;
+  /** Decks holding the note's cards; only present when requested */
+  deck_ids?: number[];
+}
+
+/**
+ * Notes created, updated and deleted since a previous sync
+ */
+export interface NoteChanges {
+  created: NoteView[];
+  updated: NoteView[];
+  deleted: number[];
+  /** Pass as `since` on the next call */
+  synced_at: number;
+}
+
+/**
+ * A deck in the nested deck tree. Counts include all subdecks.
+ */
+export interface DeckTreeNode {
+  id?: number;
+  name: string;
+  full_name: string;
+  new_count: number;
+  learning_count: number;
+  review_count: number;
+  children: DeckTreeNode[];
+}
+
+/**
+ * How a deck name is matched against existing decks. An exact match always
+ * wins; names matching several decks are rejected with the candidates listed.
+ */
+export type DeckMatch = 'exact' | 'case_insensitive' | 'fuzzy';
+
+/**
+ * Scheduling options of a deck's options group. Steps are in minutes,
+ * intervals in days and the starting ease in permille (2500 = 250%).
+ * Read only: AnkiDroid's provider rejects deck updates.
+ */
+export interface DeckOptions {
+  id: number;
+  name: string;
+  new_per_day: number;
+  reviews_per_day: number;
+  learning_steps: number[];
+  graduating_interval: number;
+  easy_interval: number;
+  starting_ease: number;
+  maximum_interval: number;
+  relearning_steps: number[];
+  leech_threshold: number;
+  max_answer_seconds: number;
+}
+
+export interface DeckDetails {
+  id: number;
+  name: string;
+  description: string;
+  filtered: boolean;
+  options?: DeckOptions;
+}
+
+export interface EnsuredDeck {
+  deck_id: number;
+  name: string;
+  created: number[];
 }
 
 export interface Card {
@@ -32,6 +130,199 @@ export interface Card {
   deckId?: number;
 }
 
+export interface Model {
+  id: number;
+  name: string;
+  field_count: number;
+  model_type?: number;
+  css?: string;
+  field_names: string[];
+  num_cards?: number;
+  sort_field_index?: number;
+  deck_id?: number;
+  latex_pre?: string;
+  latex_post?: string;
+  note_count?: number;
+}
+
+export interface RenderedCard {
+  note_id: number;
+  ord: number;
+  name: string;
+  deck_id: number;
+  question: string;
+  answer: string;
+  question_simple: string;
+  answer_simple: string;
+  answer_pure: string;
+}
+
+export interface CardTemplate {
+  id: number;
+  model_id: number;
+  ord: number;
+  name: string;
+  question_format: string;
+  answer_format: string;
+  browser_question_format: string;
+  browser_answer_format: string;
+  card_count: number;
+}
+
+export interface TemplateFormats {
+  questionFormat?: string;
+  answerFormat?: string;
+  browserQuestionFormat?: string;
+  browserAnswerFormat?: string;
+}
+
+export interface TemplateSpec {
+  name: string;
+  qfmt: string;
+  afmt: string;
+}
+
+export interface ModelSpec {
+  name: string;
+  fields: string[];
+  templates: TemplateSpec[];
+  css?: string;
+  sort_field?: number;
+  cloze?: boolean;
+}
+
+export type ModelDrift =
+  | { kind: 'fields'; expected: string[]; actual: string[] }
+  | { kind: 'css'; expected: string; actual: string }
+  | { kind: 'template_changed'; ord: number; name: string }
+  | { kind: 'template_missing'; ord: number; name: string }
+  | { kind: 'extra_template'; ord: number; name: string };
+
+export interface EnsureModelResponse {
+  model_id: number;
+  created: boolean;
+  drift: ModelDrift[];
+  applied: boolean;
+}
+
+export type MediaKind = 'image' | 'audio' | 'video' | 'other';
+
+export interface ImagePreprocessing {
+  /** Longest side in pixels; larger images are scaled down (default: 1920) */
+  max_dimension?: number;
+  /** Format images are re-encoded to (default: 'jpeg'; WebP output is not supported) */
+  format?: 'jpeg';
+  /** JPEG quality from 1 to 100 (default: 85) */
+  quality?: number;
+}
+
+export interface AddMediaResponse {
+  /** Name AnkiDroid stored the file under, which may differ from the one given */
+  filename: string;
+  kind: MediaKind;
+  /** `<img>` tag or `[sound:...]` reference to put in a note field */
+  markup?: string;
+  /** Whether identical media was already in the collection and nothing was added */
+  reused: boolean;
+}
+
+export type MediaRefKind = 'image' | 'sound' | 'audio' | 'video' | 'css_url';
+
+export interface NoteMediaRef {
+  kind: MediaRefKind;
+  filename: string;
+}
+
+export interface NoteMediaResponse {
+  note_id: number;
+  refs: NoteMediaRef[];
+  /** Referenced files neither passed as known nor added through this plugin */
+  missing: NoteMediaRef[];
+}
+
+/** Stable identifier for the kind of failure */
+export type ErrorCode =
+  | 'availability'
+  | 'not_installed'
+  | 'permission'
+  | 'validation'
+  | 'duplicate'
+  | 'not_found'
+  | 'ambiguous'
+  | 'jni'
+  | 'java_exception'
+  | 'content_provider'
+  | 'conversion'
+  | 'database'
+  | 'io'
+  | 'serialization'
+  | 'busy'
+  | 'media'
+  | 'network'
+  | 'sync'
+  | 'internal';
+
+export type ErrorDetails =
+  | { type: 'field_count'; expected: number; actual: number }
+  | { type: 'unknown_fields'; model: string; fields: string[]; expected: string[] }
+  | { type: 'missing_fields'; model: string; fields: string[] }
+  | { type: 'invalid_id'; resource: string; id: number }
+  | { type: 'ambiguous'; query: string; candidates: string[] }
+  | { type: 'http_status'; status: number }
+  | { type: 'limit_exceeded'; limit: number }
+  | { type: 'java_exception'; class_name: string; stack_trace: string[] };
+
+/** One failed try of a retried command */
+export interface RetryAttempt {
+  /** Attempt number, starting at 1 */
+  attempt: number;
+  code: ErrorCode;
+  message: string;
+  /** Wait before the next attempt in milliseconds, absent for the last one */
+  delay_ms?: number;
+}
+
+/** How reads and updates by ID are retried when AnkiDroid fails transiently */
+export interface RetryPolicy {
+  /** Total attempts including the first; 1 disables retrying (default: 3) */
+  max_attempts?: number;
+  /** Delay before the first retry in milliseconds (default: 250) */
+  initial_delay_ms?: number;
+  /** Upper bound on any delay in milliseconds (default: 2000) */
+  max_delay_ms?: number;
+  /** Factor the delay grows by after each retry (default: 2) */
+  multiplier?: number;
+  /** Fraction of each delay that is randomized, from 0 to 1 (default: 0.2) */
+  jitter?: number;
+}
+
+/** Error every command rejects with */
+export interface PluginError {
+  code: ErrorCode;
+  /** Human-readable description, not meant to be matched on */
+  message: string;
+  /** Whether the same call may succeed if retried */
+  recoverable: boolean;
+  details?: ErrorDetails;
+  /** Every try, when the command was retried before failing */
+  attempts?: RetryAttempt[];
+}
+
+export interface OperationResponse {
+  success: boolean;
+  message?: string;
+  error?: string;
+  data?: unknown;
+}
+
+/** Whether a permission is granted, or how asking for it would go */
+export type PermissionState = 'granted' | 'denied' | 'prompt' | 'prompt-with-rationale';
+
+export interface PermissionStatus {
+  /** The AnkiDroid database permission */
+  ankidroid: PermissionState;
+}
+
 /**
  * Sends a hello message to the AnkiDroid plugin
  * @param name The name to greet
@@ -42,14 +333,84 @@ export async function hello(name: string): Promise<string> {
   return response.value;
 }
 
+/**
+ * Checks whether the AnkiDroid database permission has been granted
+ *
+ * Until it is, every command that talks to AnkiDroid rejects with a
+ * `PluginError` of code `permission`, from `createNote` to `getDecks` and
+ * `listCards`. Only `hello` reports the failure in its greeting instead.
+ * @returns State of the permission
+ */
+export async function checkPermissions(): Promise<PermissionStatus> {
+  const response = await invoke<string>('plugin:ankidroid|check_permissions');
+  return JSON.parse(response) as PermissionStatus;
+}
+
+/**
+ * Asks the user for the AnkiDroid database permission
+ * @returns State of the permission once the user has answered
+ */
+export async function requestPermissions(): Promise<PermissionStatus> {
+  const response = await invoke<string>('plugin:ankidroid|request_permissions');
+  return JSON.parse(response) as PermissionStatus;
+}
+
 /**
  * Gets a list of cards from AnkiDroid
  * @returns A JSON string containing card data
+ * @throws {PluginError} When AnkiDroid cannot be read
  */
 export async function listCards(): Promise<string> {
   return await invoke<string>('plugin:ankidroid|list_cards');
 }
 
+/**
+ * Gets notes from AnkiDroid with all of their fields
+ * @param options.offset Number of notes to skip (default: 0)
+ * @param options.limit Maximum number of notes to return (default: all)
+ * @param options.includeDecks Also read each note's decks; costs one AnkiDroid
+ * query per note, so combine it with `limit` on large collections
+ * @returns Array of notes with named fields
+ */
+export async function listNotes(options?: {
+  offset?: number;
+  limit?: number;
+  includeDecks?: boolean;
+}): Promise<NoteView[]> {
+  const response = await invoke<string>('plugin:ankidroid|list_notes', { ...options });
+  return JSON.parse(response) as NoteView[];
+}
+
+/**
+ * Gets a single note with all of its fields and decks
+ * @param noteId The ID of the note
+ * @returns The note with named fields
+ */
+export async function getNote(noteId: number): Promise<NoteView> {
+  const response = await invoke<string>('plugin:ankidroid|get_note', { noteId });
+  return JSON.parse(response) as NoteView;
+}
+
+/**
+ * Gets the notes changed since a previous sync, for incremental mirroring
+ * @param since `synced_at` of the previous call, or 0 for a full read
+ * @param knownIds IDs of the notes already mirrored, used to detect deletions
+ * @param includeDecks Also read the decks of each changed note (one query per note)
+ * @returns Created and updated notes and the IDs of deleted notes
+ */
+export async function getNoteChanges(
+  since: number,
+  knownIds?: number[],
+  includeDecks?: boolean
+): Promise<NoteChanges> {
+  const response = await invoke<string>('plugin:ankidroid|get_note_changes', {
+    since,
+    knownIds,
+    includeDecks,
+  });
+  return JSON.parse(response) as NoteChanges;
+}
+
 /**
  * Creates a new card in AnkiDroid
  * @param front The front side (question) of the card
@@ -57,6 +418,7 @@ export async function listCards(): Promise<string> {
  * @param deck Optional deck name (defaults to "Default")
  * @param tags Optional tags for the card
  * @returns Response with success status and note ID
+ * @throws {PluginError} When the note cannot be added
  */
 export async function createCard(
   front: string,
@@ -73,15 +435,184 @@ export async function createCard(
   return JSON.parse(response) as CreateCardResponse;
 }
 
+/**
+ * Creates a new note in AnkiDroid
+ * @param front The front side (question) of the note
+ * @param back The back side (answer) of the note
+ * @param deck Optional deck name (defaults to "Default"), created if nothing matches
+ * @param tags Optional tags for the note
+ * @param deckMatch How the deck name is matched (defaults to 'exact')
+ * @returns Response with success status and note ID
+ * @throws {PluginError} When the note cannot be added
+ */
+export async function createNote(
+  front: string,
+  back: string,
+  deck?: string,
+  tags?: string,
+  deckMatch?: DeckMatch
+): Promise<CreateCardResponse> {
+  const response = await invoke<string>('plugin:ankidroid|create_note', {
+    front,
+    back,
+    deck,
+    tags,
+    deckMatch,
+  });
+  return JSON.parse(response) as CreateCardResponse;
+}
+
+/**
+ * Creates a note of any note type from values keyed by field name
+ * @param model The note type, by ID or by name
+ * @param fields Field values keyed by field name; the first field is required, others default to empty
+ * @param deck Optional deck name (defaults to "Default"), created if nothing matches
+ * @param tags Optional tags for the note
+ * @param deckMatch How the deck name is matched (defaults to 'exact')
+ * @returns Response with success status and note ID
+ * @throws {PluginError} With code 'validation', 'not_found' or 'ambiguous' when the note is rejected
+ */
+export async function createNoteWithFields(
+  model: number | string,
+  fields: Record<string, string>,
+  deck?: string,
+  tags?: string[],
+  deckMatch?: DeckMatch
+): Promise<CreateCardResponse> {
+  const response = await invoke<string>('plugin:ankidroid|create_note_with_fields', {
+    model,
+    fields,
+    deck,
+    tags,
+    deckMatch,
+  });
+  return JSON.parse(response) as CreateCardResponse;
+}
+
 /**
  * Gets a list of available decks from AnkiDroid
  * @returns Array of deck objects with id and name
+ * @throws {PluginError} When AnkiDroid cannot be read
  */
 export async function getDecks(): Promise<Deck[]> {
   const response = await invoke<string>('plugin:ankidroid|get_decks');
   return JSON.parse(response) as Deck[];
 }
 
+/**
+ * Creates a deck, including any missing parent decks. Whitespace around "::"
+ * is normalized, so "Lang :: Japanese:: N5" creates "Lang::Japanese::N5".
+ * Existing decks along the path are reused whatever their case, since
+ * AnkiDroid deck names are unique ignoring case.
+ * @param name The full "::"-separated deck name
+ * @param deckMatch How existing decks are matched (defaults to 'exact')
+ * @returns The deck ID, its stored name and the IDs of the decks created
+ */
+export async function createDeck(name: string, deckMatch?: DeckMatch): Promise<EnsuredDeck> {
+  const response = await invoke<string>('plugin:ankidroid|create_deck', {
+    name,
+    deckMatch,
+  });
+  return JSON.parse(response) as EnsuredDeck;
+}
+
+/**
+ * Gets a deck's description, filtered flag and options
+ * @param deckId The ID of the deck
+ * @returns The deck details; filtered decks have no options
+ */
+export async function getDeckDetails(deckId: number): Promise<DeckDetails> {
+  const response = await invoke<string>('plugin:ankidroid|get_deck_details', { deckId });
+  return JSON.parse(response) as DeckDetails;
+}
+
+/**
+ * Gets the deck currently selected in AnkiDroid
+ * @returns The selected deck with its counts, or null if none is selected
+ */
+export async function getSelectedDeck(): Promise<Deck | null> {
+  const response = await invoke<string>('plugin:ankidroid|get_selected_deck');
+  return JSON.parse(response) as Deck | null;
+}
+
+/**
+ * Selects a deck in AnkiDroid. AnkiDroid opens on the selected deck.
+ * @param deckId The ID of the deck to select
+ * @returns Operation result
+ */
+export async function setSelectedDeck(deckId: number): Promise<OperationResponse> {
+  const response = await invoke<string>('plugin:ankidroid|set_selected_deck', { deckId });
+  return JSON.parse(response) as OperationResponse;
+}
+
+/**
+ * Gets the note type AnkiDroid currently uses for new notes
+ * @returns The current model, or null if AnkiDroid reports none
+ */
+export async function getCurrentModel(): Promise<Model | null> {
+  const response = await invoke<string>('plugin:ankidroid|get_current_model');
+  return JSON.parse(response) as Model | null;
+}
+
+/**
+ * Opens AnkiDroid's reviewer on a deck, or AnkiDroid's deck list without one
+ * @param deckId Optional deck to review
+ * @returns Operation result
+ */
+export async function launchAnkiDroid(deckId?: number): Promise<OperationResponse> {
+  const response = await invoke<string>('plugin:ankidroid|launch_ankidroid', { deckId });
+  return JSON.parse(response) as OperationResponse;
+}
+
+/**
+ * Starts observing the collection; changes are emitted as
+ * `ankidroid://notes-changed`, `ankidroid://decks-changed` and
+ * `ankidroid://models-changed` events. Only changes AnkiDroid reports to
+ * content observers are seen.
+ * @param kinds Parts of the collection to observe (default: all)
+ * @param debounceMs Time changes are collected into one event (default: 500)
+ * @returns Operation result
+ */
+export async function subscribeChanges(
+  kinds?: ChangeKind[],
+  debounceMs?: number
+): Promise<OperationResponse> {
+  const response = await invoke<string>('plugin:ankidroid|subscribe_changes', { kinds, debounceMs });
+  return JSON.parse(response) as OperationResponse;
+}
+
+/**
+ * Stops observing the collection
+ * @returns Operation result
+ */
+export async function unsubscribeChanges(): Promise<OperationResponse> {
+  const response = await invoke<string>('plugin:ankidroid|unsubscribe_changes');
+  return JSON.parse(response) as OperationResponse;
+}
+
+/**
+ * Listens for change events of one kind; call subscribeChanges to start them
+ * @param kind Part of the collection to listen for
+ * @param handler Called with each debounced change event
+ * @returns Function that removes the listener
+ */
+export async function onCollectionChange(
+  kind: ChangeKind,
+  handler: (event: ChangeEvent) => void
+): Promise<UnlistenFn> {
+  return listen<ChangeEvent>(`ankidroid://${kind}-changed`, (event) => handler(event.payload));
+}
+
+/**
+ * Gets all decks nested by their "::"-separated names, e.g. for a collapsible
+ * deck picker
+ * @returns Top-level decks, each with its subdecks and rolled-up counts
+ */
+export async function getDeckTree(): Promise<DeckTreeNode[]> {
+  const response = await invoke<string>('plugin:ankidroid|get_deck_tree');
+  return JSON.parse(response) as DeckTreeNode[];
+}
+
 /**
  * Updates an existing card in AnkiDroid
  * @param noteId The ID of the note/card to update
@@ -90,6 +621,7 @@ export async function getDecks(): Promise<Deck[]> {
  * @param deck Optional new deck name
  * @param tags Optional new tags for the card
  * @returns Response with success status
+ * @throws {PluginError} When the note cannot be updated
  */
 export async function updateCard(
   noteId: number,
@@ -112,6 +644,7 @@ export async function updateCard(
  * Deletes a card from AnkiDroid
  * @param noteId The ID of the note/card to delete
  * @returns Response with success status
+ * @throws {PluginError} With code 'not_found' when there is no such note
  */
 export async function deleteCard(noteId: number): Promise<CreateCardResponse> {
   const response = await invoke<string>('plugin:ankidroid|delete_card', {
@@ -120,6 +653,275 @@ export async function deleteCard(noteId: number): Promise<CreateCardResponse> {
   return JSON.parse(response) as CreateCardResponse;
 }
 
+/**
+ * Gets the cards generated from a note, rendered by AnkiDroid
+ * @param noteId The ID of the note
+ * @returns Array of rendered cards ordered by template ordinal
+ */
+export async function getCardsForNote(noteId: number): Promise<RenderedCard[]> {
+  const response = await invoke<string>('plugin:ankidroid|get_cards_for_note', {
+    noteId,
+  });
+  return JSON.parse(response) as RenderedCard[];
+}
+
+/**
+ * Gets a single card of a note, rendered by AnkiDroid
+ * @param noteId The ID of the note
+ * @param ord The ordinal of the card template
+ * @returns The rendered card, or null if the note has no card with that ordinal
+ */
+export async function getCard(noteId: number, ord: number): Promise<RenderedCard | null> {
+  const response = await invoke<string>('plugin:ankidroid|get_card', {
+    noteId,
+    ord,
+  });
+  return JSON.parse(response) as RenderedCard | null;
+}
+
+/**
+ * Lists the card templates of a note type
+ * @param modelId The ID of the note type
+ * @returns Array of templates ordered by ordinal
+ */
+export async function listTemplates(modelId: number): Promise<CardTemplate[]> {
+  const response = await invoke<string>('plugin:ankidroid|list_templates', {
+    modelId,
+  });
+  return JSON.parse(response) as CardTemplate[];
+}
+
+/**
+ * Gets a single card template of a note type
+ * @param modelId The ID of the note type
+ * @param ord The ordinal of the template
+ * @returns The template, or null if the note type has no template with that ordinal
+ */
+export async function getTemplate(modelId: number, ord: number): Promise<CardTemplate | null> {
+  const response = await invoke<string>('plugin:ankidroid|get_template', {
+    modelId,
+    ord,
+  });
+  return JSON.parse(response) as CardTemplate | null;
+}
+
+/**
+ * Updates the formats of a card template; omitted formats are left unchanged
+ * @param modelId The ID of the note type
+ * @param ord The ordinal of the template
+ * @param formats The formats to change
+ * @returns Response with success status
+ */
+export async function updateTemplate(
+  modelId: number,
+  ord: number,
+  formats: TemplateFormats
+): Promise<OperationResponse> {
+  const response = await invoke<string>('plugin:ankidroid|update_template', {
+    modelId,
+    ord,
+    ...formats,
+  });
+  return JSON.parse(response) as OperationResponse;
+}
+
+/**
+ * Adds a card template to a note type
+ * @param modelId The ID of the note type
+ * @param name The name of the new template
+ * @param questionFormat The question (front) format
+ * @param answerFormat The answer (back) format
+ * @returns The created template
+ */
+export async function addTemplate(
+  modelId: number,
+  name: string,
+  questionFormat: string,
+  answerFormat: string
+): Promise<CardTemplate> {
+  const response = await invoke<string>('plugin:ankidroid|add_template', {
+    modelId,
+    name,
+    questionFormat,
+    answerFormat,
+  });
+  return JSON.parse(response) as CardTemplate;
+}
+
+/**
+ * Gets a note type with its field names, CSS, sort field and note count
+ * @param modelId The ID of the note type
+ * @returns The model
+ */
+export async function getModel(modelId: number): Promise<Model> {
+  const response = await invoke<string>('plugin:ankidroid|get_model', { modelId });
+  return JSON.parse(response) as Model;
+}
+
+/**
+ * Makes sure a note type matching the spec exists, creating it if missing
+ * @param spec The declarative description of the note type
+ * @param apply Whether template/CSS drift on an existing note type is applied (defaults to report only)
+ * @returns The model ID, whether it was created, and any drift found
+ */
+export async function ensureModel(spec: ModelSpec, apply?: boolean): Promise<EnsureModelResponse> {
+  const response = await invoke<string>('plugin:ankidroid|ensure_model', {
+    spec,
+    apply,
+  });
+  return JSON.parse(response) as EnsureModelResponse;
+}
+
+/**
+ * Adds a media file to AnkiDroid's collection
+ * @param data Raw file contents
+ * @param filename Preferred file name; AnkiDroid may rename it
+ * @param mimeType MIME type (default: derived from the file extension)
+ * @returns Stored file name with markup ready to insert into a note field
+ */
+export async function addMedia(
+  data: Uint8Array | number[],
+  filename: string,
+  mimeType?: string
+): Promise<AddMediaResponse> {
+  const response = await invoke<string>('plugin:ankidroid|add_media', {
+    data: Array.from(data),
+    filename,
+    mimeType,
+  });
+  return JSON.parse(response) as AddMediaResponse;
+}
+
+/**
+ * Adds a base64-encoded media file to AnkiDroid's collection
+ * @param data Base64-encoded file contents, without a `data:` prefix
+ * @param filename Preferred file name; AnkiDroid may rename it
+ * @param mimeType MIME type (default: derived from the file extension)
+ * @returns Stored file name with markup ready to insert into a note field
+ */
+export async function addMediaFromBase64(
+  data: string,
+  filename: string,
+  mimeType?: string
+): Promise<AddMediaResponse> {
+  const response = await invoke<string>('plugin:ankidroid|add_media_from_base64', {
+    data,
+    filename,
+    mimeType,
+  });
+  return JSON.parse(response) as AddMediaResponse;
+}
+
+/**
+ * Downloads a media file and adds it to AnkiDroid's collection, e.g.
+ * pronunciation audio from a dictionary. Downloads are limited to 20 MB,
+ * 30 seconds and 5 redirects.
+ * @param url http(s) URL to download
+ * @param filename Preferred file name (default: the last segment of the URL);
+ *   an extension is added from the response type if missing
+ * @returns Stored file name with markup ready to insert into a note field
+ */
+export async function addMediaFromUrl(url: string, filename?: string): Promise<AddMediaResponse> {
+  const response = await invoke<string>('plugin:ankidroid|add_media_from_url', { url, filename });
+  return JSON.parse(response) as AddMediaResponse;
+}
+
+/**
+ * Sets how JPEG, PNG and WebP images are shrunk before they are added by any
+ * media command. Images are downscaled, re-encoded and stripped of EXIF
+ * metadata; images with transparency stay PNG. Images are only re-encoded to
+ * JPEG, WebP input included. Changing the options makes images added before
+ * the change get added again rather than reused. Other media is added unchanged.
+ * @param options Preprocessing to apply, or null to add images unchanged
+ * @returns Operation result
+ */
+export async function setImagePreprocessing(
+  options: ImagePreprocessing | null
+): Promise<OperationResponse> {
+  const response = await invoke<string>('plugin:ankidroid|set_image_preprocessing', {
+    options: options ?? undefined,
+  });
+  return JSON.parse(response) as OperationResponse;
+}
+
+/**
+ * Forgets which media AnkiDroid already has. Identical files are normally
+ * reused instead of added again; call this after deleting unused media in
+ * AnkiDroid so they are added again.
+ * @returns Operation result
+ */
+export async function clearMediaIndex(): Promise<OperationResponse> {
+  const response = await invoke<string>('plugin:ankidroid|clear_media_index');
+  return JSON.parse(response) as OperationResponse;
+}
+
+/**
+ * Lists the media files a note refers to through `<img>`, `[sound:...]`,
+ * `<audio>`/`<video>` and CSS `url()`, e.g. to bundle them with an export.
+ * AnkiDroid's media folder cannot be listed, so a file counts as present only
+ * if it is in `knownFiles` or was added through this plugin.
+ * @param noteId ID of the note
+ * @param knownFiles Names of files known to be in the collection
+ * @returns All references and those whose file may be missing
+ */
+export async function getNoteMedia(
+  noteId: number,
+  knownFiles?: string[]
+): Promise<NoteMediaResponse> {
+  const response = await invoke<string>('plugin:ankidroid|get_note_media', { noteId, knownFiles });
+  return JSON.parse(response) as NoteMediaResponse;
+}
+
+/**
+ * Clears the plugin's cached model and deck IDs, e.g. after decks were renamed in AnkiDroid.
+ * While subscribed with `subscribeChanges`, deck and model changes clear them automatically.
+ * @param kind Which IDs to forget ("models" or "decks"); omit to clear both
+ * @returns Response with success status
+ */
+export async function invalidateCache(kind?: 'models' | 'decks'): Promise<OperationResponse> {
+  const response = await invoke<string>('plugin:ankidroid|invalidate_cache', { kind });
+  return JSON.parse(response) as OperationResponse;
+}
+
+/**
+ * Sets how long cached model and deck IDs are used before they are looked up again
+ * @param ttlSecs Time in seconds, or null to restore the default (300)
+ * @returns Operation result
+ */
+export async function setCacheTtl(ttlSecs: number | null): Promise<OperationResponse> {
+  const response = await invoke<string>('plugin:ankidroid|set_cache_ttl', {
+    ttlSecs: ttlSecs ?? undefined,
+  });
+  return JSON.parse(response) as OperationResponse;
+}
+
+/**
+ * Sets how reads and updates by ID are retried when AnkiDroid fails transiently,
+ * e.g. while it is starting up. Inserts and deletes are never retried.
+ * @param policy Policy to apply, or null to restore the default
+ * @returns Operation result
+ */
+export async function setRetryPolicy(policy: RetryPolicy | null): Promise<OperationResponse> {
+  const response = await invoke<string>('plugin:ankidroid|set_retry_policy', {
+    policy: policy ?? undefined,
+  });
+  return JSON.parse(response) as OperationResponse;
+}
+
+/**
+ * Checks whether a rejected command failed with a plugin error
+ * @param error Value caught from a command
+ * @returns True if the error carries a code to branch on
+ */
+export function isPluginError(error: unknown): error is PluginError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as PluginError).code === 'string' &&
+    typeof (error as PluginError).message === 'string'
+  );
+}
+
 /**
  * Helper function to parse card list response
  * @param cardsJson JSON string from listCards